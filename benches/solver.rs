@@ -0,0 +1,37 @@
+// solver benchmark with criterion --------------------------------------------
+//
+// The boards and searches of the `bench` subcommand, for statistics over many
+// runs. Run with
+//
+//     cargo bench --bench solver
+
+use asteroids_cli_game::backtracking::{SearchLimits, Strategy, Verbosity};
+use asteroids_cli_game::bench::{BENCH_BOARDS, BENCH_SEARCHES};
+use asteroids_cli_game::notation::parse_board;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+fn bench_searches(c: &mut Criterion) {
+    let mut group = c.benchmark_group("solve");
+    // the hard board takes seconds per search
+    group.sample_size(10);
+    for notation in BENCH_BOARDS {
+        let board = parse_board(notation).unwrap();
+        for search in BENCH_SEARCHES {
+            let strategy = Strategy::from_name(search).unwrap();
+            group.bench_with_input(BenchmarkId::new(search, notation), &board, |b, board| {
+                b.iter(|| {
+                    strategy.solve_within(
+                        board,
+                        None,
+                        &SearchLimits::default(),
+                        &mut Verbosity::Quiet,
+                    )
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_searches);
+criterion_main!(benches);
@@ -0,0 +1,23 @@
+// Writes the C header of the `ffi` feature, see `src/ffi.rs`, to `OUT_DIR`;
+// a test of the feature checks that `include/asteroids.h` is the same.
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    #[cfg(feature = "ffi")]
+    write_ffi_header();
+}
+
+#[cfg(feature = "ffi")]
+fn write_ffi_header() {
+    // cbindgen reads nothing but this file
+    println!("cargo:rerun-if-changed=src/ffi.rs");
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let out_dir = std::env::var("OUT_DIR").unwrap();
+    cbindgen::Builder::new()
+        .with_src(format!("{}/src/ffi.rs", crate_dir))
+        .with_language(cbindgen::Language::C)
+        .with_include_guard("ASTEROIDS_H")
+        .with_header("/* Generated from src/ffi.rs by build.rs; do not edit. */")
+        .generate()
+        .expect("unable to generate the C header")
+        .write_to_file(format!("{}/asteroids.h", out_dir));
+}
@@ -0,0 +1,87 @@
+/* Generated from src/ffi.rs by build.rs; do not edit. */
+
+#ifndef ASTEROIDS_H
+#define ASTEROIDS_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * An argument is not valid.
+ */
+#define ASTEROIDS_INVALID -1
+
+/**
+ * The board cannot be won.
+ */
+#define ASTEROIDS_UNSOLVABLE -2
+
+/**
+ * The shapes of a board row by row, starting top left, each by its position
+ * in the list of shapes of the notation, i.e. 0 for the free space and 1 for
+ * the ship.
+ */
+typedef struct AsteroidsBoard {
+  uint8_t shapes[9];
+} AsteroidsBoard;
+
+/**
+ * Set up `out` from a board in notation. Returns 0, or `ASTEROIDS_INVALID`
+ * if the notation is not a valid board.
+ *
+ * # Safety
+ *
+ * `notation` must be null or a NUL-terminated string, and `out` null or
+ * point to a board.
+ */
+int32_t asteroids_board_from_notation(const char *notation, struct AsteroidsBoard *out);
+
+/**
+ * Write the notation of the board with a terminating NUL into the buffer if
+ * it fits, like `snprintf`. Returns the length of the notation without the
+ * NUL, or `ASTEROIDS_INVALID`.
+ *
+ * # Safety
+ *
+ * `board` must be null or point to a board, and `buffer` null or point to
+ * `size` writable bytes.
+ */
+int32_t asteroids_board_to_notation(const struct AsteroidsBoard *board,
+                                    char *buffer,
+                                    uintptr_t size);
+
+/**
+ * Move the free space of the board in the direction `U`, `D`, `L` or `R`.
+ * Returns 0, 1 if the move is not possible, which leaves the board as it
+ * is, or `ASTEROIDS_INVALID`.
+ *
+ * # Safety
+ *
+ * `board` must be null or point to a board.
+ */
+int32_t asteroids_apply_move(struct AsteroidsBoard *board, char direction);
+
+/**
+ * 1 if the ship can leave the board, 0 if not, or `ASTEROIDS_INVALID`.
+ *
+ * # Safety
+ *
+ * `board` must be null or point to a board.
+ */
+int32_t asteroids_is_won(const struct AsteroidsBoard *board);
+
+/**
+ * Write a shortest solution like `LLU` with a terminating NUL into the
+ * buffer if it fits. Returns its number of moves, `ASTEROIDS_UNSOLVABLE` or
+ * `ASTEROIDS_INVALID`.
+ *
+ * # Safety
+ *
+ * `board` must be null or point to a board, and `buffer` null or point to
+ * `size` writable bytes.
+ */
+int32_t asteroids_solve(const struct AsteroidsBoard *board, char *buffer, uintptr_t size);
+
+#endif /* ASTEROIDS_H */
@@ -0,0 +1,1058 @@
+// analysis of boards ---------------------------------------------------------
+
+use crate::backtracking::{
+    distance_map, moves_between, BreadthFirstQueue, SearchLimits, State, Strategy,
+};
+use crate::notation::board_to_notation;
+use crate::visited::VisitedSet;
+use crate::{Board, BoardIndex2d, Direction, MovingTile, Shape};
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io;
+use std::ops::RangeInclusive;
+
+pub const DIRECTIONS: [Direction; 4] = [
+    Direction::Up,
+    Direction::Down,
+    Direction::Left,
+    Direction::Right,
+];
+
+/// The legal moves on the board and the boards they lead to.
+pub fn successors(board: &Board) -> Vec<(Direction, Board)> {
+    DIRECTIONS
+        .iter()
+        .filter_map(|direction| {
+            board
+                .move_free_space(direction)
+                .ok()
+                .map(|new_board| (*direction, new_board))
+        })
+        .collect()
+}
+
+/// What a breadth-first search over all positions reachable from a board found.
+pub struct Analysis {
+    /// number of moves of the shortest solutions, if there is a solution
+    pub optimal_moves: Option<usize>,
+    /// number of different shortest solutions
+    pub optimal_lines: u64,
+    pub reachable_positions: usize,
+    /// average number of legal moves per reachable position
+    pub branching_factor: f64,
+    /// number of reachable positions with no legal move, one, and so on up to
+    /// four, one per direction
+    pub legal_moves_histogram: [usize; 5],
+}
+
+impl Analysis {
+    /// Difficulty rating: long solutions in positions with many options are
+    /// hard, while every additional optimal line makes it easier to find one.
+    /// `None` if there is no solution.
+    pub fn difficulty(&self) -> Option<f64> {
+        let moves = self.optimal_moves? as f64;
+        let score = moves * self.branching_factor / (1.0 + (self.optimal_lines as f64).log2());
+        Some((score * 10.0).round() / 10.0)
+    }
+
+    /// The class of the `difficulty` score, `None` if there is no solution.
+    pub fn difficulty_class(&self) -> Option<Difficulty> {
+        let score = self.difficulty()?;
+        Some(match score {
+            score if score < 15.0 => Difficulty::Easy,
+            score if score < 30.0 => Difficulty::Medium,
+            score if score < 50.0 => Difficulty::Hard,
+            _ => Difficulty::Expert,
+        })
+    }
+}
+
+/// Classes of the difficulty score of `Analysis`, e.g. for the generator to
+/// aim for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+    Expert,
+}
+
+impl Difficulty {
+    pub fn from_name(name: &str) -> Option<Difficulty> {
+        match name.to_lowercase().as_str() {
+            "easy" => Some(Difficulty::Easy),
+            "medium" => Some(Difficulty::Medium),
+            "hard" => Some(Difficulty::Hard),
+            "expert" => Some(Difficulty::Expert),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+            Difficulty::Expert => "expert",
+        }
+    }
+
+    /// Lengths of the shortest solution that puzzles of the difficulty
+    /// usually have; several shortest solutions make a puzzle easier.
+    pub fn typical_moves(self) -> RangeInclusive<usize> {
+        match self {
+            Difficulty::Easy => 2..=6,
+            Difficulty::Medium => 7..=13,
+            Difficulty::Hard => 14..=23,
+            Difficulty::Expert => 24..=40,
+        }
+    }
+}
+
+/// Search all positions reachable from the board, counting the shortest
+/// paths to each of them.
+pub fn analyze(board: &Board) -> Analysis {
+    // distance from the start and number of shortest paths, for every position
+    let mut visited: HashMap<Board, (usize, u64)> = HashMap::from([(*board, (0, 1))]);
+    let mut queue = VecDeque::from([*board]);
+    let mut optimal_moves = None;
+    let mut optimal_lines = 0;
+    let mut moves = 0;
+    let mut legal_moves_histogram = [0; 5];
+    while let Some(current) = queue.pop_front() {
+        let (distance, paths) = visited[&current];
+        if current.is_won() && optimal_moves.unwrap_or(distance) == distance {
+            optimal_moves = Some(distance);
+            optimal_lines += paths;
+        }
+        let next_boards = successors(&current);
+        moves += next_boards.len();
+        legal_moves_histogram[next_boards.len()] += 1;
+        for (_, next) in next_boards {
+            match visited.get_mut(&next) {
+                Some((next_distance, next_paths)) => {
+                    if *next_distance == distance + 1 {
+                        *next_paths += paths;
+                    }
+                }
+                None => {
+                    visited.insert(next, (distance + 1, paths));
+                    queue.push_back(next);
+                }
+            }
+        }
+    }
+    Analysis {
+        optimal_moves,
+        optimal_lines,
+        reachable_positions: visited.len(),
+        branching_factor: moves as f64 / visited.len() as f64,
+        legal_moves_histogram,
+    }
+}
+
+/// How many positions can be reached from a board.
+#[derive(Debug, PartialEq)]
+pub struct Reachable {
+    /// including the board itself
+    pub positions: usize,
+    /// positions from which the ship can leave
+    pub won: usize,
+}
+
+/// Count the positions reachable from the board, visiting each one once.
+/// `visited` is an empty set for the positions seen, see `visited`; the
+/// positions waiting to be visited are kept in its `frontier`, so a set in a
+/// file keeps neither in memory.
+pub fn count_reachable(board: &Board, visited: &mut dyn VisitedSet) -> io::Result<Reachable> {
+    let mut stack = visited.frontier()?;
+    visited.insert(*board)?;
+    stack.push(*board)?;
+    let mut won = 0;
+    while let Some(current) = stack.pop()? {
+        if current.is_won() {
+            won += 1;
+        }
+        for (_, next) in successors(&current) {
+            if visited.insert(next)? {
+                stack.push(next)?;
+            }
+        }
+    }
+    Ok(Reachable {
+        positions: visited.len(),
+        won,
+    })
+}
+
+/// Size and shape of the graph of all positions reachable from a board, with
+/// the moves between them as edges.
+pub struct GraphStats {
+    pub nodes: usize,
+    /// every move can be undone, so a move and its reverse are one edge
+    pub edges: usize,
+    /// longest distance between any two positions
+    pub diameter: usize,
+    /// number of positions at each distance from the start
+    pub distance_histogram: Vec<usize>,
+    pub won_states: usize,
+}
+
+/// Collect the statistics of the graph of positions reachable from the board.
+/// The diameter takes breadth-first searches from many positions, so this is
+/// slower than `analyze`.
+pub fn graph_stats(board: &Board) -> GraphStats {
+    // the positions by index, in the order of their distance from the start,
+    // and the indices of their neighbors
+    let mut positions = vec![*board];
+    let mut indices = HashMap::from([(*board, 0)]);
+    let mut neighbors: Vec<Vec<usize>> = vec![];
+    let mut distance_histogram = vec![];
+    let mut distances = vec![0];
+    let mut current = 0;
+    while current < positions.len() {
+        let distance = distances[current];
+        if distance_histogram.len() <= distance {
+            distance_histogram.push(0);
+        }
+        distance_histogram[distance] += 1;
+        let mut current_neighbors = vec![];
+        for (_, next) in successors(&positions[current]) {
+            let index = *indices.entry(next).or_insert_with(|| {
+                positions.push(next);
+                distances.push(distance + 1);
+                positions.len() - 1
+            });
+            current_neighbors.push(index);
+        }
+        neighbors.push(current_neighbors);
+        current += 1;
+    }
+
+    GraphStats {
+        nodes: positions.len(),
+        edges: neighbors.iter().map(Vec::len).sum::<usize>() / 2,
+        diameter: Graph::new(&neighbors).diameter(),
+        distance_histogram,
+        won_states: positions
+            .iter()
+            .filter(|position| position.is_won())
+            .count(),
+    }
+}
+
+/// A graph of positions by index, for many breadth-first searches through
+/// it: the neighbors of all positions in one vector, which is quicker to go
+/// through than a vector for each.
+struct Graph {
+    /// where the neighbors of each position start in `neighbors`, and where
+    /// the last ones end
+    offsets: Vec<u32>,
+    neighbors: Vec<u32>,
+}
+
+impl Graph {
+    fn new(neighbors: &[Vec<usize>]) -> Graph {
+        let mut offsets = vec![0];
+        for position_neighbors in neighbors {
+            offsets.push(offsets[offsets.len() - 1] + position_neighbors.len() as u32);
+        }
+        Graph {
+            offsets,
+            neighbors: neighbors
+                .iter()
+                .flatten()
+                .map(|&next| next as u32)
+                .collect(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    fn neighbors(&self, position: usize) -> &[u32] {
+        &self.neighbors[self.offsets[position] as usize..self.offsets[position + 1] as usize]
+    }
+
+    /// Fill `distances` with the distances from `start` to every position of
+    /// the graph, which is connected, and return how far the farthest is.
+    /// `queue` is room for the positions in the order of their distance.
+    fn distances_from(&self, start: usize, distances: &mut [u32], queue: &mut Vec<u32>) -> u32 {
+        distances.fill(u32::MAX);
+        distances[start] = 0;
+        queue.clear();
+        queue.push(start as u32);
+        let mut current = 0;
+        while current < queue.len() {
+            let position = queue[current] as usize;
+            for &next in self.neighbors(position) {
+                if distances[next as usize] == u32::MAX {
+                    distances[next as usize] = distances[position] + 1;
+                    queue.push(next);
+                }
+            }
+            current += 1;
+        }
+        distances[queue[queue.len() - 1] as usize]
+    }
+
+    /// The longest distance between two positions, by the BoundingDiameters
+    /// algorithm of Takes and Kosters: each search from a position bounds how
+    /// far every other position can be from the rest, and positions that
+    /// cannot be farther than the longest distance found so far need no search
+    /// of their own.
+    fn diameter(&self) -> usize {
+        let mut distances = vec![u32::MAX; self.len()];
+        let mut queue = Vec::with_capacity(self.len());
+        // bounds of the longest distance from each position to any other
+        let mut lower = vec![0; self.len()];
+        let mut upper = vec![u32::MAX; self.len()];
+        let mut candidates: Vec<usize> = (0..self.len()).collect();
+        let mut diameter = 0;
+        let mut highest_upper = true;
+        while !candidates.is_empty() {
+            // alternately the position that may be farthest from the rest and
+            // the one that may be closest to them, which bound the others best
+            let &position = if highest_upper {
+                candidates
+                    .iter()
+                    .max_by_key(|&&position| (upper[position], self.neighbors(position).len()))
+            } else {
+                candidates.iter().min_by_key(|&&position| {
+                    (lower[position], Reverse(self.neighbors(position).len()))
+                })
+            }
+            .unwrap();
+            highest_upper = !highest_upper;
+            let eccentricity = self.distances_from(position, &mut distances, &mut queue);
+            for &other in &candidates {
+                let distance = distances[other];
+                lower[other] = lower[other].max(distance.max(eccentricity - distance));
+                upper[other] = upper[other].min(eccentricity + distance);
+                diameter = diameter.max(lower[other]);
+            }
+            candidates.retain(|&other| upper[other] > diameter);
+        }
+        diameter as usize
+    }
+}
+
+/// The hardest puzzles that can be set up with some shapes.
+pub struct GodsNumber {
+    /// valid arrangements of the shapes, solvable or not
+    pub boards: usize,
+    /// arrangements that are solvable but not won yet
+    pub solvable: usize,
+    /// a board with the longest shortest solution, and its length; `None` if
+    /// no arrangement is solvable
+    pub hardest: Option<(Board, usize)>,
+}
+
+/// Every valid arrangement of the shapes into the free cells from `index` on,
+/// in their given orientation, built up cell by cell and dropped as soon as
+/// two tiles overlap. `shapes` are the shapes left to place, sorted.
+fn arrangements(
+    board: Board,
+    index: usize,
+    shapes: &mut Vec<Shape>,
+    found: &mut impl FnMut(Board),
+) {
+    if index == 9 {
+        found(board);
+        return;
+    }
+    if board.shapes[index] != Shape::Free {
+        arrangements(board, index + 1, shapes, found);
+        return;
+    }
+    for position in 0..shapes.len() {
+        // the same shape in another position leads to the same boards
+        if position > 0 && shapes[position] == shapes[position - 1] {
+            continue;
+        }
+        let shape = shapes.remove(position);
+        let next = board.set_shape(&BoardIndex2d::from_index(index), &shape);
+        if next.is_collission_free(&MovingTile::no_move()) {
+            arrangements(next, index + 1, shapes, found);
+        }
+        shapes.insert(position, shape);
+    }
+}
+
+/// Every won position with the shapes of the board, whether it can be reached
+/// from the board or not.
+pub fn won_positions(board: &Board) -> Vec<Board> {
+    let in_front_of_exit = BoardIndex2d { x: 1, y: 2 };
+    let mut shapes: Vec<Shape> = board
+        .shapes
+        .iter()
+        .copied()
+        .filter(|&shape| shape != Shape::Ship)
+        .collect();
+    shapes.sort_by_key(|&shape| shape as u8);
+    let mut won = vec![];
+    let start = Board::empty_board().set_shape(&in_front_of_exit, &Shape::Ship);
+    arrangements(start, 0, &mut shapes, &mut |position| {
+        if position.is_won() {
+            won.push(position);
+        }
+    });
+    won
+}
+
+/// Arrange the shapes in all possible ways and find the longest shortest
+/// solution among them, known as God's number. Moves keep the shapes, so the
+/// arrangements fall apart into groups of positions that can be reached from
+/// each other, and each group is searched only once. This takes a while.
+/// `seen` is an empty set for the arrangements seen, see `visited`.
+pub fn gods_number(shapes: &[Shape], seen: &mut dyn VisitedSet) -> Result<GodsNumber, String> {
+    let count = |wanted: Shape| shapes.iter().filter(|&&shape| shape == wanted).count();
+    if shapes.len() != 9 || count(Shape::Ship) != 1 || count(Shape::Free) != 1 {
+        return Err("expected 9 shapes, one of them the ship and one free".to_string());
+    }
+    let mut shapes = shapes.to_vec();
+    shapes.sort_by_key(|&shape| shape as u8);
+
+    let mut result = GodsNumber {
+        boards: 0,
+        solvable: 0,
+        hardest: None,
+    };
+    // the first error of `seen`, after which the rest is skipped
+    let mut error = None;
+    arrangements(Board::empty_board(), 0, &mut shapes, &mut |board| {
+        if error.is_some() {
+            return;
+        }
+        let group = match unseen_group(board, seen) {
+            Ok(group) if !group.is_empty() => group,
+            Ok(_) => return,
+            Err(err) => {
+                error = Some(err);
+                return;
+            }
+        };
+        // the distances of the positions to the won ones among them
+        let mut distances: HashMap<Board, usize> = group
+            .iter()
+            .filter(|position| position.is_won())
+            .map(|&won| (won, 0))
+            .collect();
+        let mut queue: VecDeque<Board> = distances.keys().copied().collect();
+        while let Some(current) = queue.pop_front() {
+            let distance = distances[&current];
+            for (_, next) in successors(&current) {
+                distances.entry(next).or_insert_with(|| {
+                    queue.push_back(next);
+                    distance + 1
+                });
+            }
+        }
+
+        result.boards += group.len();
+        result.solvable += distances.values().filter(|&&distance| distance > 0).count();
+        // the same example for every run, whatever the order of the hash map
+        let hardest = distances
+            .into_iter()
+            .max_by_key(|&(position, distance)| (distance, Reverse(position.stable_hash())));
+        if let Some((position, distance)) = hardest {
+            let better = match result.hardest {
+                Some((known, known_distance)) => {
+                    (distance, Reverse(position.stable_hash()))
+                        > (known_distance, Reverse(known.stable_hash()))
+                }
+                None => distance > 0,
+            };
+            if better {
+                result.hardest = Some((position, distance));
+            }
+        }
+    });
+    match error {
+        Some(err) => Err(err.to_string()),
+        None => Ok(result),
+    }
+}
+
+/// All positions reachable from the board, which are added to `seen`; none
+/// if the board was seen already.
+fn unseen_group(board: Board, seen: &mut dyn VisitedSet) -> io::Result<Vec<Board>> {
+    if !seen.insert(board)? {
+        return Ok(vec![]);
+    }
+    let mut group = vec![board];
+    let mut current = 0;
+    while current < group.len() {
+        for (_, next) in successors(&group[current]) {
+            if seen.insert(next)? {
+                group.push(next);
+            }
+        }
+        current += 1;
+    }
+    Ok(group)
+}
+
+/// What one step of a search did.
+pub enum SearchProgress {
+    /// looked at the moves from this position
+    Expanded(Board),
+    /// found a shortest solution
+    Solved(Vec<Direction>),
+    /// there is no solution
+    Exhausted,
+}
+
+/// Breadth-first search for a shortest solution, which can be run one
+/// expansion at a time. It keeps the positions the way `Strategy::BreadthFirst`
+/// does, so both expand the same positions in the same order.
+pub struct BreadthFirstSearch {
+    /// positions seen, how they were reached and those not expanded yet
+    queue: BreadthFirstQueue<Board>,
+    expanded: usize,
+    /// expanded positions none of whose moves lead to a position not seen
+    /// before, in the order they were expanded
+    dead_ends: Vec<Board>,
+}
+
+impl BreadthFirstSearch {
+    pub fn new(board: &Board) -> BreadthFirstSearch {
+        BreadthFirstSearch {
+            queue: BreadthFirstQueue::new(board),
+            expanded: 0,
+            dead_ends: vec![],
+        }
+    }
+
+    /// Expand the next position, unless it is won.
+    pub fn step(&mut self) -> SearchProgress {
+        self.expand(0)
+    }
+
+    /// Expand the position with the given index in the frontier out of turn,
+    /// e.g. to explore the search by hand. Solutions found this way are not
+    /// necessarily shortest ones.
+    pub fn expand_frontier(&mut self, index: usize) -> SearchProgress {
+        self.expand(index)
+    }
+
+    fn expand(&mut self, position: usize) -> SearchProgress {
+        let Some((index, steps)) = self.queue.take(position) else {
+            return SearchProgress::Exhausted;
+        };
+        let current = *self.queue.state(index);
+        if current.is_won() {
+            self.queue.clear();
+            return SearchProgress::Solved(self.path_to(&current));
+        }
+        self.expanded += 1;
+        if self
+            .queue
+            .push_successors(index, steps, current.get_possible_successors())
+            == 0
+        {
+            self.dead_ends.push(current);
+        }
+        SearchProgress::Expanded(current)
+    }
+
+    /// The moves leading to a position that the search has seen.
+    pub fn path_to(&self, board: &Board) -> Vec<Direction> {
+        let index = self
+            .queue
+            .index_of(board)
+            .expect("a position the search has seen");
+        moves_between(&self.queue.sequence_to(index))
+    }
+
+    /// Number of positions expanded so far.
+    pub fn expanded(&self) -> usize {
+        self.expanded
+    }
+
+    /// Number of positions waiting to be expanded.
+    pub fn frontier(&self) -> usize {
+        self.queue.waiting()
+    }
+
+    /// The position with the given index among those waiting to be expanded,
+    /// which are expanded in the order of their indices.
+    pub fn frontier_position(&self, index: usize) -> Option<Board> {
+        self.queue.waiting_state(index).copied()
+    }
+
+    /// Expanded positions that added nothing to the frontier, as all their
+    /// moves lead to positions seen before.
+    pub fn dead_ends(&self) -> &[Board] {
+        &self.dead_ends
+    }
+}
+
+/// A shortest solution, found by `Strategy::BreadthFirst` without limits;
+/// `None` if there is none.
+pub fn solve(board: &Board) -> Option<Vec<Direction>> {
+    Strategy::BreadthFirst
+        .solve_within(board, None, &SearchLimits::default(), &mut ())
+        .0
+        .found()
+}
+
+/// The number of moves of the shortest solutions and how many different ones
+/// there are, e.g. to check that a puzzle has a unique solution; `None` if
+/// there is none. Unlike `analyze`, only the positions up to that many moves
+/// away are searched.
+pub fn count_optimal_solutions(board: &Board) -> Option<(usize, u64)> {
+    let mut seen = HashSet::from([*board]);
+    // the positions at the current distance and the number of shortest paths
+    // to each of them
+    let mut layer = HashMap::from([(*board, 1)]);
+    for distance in 0.. {
+        let won: u64 = layer
+            .iter()
+            .filter(|(position, _)| position.is_won())
+            .map(|(_, paths)| paths)
+            .sum();
+        if won > 0 {
+            return Some((distance, won));
+        }
+        let mut next_layer: HashMap<Board, u64> = HashMap::new();
+        for (position, paths) in &layer {
+            for (_, next) in successors(position) {
+                if !seen.contains(&next) {
+                    *next_layer.entry(next).or_default() += paths;
+                }
+            }
+        }
+        if next_layer.is_empty() {
+            return None;
+        }
+        seen.extend(next_layer.keys());
+        layer = next_layer;
+    }
+    unreachable!("the distance keeps growing until the search ends")
+}
+
+/// The graph of the positions that a breadth-first search for a solution
+/// explores, in Graphviz DOT, e.g. to render it with `dot -Tsvg`: the expanded
+/// positions and the positions their moves lead to. Every move can be undone,
+/// so a move and its reverse are one edge, labeled with the move away from
+/// the start. The start is drawn bold, won positions with a double border and
+/// the solution found in red. Meant for small puzzles, as the graph of all
+/// others is too large to make out anything.
+pub fn explored_graph_dot(board: &Board) -> String {
+    let mut search = BreadthFirstSearch::new(board);
+    let mut expanded = vec![];
+    let solution = loop {
+        match search.step() {
+            SearchProgress::Expanded(position) => expanded.push(position),
+            SearchProgress::Solved(moves) => break moves,
+            SearchProgress::Exhausted => break vec![],
+        }
+    };
+    let mut on_solution = HashSet::from([*board]);
+    // the moves of the solution, both ways
+    let mut solution_moves = HashSet::new();
+    let mut position = *board;
+    for direction in &solution {
+        let next = position.move_free_space(direction).unwrap();
+        on_solution.insert(next);
+        solution_moves.extend([(position, next), (next, position)]);
+        position = next;
+    }
+    let was_expanded: HashSet<Board> = expanded.iter().copied().collect();
+
+    // the positions by index, in the order they were seen
+    let mut positions = vec![*board];
+    let mut indices = HashMap::from([(*board, 0)]);
+    let mut edges = vec![];
+    for position in &expanded {
+        let from = indices[position];
+        for (direction, next) in successors(position) {
+            let to = *indices.entry(next).or_insert_with(|| {
+                positions.push(next);
+                positions.len() - 1
+            });
+            // the reverse of a move between expanded positions
+            if to > from || !was_expanded.contains(&next) {
+                edges.push((from, to, direction));
+            }
+        }
+    }
+
+    let mut lines = vec![
+        "graph explored {".to_string(),
+        "    node [shape=box, fontname=\"monospace\"];".to_string(),
+    ];
+    for (index, position) in positions.iter().enumerate() {
+        let mut attributes = vec![format!("label=\"{}\"", board_to_notation(position))];
+        if index == 0 {
+            attributes.push("style=bold".to_string());
+        }
+        if position.is_won() {
+            attributes.push("peripheries=2".to_string());
+        }
+        if on_solution.contains(position) {
+            attributes.push("color=red".to_string());
+        }
+        lines.push(format!("    {} [{}];", index, attributes.join(", ")));
+    }
+    for (from, to, direction) in edges {
+        let on_path = solution_moves.contains(&(positions[from], positions[to]));
+        lines.push(format!(
+            "    {} -- {} [label=\"{}\"{}];",
+            from,
+            to,
+            direction.to_char(),
+            if on_path {
+                ", color=red, penwidth=3"
+            } else {
+                ""
+            }
+        ));
+    }
+    lines.push("}".to_string());
+    lines.join("\n")
+}
+
+/// The moves that lead from one board of the history to the next, and whether
+/// each of them was optimal, i.e. got one move closer to the exit.
+/// Optimality is unknown (`None`) where the exit cannot be reached at all.
+pub fn review_moves(history: &[Board]) -> Vec<(Direction, Option<bool>)> {
+    let distances = history.first().map(distance_map).unwrap_or_default();
+    history
+        .windows(2)
+        .map(|pair| {
+            let direction = pair[0]
+                .find_free_space()
+                .direction_to(&pair[1].find_free_space())
+                .expect("consecutive boards of a history differ by one move");
+            let optimal = match (distances.get(&pair[0]), distances.get(&pair[1])) {
+                (Some(before), Some(after)) => Some(after + 1 == *before),
+                _ => None,
+            };
+            (direction, optimal)
+        })
+        .collect()
+}
+
+/// How a game compares to a shortest solution.
+#[derive(Debug, PartialEq)]
+pub struct GameReview {
+    /// length of a shortest solution from the start, if there is one
+    pub optimal_moves: Option<usize>,
+    /// the first move that did not get closer to the exit: its index, the
+    /// move played and a move that would have
+    pub first_deviation: Option<(usize, Direction, Direction)>,
+}
+
+/// Compare the history of a game to the shortest solutions from its start.
+pub fn review_game(history: &[Board]) -> GameReview {
+    let distances = history.first().map(distance_map).unwrap_or_default();
+    let first_deviation = history.windows(2).enumerate().find_map(|(index, pair)| {
+        let before = *distances.get(&pair[0])?;
+        if distances
+            .get(&pair[1])
+            .is_some_and(|&after| after + 1 == before)
+        {
+            return None;
+        }
+        let played = pair[0]
+            .find_free_space()
+            .direction_to(&pair[1].find_free_space())?;
+        let better = successors(&pair[0])
+            .into_iter()
+            .find(|(_, next)| {
+                distances
+                    .get(next)
+                    .is_some_and(|&after| after + 1 == before)
+            })
+            .map(|(direction, _)| direction)?;
+        Some((index, played, better))
+    });
+    GameReview {
+        optimal_moves: history
+            .first()
+            .and_then(|start| distances.get(start))
+            .map(|&moves| moves as usize),
+        first_deviation,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backtracking::ReversibleState;
+    use crate::generator::drill;
+    use crate::notation::parse_board;
+    use crate::sample_board;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_analyze() {
+        let analysis = analyze(&sample_board());
+        assert_eq!(analysis.optimal_moves, Some(2));
+        assert_eq!(analysis.optimal_lines, 1);
+        assert!(analysis.reachable_positions > 2);
+        assert!(analysis.branching_factor >= 1.0);
+        assert_eq!(
+            analysis.legal_moves_histogram.iter().sum::<usize>(),
+            analysis.reachable_positions
+        );
+        // on this board, some move is legal in every position
+        assert_eq!(analysis.legal_moves_histogram[0], 0);
+        assert!(analysis.difficulty().unwrap() > 0.0);
+        assert_eq!(analysis.difficulty_class(), Some(Difficulty::Easy));
+        assert_eq!(Difficulty::from_name("Expert"), Some(Difficulty::Expert));
+        assert_eq!(
+            Difficulty::from_name("hard").map(Difficulty::name),
+            Some("hard")
+        );
+        assert_eq!(Difficulty::from_name("impossible"), None);
+        assert_eq!(
+            solve(&sample_board()),
+            Some(vec![Direction::Left, Direction::Left])
+        );
+    }
+
+    #[test]
+    fn test_count_optimal_solutions() {
+        assert_eq!(count_optimal_solutions(&sample_board()), Some((2, 1)));
+        let mut rng = StdRng::seed_from_u64(5);
+        let mut unique = 0;
+        for moves in [3, 6, 9, 12] {
+            let puzzle = drill(&won_positions(&sample_board())[0], moves, &mut rng).unwrap();
+            let analysis = analyze(&puzzle);
+            assert_eq!(
+                count_optimal_solutions(&puzzle),
+                Some((moves, analysis.optimal_lines))
+            );
+            if analysis.optimal_lines == 1 {
+                unique += 1;
+            }
+        }
+        // some of them with a unique solution, some not
+        assert_eq!(unique, 2);
+    }
+
+    #[test]
+    fn test_graph_stats() {
+        let stats = graph_stats(&sample_board());
+        assert_eq!(stats.nodes, analyze(&sample_board()).reachable_positions);
+        assert_eq!(stats.distance_histogram.iter().sum::<usize>(), stats.nodes);
+        assert!(stats.diameter >= stats.distance_histogram.len() - 1);
+        assert!(stats.won_states > 0);
+        assert_eq!(
+            count_reachable(&sample_board(), &mut HashSet::new()).unwrap(),
+            Reachable {
+                positions: stats.nodes,
+                won: stats.won_states,
+            }
+        );
+    }
+
+    #[test]
+    fn test_diameter() {
+        // undirected graphs by their edges
+        let graph = |count: usize, edges: &[(usize, usize)]| {
+            let mut neighbors = vec![vec![]; count];
+            for &(from, to) in edges {
+                neighbors[from].push(to);
+                neighbors[to].push(from);
+            }
+            Graph::new(&neighbors)
+        };
+        let path = graph(5, &[(0, 1), (1, 2), (2, 3), (3, 4)]);
+        let cycle = graph(7, &[(0, 1), (1, 2), (2, 3), (3, 4), (4, 5), (5, 6), (6, 0)]);
+        // legs of one, two and three edges
+        let spider = graph(7, &[(0, 1), (0, 2), (2, 3), (0, 4), (4, 5), (5, 6)]);
+        let complete = graph(4, &[(0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)]);
+        let single = graph(1, &[]);
+        for (graph, diameter) in [
+            (path, 4),
+            (cycle, 3),
+            (spider, 5),
+            (complete, 1),
+            (single, 0),
+        ] {
+            assert_eq!(graph.diameter(), diameter);
+            let mut distances = vec![0; graph.len()];
+            let mut queue = vec![];
+            let longest = (0..graph.len())
+                .map(|start| graph.distances_from(start, &mut distances, &mut queue))
+                .max();
+            assert_eq!(longest, Some(diameter as u32));
+        }
+    }
+
+    #[test]
+    fn test_predecessors() {
+        let board = sample_board();
+        for previous in board.get_possible_predecessors() {
+            assert!(successors(&previous).iter().any(|&(_, next)| next == board));
+        }
+        let won = won_positions(&board);
+        assert!(won.iter().all(Board::is_won));
+        assert!(won.len() >= count_reachable(&board, &mut HashSet::new()).unwrap().won);
+    }
+
+    #[test]
+    fn test_gods_number() {
+        let result = gods_number(&sample_board().shapes, &mut HashSet::new()).unwrap();
+        let (hardest, moves) = result.hardest.unwrap();
+        assert_eq!(solve(&hardest).unwrap().len(), moves);
+        assert!(moves >= 2);
+        assert!(
+            result.boards
+                >= count_reachable(&sample_board(), &mut HashSet::new())
+                    .unwrap()
+                    .positions
+        );
+        assert!(result.solvable < result.boards);
+        assert!(gods_number(&sample_board().shapes[1..], &mut HashSet::new()).is_err());
+    }
+
+    #[test]
+    fn test_breadth_first_search() {
+        let mut search = BreadthFirstSearch::new(&sample_board());
+        let SearchProgress::Expanded(first) = search.step() else {
+            panic!("the sample board is not won");
+        };
+        assert_eq!(first, sample_board());
+        assert_eq!(search.expanded(), 1);
+        assert_eq!(search.frontier(), successors(&sample_board()).len());
+        let SearchProgress::Expanded(second) = search.step() else {
+            panic!("no solution after one move");
+        };
+        assert_eq!(search.path_to(&second).len(), 1);
+
+        // out of turn
+        let mut search = BreadthFirstSearch::new(&sample_board());
+        search.step();
+        let last = search.frontier() - 1;
+        let position = search.frontier_position(last).unwrap();
+        let SearchProgress::Expanded(expanded) = search.expand_frontier(last) else {
+            panic!("no solution after one move");
+        };
+        assert_eq!(expanded, position);
+        let mut search =
+            BreadthFirstSearch::new(&parse_board("1tl,s,1tr,1bl,f,1br,2t,2b,2t").unwrap());
+        for _ in 0..500 {
+            search.step();
+        }
+        assert!(!search.dead_ends().is_empty());
+        for dead_end in search.dead_ends() {
+            assert!(successors(dead_end)
+                .iter()
+                .all(|(_, next)| search.queue.index_of(next).is_some()));
+        }
+    }
+
+    #[test]
+    fn test_explored_graph_dot() {
+        let dot = explored_graph_dot(&sample_board());
+        let lines: Vec<&str> = dot.lines().collect();
+        assert_eq!(lines[0], "graph explored {");
+        assert_eq!(
+            lines[2],
+            "    0 [label=\"1tl,2dd,2t,1tl,ctl,1tl,s,er,f\", style=bold, color=red];"
+        );
+        assert_eq!(lines.iter().filter(|line| line.contains(" -- ")).count(), 2);
+        assert_eq!(lines[6], "    1 -- 2 [label=\"L\", color=red, penwidth=3];");
+        assert_eq!(lines.last(), Some(&"}"));
+
+        // each move between positions is drawn once
+        let board = parse_board("1tl,s,1tr,1bl,f,1br,2t,2b,2t").unwrap();
+        let dot = explored_graph_dot(&board);
+        let mut edges = HashSet::new();
+        for line in dot.lines().filter(|line| line.contains(" -- ")) {
+            let mut ends: Vec<&str> = line.split_whitespace().take(3).collect();
+            ends.remove(1);
+            ends.sort();
+            assert!(edges.insert(ends));
+        }
+        assert_eq!(
+            dot.matches("penwidth=3").count(),
+            solve(&board).unwrap().len()
+        );
+    }
+
+    #[test]
+    fn test_review_moves() {
+        let board = sample_board();
+        let mut history = vec![board];
+        // a detour: one step towards the exit, back, then straight out
+        for direction in [
+            Direction::Left,
+            Direction::Right,
+            Direction::Left,
+            Direction::Left,
+        ] {
+            history.push(history.last().unwrap().move_free_space(&direction).unwrap());
+        }
+        assert_eq!(distance_map(&board)[&board], 2);
+        assert_eq!(
+            review_game(&history),
+            GameReview {
+                optimal_moves: Some(2),
+                first_deviation: Some((1, Direction::Right, Direction::Left)),
+            }
+        );
+        assert_eq!(
+            review_game(&[history[0], history[1], history[4]]).first_deviation,
+            None
+        );
+        assert_eq!(
+            review_moves(&history),
+            vec![
+                (Direction::Left, Some(true)),
+                (Direction::Right, Some(false)),
+                (Direction::Left, Some(true)),
+                (Direction::Left, Some(true)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_analyze_unsolvable() {
+        use crate::Shape::*;
+        // only one tile can move, back and forth
+        let board = Board {
+            shapes: [
+                OneTL,
+                OneBR,
+                Ship,
+                LargeCornerTL,
+                LargeEdgeL,
+                TwoHorR,
+                TwoDiagDown,
+                LargeCornerTR,
+                Free,
+            ],
+        };
+        assert!(board.is_valid());
+        let analysis = analyze(&board);
+        assert_eq!(analysis.optimal_moves, None);
+        assert_eq!(analysis.reachable_positions, 2);
+        assert_eq!(analysis.legal_moves_histogram, [0, 2, 0, 0, 0]);
+        assert_eq!(count_optimal_solutions(&board), None);
+        assert_eq!(analysis.difficulty(), None);
+        assert_eq!(analysis.difficulty_class(), None);
+        assert_eq!(solve(&board), None);
+
+        let stats = graph_stats(&board);
+        assert_eq!(stats.nodes, 2);
+        assert_eq!(stats.edges, 1);
+        assert_eq!(stats.diameter, 1);
+        assert_eq!(stats.distance_histogram, vec![1, 1]);
+        assert_eq!(stats.won_states, 0);
+        assert_eq!(
+            count_reachable(&board, &mut HashSet::new()).unwrap(),
+            Reachable {
+                positions: 2,
+                won: 0
+            }
+        );
+    }
+}
@@ -1,238 +1,1904 @@
 // backpropagation library ----------------------------------------------------
+//
+// Searches for a sequence of states that leads to a final state. Each search
+// strategy has its strengths: depth-first search finds some sequence with
+// little memory, breadth-first search and A* find shortest ones, and iterative
+// deepening finds shortest ones with little memory.
 
-use itertools::Itertools;
-use std::{collections::HashSet, hash::Hash};
+use crate::analysis::{successors, won_positions, DIRECTIONS};
+use crate::patterns::PatternDatabase;
+use crate::{Board, BoardIndex2d, Direction, Shape};
+use indexmap::{IndexMap, IndexSet};
+use itertools::{Either, Itertools};
+use rustc_hash::{FxBuildHasher, FxHashSet};
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, VecDeque},
+    fmt,
+    hash::{BuildHasher, Hash},
+    path::Path,
+    sync::atomic::{AtomicBool, Ordering},
+    sync::Arc,
+    time::Duration,
+};
+// std's clock panics on wasm32-unknown-unknown, where this one asks the browser
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
 
-trait State: ToString + Eq + Hash {
+/// A state of a search. Searches tell states apart by their key, so states
+/// need neither be hashable nor printable; see `DescribeState` for printing
+/// them.
+pub trait State {
+    /// Identifies the state in the sets of states a search has seen. States
+    /// with the same key are evaluated only once, so they must lead to final
+    /// states in the same number of steps.
+    type Key: Eq + Hash;
+    fn key(&self) -> Self::Key;
     fn is_final(&self) -> bool;
-    fn get_possible_successors(&self) -> Vec<Box<Self>>;
+    /// The states one step on. They do not borrow this state, so that a
+    /// search can go through them while adding them to the states it has
+    /// seen.
+    fn get_possible_successors(&self) -> impl Iterator<Item = Self> + use<Self>;
 }
 
-struct Attempt<AState: State> {
-    state: AState,
-    successors_to_try: Vec<Box<AState>>,
+/// States whose steps can be retraced, so that a search can also start from
+/// the final states.
+pub trait ReversibleState: State + Sized {
+    /// The states that have this one among their successors.
+    fn get_possible_predecessors(&self) -> impl Iterator<Item = Self> + use<Self>;
+    /// Every final state that may be reachable from this one.
+    fn final_states(&self) -> Vec<Self>;
+}
+
+/// Estimate of the number of steps from a state to a final state, to search
+/// promising states first. Sequences found with an estimate that is never too
+/// high are shortest ones.
+pub trait Heuristic<AState: State> {
+    fn estimate(&self, state: &AState) -> usize;
 }
 
+/// Notified of what a search does, e.g. to show its progress or to log it.
+pub trait SearchObserver<AState> {
+    /// The successors of `state` are about to be evaluated.
+    fn on_expand(&mut self, _state: &AState) {}
+    /// The search goes back from `state` to try other successors before it.
+    fn on_backtrack(&mut self, _state: &AState) {}
+    /// `state` is known not to lead to a final state.
+    fn on_dead_end(&mut self, _state: &AState) {}
+    /// A search of sequences of up to `depth_limit` steps starts, for searches
+    /// that deepen step by step.
+    fn on_iteration(&mut self, _depth_limit: usize) {}
+}
+
+/// A state in the output of `Verbosity`.
+pub trait DescribeState {
+    fn describe(&self) -> String;
+}
+
+impl<AState: fmt::Display> DescribeState for AState {
+    fn describe(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// Ignores what a search does, for states that cannot be described.
+impl<AState> SearchObserver<AState> for () {}
+
+/// Prints what a search does, e.g. for debugging.
 #[derive(PartialEq)]
-enum Verbosity {
+#[allow(dead_code)] // the output of Info and Trace is for debugging only
+pub enum Verbosity {
     Quiet,
     Info,
     Trace,
 }
 
-fn get_sequence_to_final_state<AState: State + Clone>(
-    initial_state: &AState,
-    verbosity: &Verbosity,
-) -> Result<Vec<AState>, &'static str> {
-    // Things that we can currently try.
-    let mut attempts: Vec<Attempt<AState>> = vec![Attempt {
-        state: initial_state.clone(),
-        successors_to_try: initial_state.get_possible_successors(),
-    }];
-    // Things that we have tried and failed, so do not re-try.
-    let mut dead_ends: HashSet<AState> = HashSet::new();
-
-    loop {
-        if let Some(current_attempt) = attempts.last_mut() {
-            if let Some(successor) = current_attempt.successors_to_try.pop() {
-                // found a successor to try, so try it
-
-                if *verbosity == Verbosity::Trace {
-                    println!("Going to evaluate successors of {}.", successor.to_string());
+impl<AState: DescribeState> SearchObserver<AState> for Verbosity {
+    fn on_expand(&mut self, state: &AState) {
+        if *self == Verbosity::Trace {
+            println!("Going to evaluate successors of {}.", state.describe());
+        }
+    }
+    fn on_backtrack(&mut self, state: &AState) {
+        if *self != Verbosity::Quiet {
+            println!("Backtracking from {}", state.describe());
+        }
+    }
+    fn on_dead_end(&mut self, state: &AState) {
+        if *self == Verbosity::Trace {
+            println!("  {} is a dead end.", state.describe());
+        }
+    }
+    fn on_iteration(&mut self, depth_limit: usize) {
+        if *self != Verbosity::Quiet {
+            println!("Searching sequences of up to {} steps.", depth_limit);
+        }
+    }
+}
+
+/// How far a search may go before it gives up; `None` for no limit.
+#[derive(Clone, Debug, Default)]
+pub struct SearchLimits {
+    /// states whose successors are evaluated
+    pub max_nodes: Option<usize>,
+    /// steps of the sequences that are tried
+    pub max_depth: Option<usize>,
+    pub max_duration: Option<Duration>,
+    /// to stop the search from elsewhere, e.g. another thread
+    pub cancel: Option<CancellationToken>,
+}
+
+/// Stops a search that was given a clone of the token once `cancel` is called.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// The limit that made a search give up.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Limit {
+    Nodes,
+    Depth,
+    Duration,
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Limit::Nodes => write!(f, "too many states to search"),
+            Limit::Depth => write!(f, "sequences too long to search"),
+            Limit::Duration => write!(f, "search took too long"),
+        }
+    }
+}
+
+/// How a search ended.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SearchOutcome<T> {
+    /// the sequence from the initial state to a final state, both included
+    Found(T),
+    /// no final state can be reached
+    Exhausted,
+    /// the search gave up before it found a final state
+    Aborted(Limit),
+    /// the search was stopped with its cancellation token
+    Cancelled,
+}
+
+impl<T> SearchOutcome<T> {
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> SearchOutcome<U> {
+        match self {
+            SearchOutcome::Found(found) => SearchOutcome::Found(f(found)),
+            SearchOutcome::Exhausted => SearchOutcome::Exhausted,
+            SearchOutcome::Aborted(limit) => SearchOutcome::Aborted(limit),
+            SearchOutcome::Cancelled => SearchOutcome::Cancelled,
+        }
+    }
+
+    pub fn found(self) -> Option<T> {
+        match self {
+            SearchOutcome::Found(found) => Some(found),
+            _ => None,
+        }
+    }
+}
+
+/// Why a search stopped without finding a final state.
+pub enum Stop {
+    Exhausted,
+    Aborted(Limit),
+    Cancelled,
+}
+
+/// A search in progress: the limits it keeps, who observes it and what it
+/// took so far.
+pub struct SearchRun<'a, AState> {
+    limits: &'a SearchLimits,
+    observer: &'a mut dyn SearchObserver<AState>,
+    started: Instant,
+    pub stats: SearchStats,
+}
+
+impl<AState: State> SearchRun<'_, AState> {
+    /// The successors of `state`, counted as an expanded node, unless the
+    /// search has to give up.
+    fn expand(&mut self, state: &AState) -> Result<impl Iterator<Item = AState>, Stop> {
+        self.count_expansion(state)?;
+        Ok(state.get_possible_successors())
+    }
+
+    /// Count `state` as an expanded node, unless the search has to give up.
+    fn count_expansion(&mut self, state: &AState) -> Result<(), Stop> {
+        if self
+            .limits
+            .cancel
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+        {
+            return Err(Stop::Cancelled);
+        }
+        if self
+            .limits
+            .max_nodes
+            .is_some_and(|max_nodes| self.stats.nodes_expanded >= max_nodes)
+        {
+            return Err(Stop::Aborted(Limit::Nodes));
+        }
+        if self
+            .limits
+            .max_duration
+            .is_some_and(|max_duration| self.started.elapsed() >= max_duration)
+        {
+            return Err(Stop::Aborted(Limit::Duration));
+        }
+        self.stats.nodes_expanded += 1;
+        self.observer.on_expand(state);
+        Ok(())
+    }
+
+    /// Whether sequences of `depth` steps are too long to try.
+    fn too_deep(&self, depth: usize) -> bool {
+        self.limits
+            .max_depth
+            .is_some_and(|max_depth| depth > max_depth)
+    }
+
+    /// Note the number of states waiting to be evaluated.
+    fn frontier(&mut self, size: usize) {
+        self.stats.max_frontier = self.stats.max_frontier.max(size);
+    }
+}
+
+impl<AState: ReversibleState> SearchRun<'_, AState> {
+    /// Like `expand`, but the predecessors of `state`.
+    fn expand_backward(&mut self, state: &AState) -> Result<impl Iterator<Item = AState>, Stop> {
+        self.count_expansion(state)?;
+        Ok(state.get_possible_predecessors())
+    }
+}
+
+/// A way to search for a sequence of states from `initial_state` to a final
+/// state, both included.
+pub trait SearchStrategy<AState: State + Clone> {
+    /// The search, within the limits of `run` and counting what it does.
+    fn search_within(
+        &self,
+        initial_state: &AState,
+        run: &mut SearchRun<AState>,
+    ) -> Result<Vec<AState>, Stop>;
+
+    /// The search and what it took.
+    fn search(
+        &self,
+        initial_state: &AState,
+        limits: &SearchLimits,
+        observer: &mut dyn SearchObserver<AState>,
+    ) -> (SearchOutcome<Vec<AState>>, SearchStats) {
+        let mut run = SearchRun {
+            limits,
+            observer,
+            started: Instant::now(),
+            stats: SearchStats::default(),
+        };
+        let outcome = match self.search_within(initial_state, &mut run) {
+            Ok(sequence) => SearchOutcome::Found(sequence),
+            Err(Stop::Exhausted) => SearchOutcome::Exhausted,
+            Err(Stop::Aborted(limit)) => SearchOutcome::Aborted(limit),
+            Err(Stop::Cancelled) => SearchOutcome::Cancelled,
+        };
+        run.stats.elapsed = run.started.elapsed();
+        (outcome, run.stats)
+    }
+}
+
+/// What a search took, e.g. to compare strategies or to measure how hard a
+/// puzzle is.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SearchStats {
+    /// states whose successors were evaluated
+    pub nodes_expanded: usize,
+    /// most states waiting to be evaluated at once, or for depth-first
+    /// searches the longest sequence tried
+    pub max_frontier: usize,
+    /// states found not to lead to a final state by `DepthFirst`
+    pub dead_ends: usize,
+    pub elapsed: Duration,
+}
+
+struct Attempt<AState: State> {
+    state: AState,
+    successors_to_try: Vec<AState>,
+}
+
+/// The states of a stack of attempts, followed by the final state.
+fn sequence_of_attempts<AState: State>(
+    attempts: Vec<Attempt<AState>>,
+    final_state: AState,
+) -> Vec<AState> {
+    let mut sequence: Vec<AState> = attempts.into_iter().map(|a| a.state).collect();
+    sequence.push(final_state);
+    sequence
+}
+
+/// Every state seen so far, stored once by its key, and the state it was
+/// first reached from. States are referred to by their index, in the order
+/// they were seen.
+struct Visited<AState: State, S = FxBuildHasher> {
+    parents: IndexMap<AState::Key, (AState, Option<usize>), S>,
+}
+
+impl<AState: State + Clone, S: BuildHasher + Default> Visited<AState, S> {
+    /// The states that searches start from, without a parent.
+    fn new(initial_states: impl IntoIterator<Item = AState>) -> Visited<AState, S> {
+        Visited {
+            parents: initial_states
+                .into_iter()
+                .map(|state| (state.key(), (state, None)))
+                .collect(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    fn index_of(&self, state: &AState) -> Option<usize> {
+        self.parents.get_index_of(&state.key())
+    }
+
+    fn contains(&self, state: &AState) -> bool {
+        self.parents.contains_key(&state.key())
+    }
+
+    fn state(&self, index: usize) -> &AState {
+        &self.parents.get_index(index).unwrap().1 .0
+    }
+
+    /// Add a new state, reached from the state with index `parent`, and
+    /// return its index.
+    fn insert(&mut self, state: AState, parent: usize) -> usize {
+        self.parents
+            .insert_full(state.key(), (state, Some(parent)))
+            .0
+    }
+
+    /// Note that the state with `index` is reached from `parent` after all.
+    fn set_parent(&mut self, index: usize, parent: usize) {
+        self.parents.get_index_mut(index).unwrap().1 .1 = Some(parent);
+    }
+
+    /// The sequence from the initial state to the state with `index`.
+    fn sequence_to(&self, index: usize) -> Vec<AState> {
+        let mut indices = vec![index];
+        while let Some((_, (_, Some(parent)))) = self.parents.get_index(*indices.last().unwrap()) {
+            indices.push(*parent);
+        }
+        indices
+            .into_iter()
+            .rev()
+            .map(|index| self.state(index).clone())
+            .collect()
+    }
+}
+
+/// The states a breadth-first search has seen and those whose successors it
+/// has yet to evaluate, nearest first. `BreadthFirst` runs through them in
+/// order; searches that are run one state at a time, e.g. to show them, may
+/// take them out of turn.
+pub(crate) struct BreadthFirstQueue<AState: State> {
+    visited: Visited<AState>,
+    /// states waiting to be evaluated, by index, and their steps
+    queue: VecDeque<(usize, usize)>,
+}
+
+impl<AState: State + Clone> BreadthFirstQueue<AState> {
+    pub(crate) fn new(initial_state: &AState) -> BreadthFirstQueue<AState> {
+        BreadthFirstQueue {
+            visited: Visited::new([initial_state.clone()]),
+            queue: VecDeque::from([(0, 0)]),
+        }
+    }
+
+    /// Take the state with the given position among those waiting, by its
+    /// index and steps.
+    pub(crate) fn take(&mut self, position: usize) -> Option<(usize, usize)> {
+        self.queue.remove(position)
+    }
+
+    /// Queue the successors of the state with `index`, `steps` steps away,
+    /// that were not seen before, and return how many there are.
+    pub(crate) fn push_successors(
+        &mut self,
+        index: usize,
+        steps: usize,
+        successors: impl Iterator<Item = AState>,
+    ) -> usize {
+        let waiting = self.queue.len();
+        for successor in successors {
+            if !self.visited.contains(&successor) {
+                let successor = self.visited.insert(successor, index);
+                self.queue.push_back((successor, steps + 1));
+            }
+        }
+        self.queue.len() - waiting
+    }
+
+    /// Stop evaluating states, e.g. once a final state is found.
+    pub(crate) fn clear(&mut self) {
+        self.queue.clear();
+    }
+
+    pub(crate) fn state(&self, index: usize) -> &AState {
+        self.visited.state(index)
+    }
+
+    pub(crate) fn index_of(&self, state: &AState) -> Option<usize> {
+        self.visited.index_of(state)
+    }
+
+    /// The sequence from the initial state to the state with `index`.
+    pub(crate) fn sequence_to(&self, index: usize) -> Vec<AState> {
+        self.visited.sequence_to(index)
+    }
+
+    /// Number of states waiting to be evaluated.
+    pub(crate) fn waiting(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// The state with the given position among those waiting.
+    pub(crate) fn waiting_state(&self, position: usize) -> Option<&AState> {
+        self.queue
+            .get(position)
+            .map(|&(index, _)| self.state(index))
+    }
+}
+
+/// Try successors until the sequence runs into a dead end, then backtrack.
+/// The sequence is the first one found, not necessarily a shortest one.
+pub struct DepthFirst;
+
+impl<AState: State + Clone> SearchStrategy<AState> for DepthFirst {
+    fn search_within(
+        &self,
+        initial_state: &AState,
+        run: &mut SearchRun<AState>,
+    ) -> Result<Vec<AState>, Stop> {
+        if initial_state.is_final() {
+            return Ok(vec![initial_state.clone()]);
+        }
+        // Things that we are trying or have tried and failed, so do not
+        // re-try. Each attempt was reached from the one before, so the
+        // sequence of attempts is the sequence to the last one.
+        let mut tried: Visited<AState> = Visited::new([initial_state.clone()]);
+        // Things that we can currently try, by their index in `tried`.
+        let mut attempts: Vec<(usize, Vec<AState>)> =
+            vec![(0, run.expand(initial_state)?.collect())];
+        // Whether sequences were cut off at the depth limit.
+        let mut cut_off = false;
+
+        loop {
+            if let Some((index, successors_to_try)) = attempts.last_mut() {
+                let index = *index;
+                if let Some(successor) = successors_to_try.pop() {
+                    // found a successor to try, so try it
+
+                    if successor.is_final() {
+                        let mut sequence = tried.sequence_to(index);
+                        sequence.push(successor);
+                        return Ok(sequence);
+                    }
+
+                    if tried.contains(&successor) {
+                        // this has already been tested, so no need to re-try
+                    } else if run.too_deep(attempts.len() + 1) {
+                        cut_off = true;
+                    } else {
+                        let successors_to_try = run.expand(&successor)?.collect();
+                        attempts.push((tried.insert(successor, index), successors_to_try));
+                        run.frontier(attempts.len());
+                    }
+                } else {
+                    // no more successors -- backtrack
+                    attempts.pop();
+                    let state = tried.state(index);
+                    run.observer.on_backtrack(state);
+                    run.observer.on_dead_end(state);
+                    run.stats.dead_ends += 1;
                 }
+            } else if cut_off {
+                return Err(Stop::Aborted(Limit::Depth));
+            } else {
+                return Err(Stop::Exhausted);
+            }
+        }
+    }
+}
+
+/// Evaluate all states one step away, then all states two steps away, and so
+/// on, so that the sequence is a shortest one.
+pub struct BreadthFirst;
 
+impl<AState: State + Clone> SearchStrategy<AState> for BreadthFirst {
+    fn search_within(
+        &self,
+        initial_state: &AState,
+        run: &mut SearchRun<AState>,
+    ) -> Result<Vec<AState>, Stop> {
+        let mut queue = BreadthFirstQueue::new(initial_state);
+        let mut cut_off = false;
+
+        while let Some((index, steps)) = queue.take(0) {
+            if queue.state(index).is_final() {
+                return Ok(queue.sequence_to(index));
+            }
+            if run.too_deep(steps + 1) {
+                cut_off = true;
+                continue;
+            }
+            let successors = run.expand(queue.state(index))?;
+            queue.push_successors(index, steps, successors);
+            run.frontier(queue.waiting());
+        }
+        if cut_off {
+            Err(Stop::Aborted(Limit::Depth))
+        } else {
+            Err(Stop::Exhausted)
+        }
+    }
+}
+
+/// Depth-first searches that are allowed one step more each iteration, so
+/// that the sequence is a shortest one. Only the current sequence is kept in
+/// memory, at the cost of evaluating states repeatedly.
+pub struct IterativeDeepening;
+
+impl<AState: State + Clone> SearchStrategy<AState> for IterativeDeepening {
+    fn search_within(
+        &self,
+        initial_state: &AState,
+        run: &mut SearchRun<AState>,
+    ) -> Result<Vec<AState>, Stop> {
+        if initial_state.is_final() {
+            return Ok(vec![initial_state.clone()]);
+        }
+        for depth_limit in 1.. {
+            if run.too_deep(depth_limit) {
+                return Err(Stop::Aborted(Limit::Depth));
+            }
+            run.observer.on_iteration(depth_limit);
+            // Whether any sequence was cut off at the depth limit; if not, a
+            // deeper search does not find anything new.
+            let mut cut_off = false;
+            let mut attempts: Vec<Attempt<AState>> = vec![Attempt {
+                state: initial_state.clone(),
+                successors_to_try: run.expand(initial_state)?.collect(),
+            }];
+            // The states of the attempts.
+            let mut on_path: FxHashSet<AState::Key> = [initial_state.key()].into_iter().collect();
+            while let Some(current_attempt) = attempts.last_mut() {
+                let Some(successor) = current_attempt.successors_to_try.pop() else {
+                    let attempt = attempts.pop().unwrap();
+                    run.observer.on_backtrack(&attempt.state);
+                    on_path.remove(&attempt.state.key());
+                    continue;
+                };
                 if successor.is_final() {
-                    attempts.push(Attempt {
-                        state: successor.as_ref().clone(),
-                        successors_to_try: vec![],
-                    });
-                    return Ok(attempts
-                        .iter()
-                        .map(|a| a.state.clone())
-                        .collect::<Vec<AState>>());
+                    return Ok(sequence_of_attempts(attempts, successor));
                 }
+                if on_path.contains(&successor.key()) {
+                    // a cycle, which never leads to a shorter sequence
+                    continue;
+                }
+                if attempts.len() == depth_limit {
+                    cut_off = true;
+                    continue;
+                }
+                on_path.insert(successor.key());
+                attempts.push(Attempt {
+                    successors_to_try: run.expand(&successor)?.collect(),
+                    state: successor,
+                });
+                run.frontier(attempts.len());
+            }
+            if !cut_off {
+                return Err(Stop::Exhausted);
+            }
+        }
+        unreachable!("the depth limit keeps growing until the search ends")
+    }
+}
 
-                if attempts
-                    .iter()
-                    .any(|old_attempt| &(old_attempt.state) == successor.as_ref())
-                    || dead_ends.contains(successor.as_ref())
-                {
-                    // this has already been tested, so no need to re-try
-                    if *verbosity == Verbosity::Trace {
-                        println!(
-                            "{} has been tried before, not considering it.",
-                            successor.as_ref().to_string()
-                        );
+/// Evaluate the states with the lowest steps so far plus estimated steps to
+/// go first. The sequence is a shortest one if the heuristic never estimates
+/// too many steps.
+pub struct AStar<H> {
+    pub heuristic: H,
+}
+
+impl<AState: State + Clone, H: Heuristic<AState>> SearchStrategy<AState> for AStar<H> {
+    fn search_within(
+        &self,
+        initial_state: &AState,
+        run: &mut SearchRun<AState>,
+    ) -> Result<Vec<AState>, Stop> {
+        let mut visited: Visited<AState> = Visited::new([initial_state.clone()]);
+        // The steps to each visited state, by index, on the shortest way
+        // found so far.
+        let mut steps_to: Vec<usize> = vec![0];
+        // States to evaluate, by index, with their estimated total steps and
+        // steps so far; of equally promising states, the one with the fewest
+        // steps so far is evaluated first, and of those the one seen first.
+        let mut open: BinaryHeap<Reverse<(usize, usize, usize)>> =
+            BinaryHeap::from([Reverse((self.heuristic.estimate(initial_state), 0, 0))]);
+        let mut cut_off = false;
+
+        while let Some(Reverse((_, steps, index))) = open.pop() {
+            if steps_to[index] < steps {
+                // reached on a shorter way since it was queued
+                continue;
+            }
+            if visited.state(index).is_final() {
+                return Ok(visited.sequence_to(index));
+            }
+            if run.too_deep(steps + 1) {
+                cut_off = true;
+                continue;
+            }
+            for successor in run.expand(visited.state(index))? {
+                let successor_steps = steps + 1;
+                let estimate = self.heuristic.estimate(&successor);
+                let successor_index = match visited.index_of(&successor) {
+                    Some(known) if steps_to[known] <= successor_steps => continue,
+                    Some(known) => {
+                        visited.set_parent(known, index);
+                        steps_to[known] = successor_steps;
+                        known
                     }
-                } else {
-                    attempts.push(Attempt {
-                        state: successor.as_ref().clone(),
-                        successors_to_try: successor.get_possible_successors(),
-                    });
-                }
+                    None => {
+                        steps_to.push(successor_steps);
+                        visited.insert(successor, index)
+                    }
+                };
+                open.push(Reverse((
+                    successor_steps + estimate,
+                    successor_steps,
+                    successor_index,
+                )));
+            }
+            run.frontier(open.len());
+        }
+        if cut_off {
+            Err(Stop::Aborted(Limit::Depth))
+        } else {
+            Err(Stop::Exhausted)
+        }
+    }
+}
+
+/// Breadth-first searches forward from the initial state and backward from
+/// the final states, taking turns with whichever has fewer states to expand,
+/// until they meet in the middle. The sequence is a shortest one, and for long
+/// sequences both searches together are a lot smaller than a single one.
+pub struct Bidirectional;
+
+/// The states one of the searches of `Bidirectional` has reached.
+struct Side<AState: State> {
+    reached: Visited<AState>,
+    /// the steps to each reached state, by index
+    steps: Vec<usize>,
+    /// the indices of the states reached last, which are expanded next
+    layer: Vec<usize>,
+    depth: usize,
+}
+
+impl<AState: State + Clone> Side<AState> {
+    fn new(states: Vec<AState>) -> Side<AState> {
+        let reached = Visited::new(states);
+        Side {
+            steps: vec![0; reached.len()],
+            layer: (0..reached.len()).collect(),
+            reached,
+            depth: 0,
+        }
+    }
+
+    fn steps_to(&self, state: &AState) -> Option<usize> {
+        self.reached.index_of(state).map(|index| self.steps[index])
+    }
+}
+
+impl<AState: ReversibleState + Clone> SearchStrategy<AState> for Bidirectional {
+    fn search_within(
+        &self,
+        initial_state: &AState,
+        run: &mut SearchRun<AState>,
+    ) -> Result<Vec<AState>, Stop> {
+        if initial_state.is_final() {
+            return Ok(vec![initial_state.clone()]);
+        }
+        let mut forward = Side::new(vec![initial_state.clone()]);
+        let mut backward = Side::new(initial_state.final_states());
+
+        while !forward.layer.is_empty() && !backward.layer.is_empty() {
+            if run.too_deep(forward.depth + backward.depth + 1) {
+                return Err(Stop::Aborted(Limit::Depth));
+            }
+            let forward_turn = forward.layer.len() <= backward.layer.len();
+            let (side, other) = if forward_turn {
+                (&mut forward, &backward)
             } else {
-                // no more successors -- backtrack
-                if let Some(attempt) = attempts.pop() {
-                    if *verbosity != Verbosity::Quiet {
-                        println!("Backtracking from {}", attempt.state.to_string());
+                (&mut backward, &forward)
+            };
+            // The shortest way through the layer found so far: its steps, the
+            // index of the state on this side and the state on the other side
+            // where it crosses.
+            let mut meeting: Option<(usize, usize, AState)> = None;
+            let mut next_layer = vec![];
+            for index in std::mem::take(&mut side.layer) {
+                let state = side.reached.state(index);
+                let neighbors = if forward_turn {
+                    Either::Left(run.expand(state)?)
+                } else {
+                    Either::Right(run.expand_backward(state)?)
+                };
+                for neighbor in neighbors {
+                    if let Some(other_steps) = other.steps_to(&neighbor) {
+                        let steps = side.depth + 1 + other_steps;
+                        if meeting.as_ref().is_none_or(|(best, _, _)| steps < *best) {
+                            meeting = Some((steps, index, neighbor.clone()));
+                        }
                     }
-                    dead_ends.insert(attempt.state);
-                    if *verbosity == Verbosity::Trace {
-                        println!(
-                            "  Known dead ends: {:?}",
-                            dead_ends.iter().map(|s| s.to_string()).format(", ")
-                        );
+                    if !side.reached.contains(&neighbor) {
+                        next_layer.push(side.reached.insert(neighbor, index));
+                        side.steps.push(side.depth + 1);
                     }
+                }
+            }
+            side.layer = next_layer;
+            side.depth += 1;
+            let meeting = meeting
+                .map(|(_, index, other_state)| (side.reached.state(index).clone(), other_state));
+            run.frontier(forward.layer.len() + backward.layer.len());
+
+            if let Some((this_state, other_state)) = meeting {
+                let (before, after) = if forward_turn {
+                    (this_state, other_state)
                 } else {
-                    assert!(false); // attempts must not have been empty
+                    (other_state, this_state)
+                };
+                let sequence_to = |side: &Side<AState>, state: &AState| {
+                    side.reached
+                        .sequence_to(side.reached.index_of(state).unwrap())
+                };
+                let mut sequence = sequence_to(&forward, &before);
+                // the backward search reached the state after from the final
+                // state, in the opposite order
+                sequence.extend(sequence_to(&backward, &after).into_iter().rev());
+                return Ok(sequence);
+            }
+        }
+        Err(Stop::Exhausted)
+    }
+}
+
+/// Every sequence from the initial state to a final state that does not visit
+/// a state twice, found one after the other by depth-first search. Sequences
+/// end at the first final state they reach.
+pub struct Solutions<AState: State> {
+    attempts: Vec<Attempt<AState>>,
+    /// the initial state, if it is final and not returned yet
+    final_initial_state: Option<AState>,
+    max_steps: Option<usize>,
+    /// the states of the attempts
+    on_path: FxHashSet<AState::Key>,
+}
+
+pub fn find_all_solutions<AState: State + Clone>(initial_state: &AState) -> Solutions<AState> {
+    if initial_state.is_final() {
+        return Solutions {
+            attempts: vec![],
+            final_initial_state: Some(initial_state.clone()),
+            max_steps: None,
+            on_path: FxHashSet::default(),
+        };
+    }
+    Solutions {
+        attempts: vec![Attempt {
+            state: initial_state.clone(),
+            successors_to_try: initial_state.get_possible_successors().collect(),
+        }],
+        final_initial_state: None,
+        max_steps: None,
+        on_path: [initial_state.key()].into_iter().collect(),
+    }
+}
+
+impl<AState: State> Solutions<AState> {
+    /// Only the sequences of up to `max_steps` steps, which are a lot fewer.
+    pub fn with_max_steps(self, max_steps: usize) -> Solutions<AState> {
+        Solutions {
+            max_steps: Some(max_steps),
+            ..self
+        }
+    }
+}
+
+impl<AState: State + Clone> Iterator for Solutions<AState> {
+    type Item = Vec<AState>;
+
+    fn next(&mut self) -> Option<Vec<AState>> {
+        if let Some(state) = self.final_initial_state.take() {
+            return Some(vec![state]);
+        }
+        loop {
+            let Some(successor) = self.attempts.last_mut()?.successors_to_try.pop() else {
+                // no more successors -- backtrack
+                if let Some(attempt) = self.attempts.pop() {
+                    self.on_path.remove(&attempt.state.key());
                 }
+                continue;
+            };
+            if self.on_path.contains(&successor.key()) {
+                continue;
             }
-        } else {
-            return Err(
-                "No suitable chain of states found to final state; all possibilities exhausted.",
-            );
+            // the number of steps to the successor
+            let steps = self.attempts.len();
+            if self.max_steps.is_some_and(|max_steps| steps > max_steps) {
+                continue;
+            }
+            if successor.is_final() {
+                let mut sequence: Vec<AState> = self
+                    .attempts
+                    .iter()
+                    .map(|attempt| attempt.state.clone())
+                    .collect();
+                sequence.push(successor);
+                return Some(sequence);
+            }
+            if self.max_steps == Some(steps) {
+                continue;
+            }
+            self.on_path.insert(successor.key());
+            self.attempts.push(Attempt {
+                successors_to_try: successor.get_possible_successors().collect(),
+                state: successor,
+            });
         }
     }
 }
 
-// example 1 ------------------------------------------------------------------
+/// Number of steps from each state reachable from the initial state to the
+/// nearest final state, for the states that lead to a final state at all.
+/// All reachable states are searched forward first, then the distances are
+/// found by a breadth-first search backward from the final ones among them.
+pub fn distance_map<AState: State + Clone + Eq + Hash>(
+    initial_state: &AState,
+) -> HashMap<AState, u32, FxBuildHasher> {
+    distance_map_with_hasher(initial_state)
+}
 
-// just count up until a target value is reached
+/// Like `distance_map`, with the given hasher for the states.
+pub fn distance_map_with_hasher<AState: State + Clone + Eq + Hash, S: BuildHasher + Default>(
+    initial_state: &AState,
+) -> HashMap<AState, u32, S> {
+    let mut reached: IndexSet<AState, S> = [initial_state.clone()].into_iter().collect();
+    // The indices of the states each reachable state is reached from.
+    let mut predecessors: Vec<Vec<usize>> = vec![vec![]];
+    let mut next = 0;
+    while next < reached.len() {
+        for successor in reached[next].get_possible_successors() {
+            match reached.get_index_of(&successor) {
+                Some(known) => predecessors[known].push(next),
+                None => {
+                    reached.insert(successor);
+                    predecessors.push(vec![next]);
+                }
+            }
+        }
+        next += 1;
+    }
 
-#[derive(Clone, Eq, Hash)]
-struct Counter {
-    value: i32,
+    let mut distances: Vec<Option<u32>> = vec![None; reached.len()];
+    let mut queue: VecDeque<usize> = (0..reached.len())
+        .filter(|&index| reached[index].is_final())
+        .collect();
+    for &index in &queue {
+        distances[index] = Some(0);
+    }
+    while let Some(index) = queue.pop_front() {
+        let distance = distances[index].unwrap() + 1;
+        for &predecessor in &predecessors[index] {
+            if distances[predecessor].is_none() {
+                distances[predecessor] = Some(distance);
+                queue.push_back(predecessor);
+            }
+        }
+    }
+    distances
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, distance)| Some((reached[index].clone(), distance?)))
+        .collect()
 }
 
-impl State for Counter {
-    fn get_possible_successors(&self) -> Vec<Box<Self>> {
-        vec![Box::new(Self {
-            value: self.value + 1,
-        })]
+// asteroids board ------------------------------------------------------------
+
+impl State for Board {
+    type Key = u64;
+    fn key(&self) -> u64 {
+        self.canonical_key()
     }
     fn is_final(&self) -> bool {
-        self.value == 7
+        self.is_won()
+    }
+    fn get_possible_successors(&self) -> impl Iterator<Item = Self> + use<> {
+        let board = *self;
+        DIRECTIONS
+            .into_iter()
+            .filter_map(move |direction| board.move_free_space(&direction).ok())
     }
 }
 
-impl PartialEq for Counter {
-    fn eq(&self, other_state: &Self) -> bool {
-        other_state.value == self.value
+impl ReversibleState for Board {
+    fn get_possible_predecessors(&self) -> impl Iterator<Item = Self> + use<> {
+        // every move can be undone
+        self.get_possible_successors()
+    }
+    fn final_states(&self) -> Vec<Self> {
+        won_positions(self)
     }
 }
 
-impl ToString for Counter {
-    fn to_string(&self) -> String {
-        self.value.to_string()
+/// Moves of the ship to the exit plus the moves that it takes the free space
+/// to get next to the ship. Every move moves the free space by one cell, so
+/// the estimate is never too high.
+pub struct ExitDistance;
+
+impl Heuristic<Board> for ExitDistance {
+    fn estimate(&self, board: &Board) -> usize {
+        let Some(ship) = board.shapes.iter().position(|&shape| shape == Shape::Ship) else {
+            return 0;
+        };
+        let distance =
+            |a: BoardIndex2d, b: BoardIndex2d| ((a.x - b.x).abs() + (a.y - b.y).abs()) as usize;
+        let ship = BoardIndex2d::from_index(ship);
+        let in_front_of_exit = BoardIndex2d { x: 1, y: 2 };
+        if ship == in_front_of_exit {
+            return 0;
+        }
+        distance(ship, in_front_of_exit) + distance(board.find_free_space(), ship) - 1
     }
 }
 
-fn demo_example_1(verbosity: &Verbosity) {
-    println!("");
-    println!("Demo example 1");
-    match get_sequence_to_final_state::<Counter>(&Counter { value: 1i32 }, verbosity) {
-        Err(msg) => eprintln!("even simple example did not work due to '{}'", msg),
-        Ok(counter_result) => counter_result
+/// A board that stands for itself and its mirror image. The exit is in the
+/// middle, so both lead to it in the same number of moves, and searching these
+/// instead of boards evaluates about half as many positions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct UpToMirroring(Board);
+
+impl UpToMirroring {
+    fn new(board: &Board) -> UpToMirroring {
+        UpToMirroring(board.mirror_canonical())
+    }
+}
+
+impl fmt::Display for UpToMirroring {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl State for UpToMirroring {
+    type Key = u64;
+    fn key(&self) -> u64 {
+        self.0.canonical_key()
+    }
+    fn is_final(&self) -> bool {
+        self.0.is_won()
+    }
+    fn get_possible_successors(&self) -> impl Iterator<Item = Self> + use<> {
+        self.0
+            .get_possible_successors()
+            .map(|board| UpToMirroring::new(&board))
+    }
+}
+
+impl ReversibleState for UpToMirroring {
+    fn get_possible_predecessors(&self) -> impl Iterator<Item = Self> + use<> {
+        self.0
+            .get_possible_predecessors()
+            .map(|board| UpToMirroring::new(&board))
+    }
+    fn final_states(&self) -> Vec<Self> {
+        won_positions(&self.0)
             .iter()
-            .for_each(|state| println!("{}", state.value)),
+            .map(UpToMirroring::new)
+            .unique()
+            .collect()
     }
 }
 
-// example 2 ------------------------------------------------------------------
+impl Heuristic<UpToMirroring> for ExitDistance {
+    fn estimate(&self, state: &UpToMirroring) -> usize {
+        // the same for a board and its mirror image
+        self.estimate(&state.0)
+    }
+}
 
-// count up by either 2 or 1 until a target value is reached
+impl Heuristic<UpToMirroring> for PatternDatabase {
+    fn estimate(&self, state: &UpToMirroring) -> usize {
+        // the tables are those of the shapes of both mirror images
+        self.estimate(&state.0)
+    }
+}
 
-#[derive(Clone, Eq, Hash)]
-struct JumpingCounter {
-    value: i32,
+impl AsRef<Board> for UpToMirroring {
+    fn as_ref(&self) -> &Board {
+        &self.0
+    }
 }
 
-impl State for JumpingCounter {
-    fn get_possible_successors(&self) -> Vec<Box<Self>> {
-        // ensure that we do not count up infinitively
-        // If we allow value+2 in every case and if we 'miss' the final value,
-        // we just count up and up.
-        match self.value {
-            0..=10 => vec![
-                Box::new(Self {
-                    value: self.value + 1,
-                }),
-                Box::new(Self {
-                    value: self.value + 2,
-                }),
-            ],
-            _ => vec![],
-        }
+/// A board whose successors include the slides of `Rules::slide_counts_once`,
+/// i.e. the free space moving in one direction until it is blocked or the
+/// board is won, so that a search counts a slide as one step.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Sliding(Board);
+
+impl fmt::Display for Sliding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl AsRef<Board> for Sliding {
+    fn as_ref(&self) -> &Board {
+        &self.0
+    }
+}
+
+/// Whether a slide in the direction stops at the board.
+fn slide_stops(board: &Board, direction: &Direction) -> bool {
+    board.is_won() || board.move_free_space(direction).is_err()
+}
+
+impl State for Sliding {
+    type Key = u64;
+    fn key(&self) -> u64 {
+        self.0.canonical_key()
     }
     fn is_final(&self) -> bool {
-        self.value == 4
+        self.0.is_won()
+    }
+    fn get_possible_successors(&self) -> impl Iterator<Item = Self> + use<> {
+        let board = self.0;
+        DIRECTIONS
+            .into_iter()
+            .filter_map(move |direction| {
+                let next = board.move_free_space(&direction).ok()?;
+                let mut end = next;
+                while !slide_stops(&end, &direction) {
+                    end = end.move_free_space(&direction).unwrap();
+                }
+                Some([Some(next), (end != next).then_some(end)])
+            })
+            .flatten()
+            .flatten()
+            .map(Sliding)
+    }
+}
+
+impl ReversibleState for Sliding {
+    fn get_possible_predecessors(&self) -> impl Iterator<Item = Self> + use<> {
+        let board = self.0;
+        DIRECTIONS
+            .into_iter()
+            .filter_map(move |direction| {
+                let previous = board.move_free_space(&direction.opposite()).ok()?;
+                // the boards further back that slide all the way to this one,
+                // without passing a won board
+                let further = slide_stops(&board, &direction).then(|| {
+                    std::iter::successors(Some(previous), move |start| {
+                        if start.is_won() {
+                            None
+                        } else {
+                            start.move_free_space(&direction.opposite()).ok()
+                        }
+                    })
+                    .skip(1)
+                });
+                Some(std::iter::once(previous).chain(further.into_iter().flatten()))
+            })
+            .flatten()
+            .map(Sliding)
+    }
+    fn final_states(&self) -> Vec<Self> {
+        won_positions(&self.0).into_iter().map(Sliding).collect()
+    }
+}
+
+impl Heuristic<Sliding> for ExitDistance {
+    fn estimate(&self, state: &Sliding) -> usize {
+        // a single slide may take the ship all the way to the exit
+        usize::from(!state.0.is_won())
+    }
+}
+
+/// The tables count moves, not slides, so they tell nothing about slides;
+/// `Strategy::solve_with_cost` does not build them for slides at all.
+impl Heuristic<Sliding> for PatternDatabase {
+    fn estimate(&self, state: &Sliding) -> usize {
+        ExitDistance.estimate(state)
     }
 }
 
-impl PartialEq for JumpingCounter {
-    fn eq(&self, other_state: &Self) -> bool {
-        other_state.value == self.value
+/// Passes on what a search of states that stand for boards does.
+struct BoardObserver<'a>(&'a mut dyn SearchObserver<Board>);
+
+impl<AState: AsRef<Board>> SearchObserver<AState> for BoardObserver<'_> {
+    fn on_expand(&mut self, state: &AState) {
+        self.0.on_expand(state.as_ref());
+    }
+    fn on_backtrack(&mut self, state: &AState) {
+        self.0.on_backtrack(state.as_ref());
+    }
+    fn on_dead_end(&mut self, state: &AState) {
+        self.0.on_dead_end(state.as_ref());
     }
+    fn on_iteration(&mut self, depth_limit: usize) {
+        self.0.on_iteration(depth_limit);
+    }
+}
+
+/// The moves that lead from the board through positions that are each the
+/// next one of the sequence or its mirror image.
+fn moves_up_to_mirroring(board: &Board, sequence: &[UpToMirroring]) -> Vec<Direction> {
+    let mut board = *board;
+    sequence[1..]
+        .iter()
+        .map(|next| {
+            let (direction, next_board) = successors(&board)
+                .into_iter()
+                .find(|(_, candidate)| UpToMirroring::new(candidate) == *next)
+                .expect("consecutive states are one move apart");
+            board = next_board;
+            direction
+        })
+        .collect()
 }
 
-impl ToString for JumpingCounter {
-    fn to_string(&self) -> String {
-        self.value.to_string()
+/// The moves that lead from each board of the sequence to the next one, by a
+/// single move or a slide.
+fn moves_of_slides(boards: &[Sliding]) -> Vec<Direction> {
+    boards
+        .windows(2)
+        .flat_map(|pair| {
+            DIRECTIONS
+                .iter()
+                .find_map(|direction| {
+                    let mut board = pair[0].0;
+                    let mut moves = vec![];
+                    while let Ok(next) = board.move_free_space(direction) {
+                        board = next;
+                        moves.push(*direction);
+                        if board == pair[1].0 {
+                            return Some(moves);
+                        }
+                        if board.is_won() {
+                            break;
+                        }
+                    }
+                    None
+                })
+                .expect("consecutive states are one move or slide apart")
+        })
+        .collect()
+}
+
+/// The moves that lead from each board of the sequence to the next one.
+pub(crate) fn moves_between(boards: &[Board]) -> Vec<Direction> {
+    boards
+        .windows(2)
+        .map(|pair| {
+            successors(&pair[0])
+                .into_iter()
+                .find(|(_, next)| *next == pair[1])
+                .map(|(direction, _)| direction)
+                .expect("consecutive states are one move apart")
+        })
+        .collect()
+}
+
+/// Every solution of the board of up to `max_moves` moves, if given, that
+/// does not pass a position twice, in no particular order. There are a lot of
+/// them for all but the smallest puzzles.
+pub fn all_solutions(
+    board: &Board,
+    max_moves: Option<usize>,
+) -> impl Iterator<Item = Vec<Direction>> {
+    let solutions = find_all_solutions(board);
+    match max_moves {
+        Some(max_moves) => solutions.with_max_steps(max_moves),
+        None => solutions,
     }
+    .map(|boards| moves_between(&boards))
 }
 
-fn demo_example_2(verbosity: &Verbosity) {
-    println!("");
-    println!("Demo example 2");
-    match get_sequence_to_final_state::<JumpingCounter>(&JumpingCounter { value: 1i32 }, verbosity)
+/// What counts as one move, to find the solutions with the fewest of them.
+/// Every move of the free space moves one tile by one cell, so counting tiles
+/// moved is the same as counting moves.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum CostModel {
+    /// every move of the free space
+    #[default]
+    Moves,
+    /// like `Moves`, but a slide counts as one move, see `Sliding`
+    Slides,
+}
+
+impl CostModel {
+    /// The cost model called `moves` or `slides`.
+    pub fn from_name(name: &str) -> Option<CostModel> {
+        match name {
+            "moves" => Some(CostModel::Moves),
+            "slides" => Some(CostModel::Slides),
+            _ => None,
+        }
+    }
+
+    /// The cost of playing the moves on the board, which must be legal.
+    pub fn cost(self, board: &Board, moves: &[Direction]) -> usize {
+        match self {
+            CostModel::Moves => moves.len(),
+            CostModel::Slides => {
+                let mut board = *board;
+                moves
+                    .chunk_by(|a, b| a == b)
+                    .map(|run| {
+                        for direction in run {
+                            board = board.move_free_space(direction).expect("a legal move");
+                        }
+                        // moves in the same direction up to where a slide
+                        // stops are that slide
+                        if slide_stops(&board, &run[0]) {
+                            1
+                        } else {
+                            run.len()
+                        }
+                    })
+                    .sum()
+            }
+        }
+    }
+}
+
+/// The search strategies for boards, to pick one at runtime.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Strategy {
+    DepthFirst,
+    BreadthFirst,
+    IterativeDeepening,
+    /// with the `ExitDistance` heuristic
+    AStar,
+    /// A* with the `PatternDatabase` heuristic
+    PatternAStar,
+    Bidirectional,
+}
+
+impl Strategy {
+    /// The strategy called `dfs`, `bfs`, `iddfs`, `astar`, `pdb` or `bidir`.
+    pub fn from_name(name: &str) -> Option<Strategy> {
+        match name {
+            "dfs" => Some(Strategy::DepthFirst),
+            "bfs" => Some(Strategy::BreadthFirst),
+            "iddfs" => Some(Strategy::IterativeDeepening),
+            "astar" => Some(Strategy::AStar),
+            "pdb" => Some(Strategy::PatternAStar),
+            "bidir" => Some(Strategy::Bidirectional),
+            _ => None,
+        }
+    }
+
+    /// Whether the solutions found are shortest ones. Depth-first search
+    /// returns the first one it comes across; the heuristics of A* never
+    /// overestimate, so A* finds shortest ones just like the others.
+    pub fn finds_shortest(self) -> bool {
+        self != Strategy::DepthFirst
+    }
+
+    /// The search with this strategy, from a state that stands for the board.
+    /// `PatternAStar` caches its tables in the file at `patterns`, if given,
+    /// see `PatternDatabase::open`.
+    fn search<AState>(
+        self,
+        board: &Board,
+        initial_state: &AState,
+        patterns: Option<&Path>,
+        limits: &SearchLimits,
+        observer: &mut dyn SearchObserver<AState>,
+    ) -> (SearchOutcome<Vec<AState>>, SearchStats)
+    where
+        AState: ReversibleState + Clone,
+        ExitDistance: Heuristic<AState>,
+        PatternDatabase: Heuristic<AState>,
     {
-        Err(msg) => eprintln!("even simple example did not work due to '{}'", msg),
-        Ok(counter_result) => counter_result
+        match self {
+            Strategy::DepthFirst => DepthFirst.search(initial_state, limits, observer),
+            Strategy::BreadthFirst => BreadthFirst.search(initial_state, limits, observer),
+            Strategy::IterativeDeepening => {
+                IterativeDeepening.search(initial_state, limits, observer)
+            }
+            Strategy::AStar => AStar {
+                heuristic: ExitDistance,
+            }
+            .search(initial_state, limits, observer),
+            Strategy::PatternAStar => AStar {
+                heuristic: PatternDatabase::for_board(patterns, board),
+            }
+            .search(initial_state, limits, observer),
+            Strategy::Bidirectional => Bidirectional.search(initial_state, limits, observer),
+        }
+    }
+
+    /// Solve the board, giving up at the limits, and return what the search
+    /// took. The solution is a shortest one, except for `DepthFirst`. The
+    /// tables of `PatternAStar` are cached in the file at `patterns`, if given.
+    pub fn solve_within(
+        self,
+        board: &Board,
+        patterns: Option<&Path>,
+        limits: &SearchLimits,
+        observer: &mut dyn SearchObserver<Board>,
+    ) -> (SearchOutcome<Vec<Direction>>, SearchStats) {
+        let (outcome, stats) = self.search(board, board, patterns, limits, observer);
+        (outcome.map(|states| moves_between(&states)), stats)
+    }
+
+    /// Like `solve_within`, but treating mirror images as the same position,
+    /// see `UpToMirroring`. The observer sees one board of each pair.
+    pub fn solve_up_to_mirroring(
+        self,
+        board: &Board,
+        patterns: Option<&Path>,
+        limits: &SearchLimits,
+        observer: &mut dyn SearchObserver<Board>,
+    ) -> (SearchOutcome<Vec<Direction>>, SearchStats) {
+        let (outcome, stats) = self.search(
+            board,
+            &UpToMirroring::new(board),
+            patterns,
+            limits,
+            &mut BoardObserver(observer),
+        );
+        (
+            outcome.map(|states| moves_up_to_mirroring(board, &states)),
+            stats,
+        )
+    }
+
+    /// Like `solve_within`, but the solution is one of the cheapest according
+    /// to the cost model instead of one with the fewest moves. For slides,
+    /// `PatternAStar` is the same as `AStar`, as its tables count moves.
+    pub fn solve_with_cost(
+        self,
+        board: &Board,
+        cost_model: CostModel,
+        patterns: Option<&Path>,
+        limits: &SearchLimits,
+        observer: &mut dyn SearchObserver<Board>,
+    ) -> (SearchOutcome<Vec<Direction>>, SearchStats) {
+        match cost_model {
+            CostModel::Moves => self.solve_within(board, patterns, limits, observer),
+            CostModel::Slides => {
+                let strategy = match self {
+                    Strategy::PatternAStar => Strategy::AStar,
+                    strategy => strategy,
+                };
+                let (outcome, stats) = strategy.search(
+                    board,
+                    &Sliding(*board),
+                    None,
+                    limits,
+                    &mut BoardObserver(observer),
+                );
+                (outcome.map(|states| moves_of_slides(&states)), stats)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::drill;
+    use crate::notation::parse_board;
+    use crate::sample_board;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::fmt;
+
+    // example 1: just count up until a target value is reached
+
+    #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+    struct Counter {
+        value: i32,
+    }
+
+    impl State for Counter {
+        type Key = i32;
+        fn key(&self) -> i32 {
+            self.value
+        }
+        fn get_possible_successors(&self) -> impl Iterator<Item = Self> + use<> {
+            std::iter::once(Self {
+                value: self.value + 1,
+            })
+        }
+        fn is_final(&self) -> bool {
+            self.value == 7
+        }
+    }
+
+    impl fmt::Display for Counter {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{}", self.value)
+        }
+    }
+
+    #[test]
+    fn test_counter() {
+        let (states, stats) = DepthFirst.search(
+            &Counter { value: 1 },
+            &SearchLimits::default(),
+            &mut Verbosity::Trace,
+        );
+        let values: Vec<i32> = states
+            .found()
+            .unwrap()
+            .iter()
+            .map(|state| state.value)
+            .collect();
+        assert_eq!(values, vec![1, 2, 3, 4, 5, 6, 7]);
+        assert_eq!(stats.nodes_expanded, 6);
+        assert_eq!(stats.max_frontier, 6);
+        assert_eq!(stats.dead_ends, 0);
+    }
+
+    // example 2: count up by either 2 or 1 until a target value is reached
+
+    #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+    struct JumpingCounter {
+        value: i32,
+    }
+
+    impl State for JumpingCounter {
+        type Key = i32;
+        fn key(&self) -> i32 {
+            self.value
+        }
+        fn get_possible_successors(&self) -> impl Iterator<Item = Self> + use<> {
+            // ensure that we do not count up infinitively
+            // If we allow value+2 in every case and if we 'miss' the final value,
+            // we just count up and up.
+            let steps = match self.value {
+                0..=10 => [1, 2].as_slice(),
+                _ => &[],
+            };
+            let value = self.value;
+            steps.iter().map(move |step| Self {
+                value: value + step,
+            })
+        }
+        fn is_final(&self) -> bool {
+            self.value == 4
+        }
+    }
+
+    impl fmt::Display for JumpingCounter {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{}", self.value)
+        }
+    }
+
+    impl ReversibleState for JumpingCounter {
+        fn get_possible_predecessors(&self) -> impl Iterator<Item = Self> + use<> {
+            [self.value - 1, self.value - 2]
+                .into_iter()
+                .filter(|value| (0..=10).contains(value))
+                .map(|value| Self { value })
+        }
+        fn final_states(&self) -> Vec<Self> {
+            vec![Self { value: 4 }]
+        }
+    }
+
+    /// Steps of two, rounded up.
+    struct HalfDistance;
+
+    impl Heuristic<JumpingCounter> for HalfDistance {
+        fn estimate(&self, state: &JumpingCounter) -> usize {
+            ((4 - state.value).max(0) as usize).div_ceil(2)
+        }
+    }
+
+    #[test]
+    fn test_jumping_counter() {
+        let states = DepthFirst
+            .search(
+                &JumpingCounter { value: 1 },
+                &SearchLimits::default(),
+                &mut Verbosity::Info,
+            )
+            .0
+            .found()
+            .unwrap();
+        assert_eq!(states.first().unwrap().value, 1);
+        assert!(states.last().unwrap().is_final());
+        assert!(states
+            .windows(2)
+            .all(|pair| [1, 2].contains(&(pair[1].value - pair[0].value))));
+
+        let shortest: [&dyn SearchStrategy<JumpingCounter>; 4] = [
+            &BreadthFirst,
+            &IterativeDeepening,
+            &AStar {
+                heuristic: HalfDistance,
+            },
+            &Bidirectional,
+        ];
+        for strategy in shortest {
+            let states = strategy
+                .search(
+                    &JumpingCounter { value: 1 },
+                    &SearchLimits::default(),
+                    &mut Verbosity::Trace,
+                )
+                .0
+                .found()
+                .unwrap();
+            let values: Vec<i32> = states.iter().map(|state| state.value).collect();
+            assert!(values == vec![1, 2, 4] || values == vec![1, 3, 4]);
+        }
+
+        let all: Vec<Vec<i32>> = find_all_solutions(&JumpingCounter { value: 1 })
+            .map(|states| states.iter().map(|state| state.value).collect())
+            .collect();
+        assert_eq!(all, vec![vec![1, 3, 4], vec![1, 2, 4], vec![1, 2, 3, 4]]);
+        let short = find_all_solutions(&JumpingCounter { value: 1 }).with_max_steps(2);
+        assert_eq!(short.count(), 2);
+
+        // counting past the final value does not lead back to it
+        let distances = distance_map(&JumpingCounter { value: 1 });
+        let mut values: Vec<(i32, u32)> = distances
+            .iter()
+            .map(|(state, &distance)| (state.value, distance))
+            .collect();
+        values.sort();
+        assert_eq!(values, vec![(1, 2), (2, 1), (3, 1), (4, 0)]);
+    }
+
+    /// Records what a search does.
+    #[derive(Default)]
+    struct Recorder {
+        events: Vec<String>,
+    }
+
+    impl<AState: DescribeState> SearchObserver<AState> for Recorder {
+        fn on_expand(&mut self, state: &AState) {
+            self.events.push(format!("expand {}", state.describe()));
+        }
+        fn on_backtrack(&mut self, state: &AState) {
+            self.events.push(format!("backtrack {}", state.describe()));
+        }
+        fn on_dead_end(&mut self, state: &AState) {
+            self.events.push(format!("dead end {}", state.describe()));
+        }
+        fn on_iteration(&mut self, depth_limit: usize) {
+            self.events.push(format!("iteration {}", depth_limit));
+        }
+    }
+
+    #[test]
+    fn test_observer() {
+        let unlimited = SearchLimits::default();
+        let mut recorder = Recorder::default();
+        DepthFirst.search(&JumpingCounter { value: 10 }, &unlimited, &mut recorder);
+        // successors are tried last one first
+        assert_eq!(
+            recorder.events,
+            vec![
+                "expand 10",
+                "expand 12",
+                "backtrack 12",
+                "dead end 12",
+                "expand 11",
+                "backtrack 11",
+                "dead end 11",
+                "backtrack 10",
+                "dead end 10",
+            ]
+        );
+
+        let mut recorder = Recorder::default();
+        IterativeDeepening.search(&Counter { value: 5 }, &unlimited, &mut recorder);
+        assert_eq!(
+            recorder.events,
+            vec![
+                "iteration 1",
+                "expand 5",
+                "backtrack 5",
+                "iteration 2",
+                "expand 5",
+                "expand 6",
+            ]
+        );
+    }
+
+    // example 3: halve or count down to zero, with states that can be
+    // neither printed nor hashed
+
+    #[derive(Clone)]
+    struct Halving {
+        value: u32,
+    }
+
+    impl State for Halving {
+        type Key = u32;
+        fn key(&self) -> u32 {
+            self.value
+        }
+        fn is_final(&self) -> bool {
+            self.value == 0
+        }
+        fn get_possible_successors(&self) -> impl Iterator<Item = Self> + use<> {
+            let value = self.value;
+            [value / 2, value.saturating_sub(1)]
+                .into_iter()
+                .map(|value| Self { value })
+        }
+    }
+
+    #[test]
+    fn test_bounds() {
+        let (states, _) =
+            BreadthFirst.search(&Halving { value: 11 }, &SearchLimits::default(), &mut ());
+        let values: Vec<u32> = states
+            .found()
+            .unwrap()
             .iter()
-            .for_each(|state| println!("{}", state.value)),
-    }
-}
-
-// example 3 ------------------------------------------------------------------
-
-#[derive(Clone, Eq, PartialEq, Hash)]
-enum Piece {
-    Free,
-    Ship,
-    OneTL,
-    OneTR,
-    OneBL,
-    OneBR,
-    TwoDiagDown,
-    TwoDiagUp,
-    TwoHorT,
-    TwoHorL,
-    TwoHorB,
-    TwoHorR,
-    LargeEdgeT,
-    LargeEdgeL,
-    LargeEdgeB,
-    LargeEdgeR,
-    LargeCornerTL,
-    LargeCornerTR,
-    LargeCornerBL,
-    LargeCornerBR,
-}
-
-#[derive(Clone, Eq, PartialEq, Hash)]
-struct AsteroidsState(
-    Piece,
-    Piece,
-    Piece,
-    Piece,
-    Piece,
-    Piece,
-    Piece,
-    Piece,
-    Piece,
-);
-
-fn main() {
-    demo_example_1(&Verbosity::Trace);
-    demo_example_2(&Verbosity::Trace);
+            .map(|state| state.value)
+            .collect();
+        assert_eq!(values, vec![11, 5, 2, 1, 0]);
+        assert_eq!(Counter { value: 3 }.describe(), "3");
+    }
+
+    #[test]
+    fn test_exhausted() {
+        let unlimited = SearchLimits::default();
+        let states = BreadthFirst
+            .search(&Counter { value: 7 }, &unlimited, &mut Verbosity::Quiet)
+            .0;
+        assert_eq!(states, SearchOutcome::Found(vec![Counter { value: 7 }]));
+        let (states, stats) = DepthFirst.search(
+            &JumpingCounter { value: 5 },
+            &unlimited,
+            &mut Verbosity::Quiet,
+        );
+        assert_eq!(states, SearchOutcome::Exhausted);
+        // 5 to 12, none of which leads to 4
+        assert_eq!(stats.dead_ends, 8);
+        let states = Bidirectional
+            .search(
+                &JumpingCounter { value: 5 },
+                &unlimited,
+                &mut Verbosity::Quiet,
+            )
+            .0;
+        assert_eq!(states, SearchOutcome::Exhausted);
+    }
+
+    /// Makes A* a breadth-first search.
+    struct NoEstimate;
+
+    impl<AState: State> Heuristic<AState> for NoEstimate {
+        fn estimate(&self, _: &AState) -> usize {
+            0
+        }
+    }
+
+    #[test]
+    fn test_limits() {
+        let counter = Counter { value: 1 };
+        let limits = |max_nodes, max_depth| SearchLimits {
+            max_nodes,
+            max_depth,
+            ..SearchLimits::default()
+        };
+        let strategies: [&dyn SearchStrategy<Counter>; 4] = [
+            &DepthFirst,
+            &BreadthFirst,
+            &IterativeDeepening,
+            &AStar {
+                heuristic: NoEstimate,
+            },
+        ];
+        for strategy in strategies {
+            let (states, stats) =
+                strategy.search(&counter, &limits(Some(3), None), &mut Verbosity::Quiet);
+            assert_eq!(states, SearchOutcome::Aborted(Limit::Nodes));
+            assert_eq!(stats.nodes_expanded, 3);
+            let states = strategy
+                .search(&counter, &limits(None, Some(5)), &mut Verbosity::Quiet)
+                .0;
+            assert_eq!(states, SearchOutcome::Aborted(Limit::Depth));
+            let states = strategy
+                .search(&counter, &limits(None, Some(6)), &mut Verbosity::Quiet)
+                .0;
+            assert_eq!(states.found().unwrap().len(), 7);
+        }
+        let limits = SearchLimits {
+            max_duration: Some(Duration::ZERO),
+            ..SearchLimits::default()
+        };
+        let states = BreadthFirst
+            .search(&counter, &limits, &mut Verbosity::Quiet)
+            .0;
+        assert_eq!(states, SearchOutcome::Aborted(Limit::Duration));
+    }
+
+    #[test]
+    fn test_cancel() {
+        let token = CancellationToken::default();
+        let limits = SearchLimits {
+            cancel: Some(token.clone()),
+            ..SearchLimits::default()
+        };
+        let counter = Counter { value: 1 };
+        assert!(BreadthFirst
+            .search(&counter, &limits, &mut Verbosity::Quiet)
+            .0
+            .found()
+            .is_some());
+        token.cancel();
+        let (states, stats) = BreadthFirst.search(&counter, &limits, &mut Verbosity::Quiet);
+        assert_eq!(states, SearchOutcome::Cancelled);
+        assert_eq!(stats.nodes_expanded, 0);
+    }
+
+    fn solve(strategy: Strategy, board: &Board) -> Option<Vec<Direction>> {
+        strategy
+            .solve_within(board, None, &SearchLimits::default(), &mut Verbosity::Quiet)
+            .0
+            .found()
+    }
+
+    #[test]
+    fn test_strategies() {
+        let won = sample_board()
+            .move_free_space(&Direction::Left)
+            .and_then(|board| board.move_free_space(&Direction::Left))
+            .unwrap();
+        assert_eq!(ExitDistance.estimate(&won), 0);
+        assert_eq!(ExitDistance.estimate(&sample_board()), 2);
+        for name in ["dfs", "bfs", "iddfs", "astar", "pdb", "bidir"] {
+            let strategy = Strategy::from_name(name).unwrap();
+            assert_eq!(solve(strategy, &won), Some(vec![]));
+            let moves = solve(strategy, &sample_board()).unwrap();
+            let end = sample_board().apply_moves(&moves).unwrap();
+            assert!(end.is_won());
+        }
+        assert_eq!(Strategy::from_name("random"), None);
+
+        assert_eq!(all_solutions(&won, None).collect::<Vec<_>>(), vec![vec![]]);
+        assert_eq!(
+            all_solutions(&sample_board(), Some(3)).collect::<Vec<_>>(),
+            vec![vec![Direction::Left, Direction::Left]]
+        );
+        for moves in all_solutions(&sample_board(), None).take(20) {
+            let end = sample_board().apply_moves(&moves).unwrap();
+            assert!(end.is_won());
+        }
+
+        let mut rng = StdRng::seed_from_u64(3);
+        for moves in [4, 8] {
+            let board = drill(&won, moves, &mut rng).unwrap();
+            assert!(ExitDistance.estimate(&board) <= moves);
+            for strategy in [
+                Strategy::BreadthFirst,
+                Strategy::IterativeDeepening,
+                Strategy::AStar,
+                Strategy::PatternAStar,
+                Strategy::Bidirectional,
+            ] {
+                assert_eq!(solve(strategy, &board).unwrap().len(), moves);
+            }
+        }
+    }
+
+    #[test]
+    fn test_up_to_mirroring() {
+        // a board whose shapes are their own mirror images as a whole
+        let board = parse_board("1tl,s,1tr,1bl,f,1br,2t,2b,2t").unwrap();
+        let positions = distance_map(&board).len();
+        let up_to_mirroring = distance_map(&UpToMirroring::new(&board)).len();
+        // only the positions that are their own mirror image are not paired up
+        assert_eq!((positions, up_to_mirroring), (91534, 45801));
+
+        let won = won_positions(&board)[0];
+        let puzzle = drill(&won, 12, &mut StdRng::seed_from_u64(3)).unwrap();
+        for name in ["dfs", "bfs", "iddfs", "astar", "pdb", "bidir"] {
+            let strategy = Strategy::from_name(name).unwrap();
+            for puzzle in [puzzle, puzzle.mirror()] {
+                let (outcome, _) = strategy.solve_up_to_mirroring(
+                    &puzzle,
+                    None,
+                    &SearchLimits::default(),
+                    &mut Verbosity::Quiet,
+                );
+                let moves = outcome.found().unwrap();
+                if name != "dfs" {
+                    assert_eq!(moves.len(), 12);
+                }
+                let end = puzzle.apply_moves(&moves).unwrap();
+                assert!(end.is_won());
+            }
+        }
+    }
+
+    #[test]
+    fn test_slides() {
+        let board = sample_board();
+        assert_eq!(CostModel::Slides.cost(&board, &[Direction::Left]), 1);
+        assert_eq!(
+            CostModel::Slides.cost(&board, &[Direction::Left, Direction::Left]),
+            1
+        );
+        // slides and their reverse
+        for (board, _) in distance_map(&board).into_iter().take(200) {
+            for successor in Sliding(board).get_possible_successors() {
+                assert!(successor
+                    .get_possible_predecessors()
+                    .contains(&Sliding(board)));
+            }
+            for predecessor in Sliding(board).get_possible_predecessors() {
+                assert!(predecessor
+                    .get_possible_successors()
+                    .contains(&Sliding(board)));
+            }
+        }
+
+        let puzzle = parse_board("1tl,s,1tr,1bl,f,1br,2t,2b,2t").unwrap();
+        let puzzle = drill(
+            &won_positions(&puzzle)[0],
+            12,
+            &mut StdRng::seed_from_u64(3),
+        )
+        .unwrap();
+        let mut costs = vec![];
+        for name in ["bfs", "iddfs", "astar", "pdb", "bidir"] {
+            let (outcome, _) = Strategy::from_name(name).unwrap().solve_with_cost(
+                &puzzle,
+                CostModel::Slides,
+                None,
+                &SearchLimits::default(),
+                &mut Verbosity::Quiet,
+            );
+            let moves = outcome.found().unwrap();
+            let end = puzzle.apply_moves(&moves).unwrap();
+            assert!(end.is_won());
+            costs.push(CostModel::Slides.cost(&puzzle, &moves));
+        }
+        assert!(costs.iter().all(|&cost| cost == costs[0]));
+        assert!(costs[0] < 12);
+    }
 }
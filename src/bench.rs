@@ -0,0 +1,140 @@
+// solver benchmark -----------------------------------------------------------
+//
+// Times the searches on a fixed set of boards, so that changes to the search
+// code can be compared by the same numbers, e.g. before and after a change:
+//
+//     cargo run --release -- bench
+//
+// `benches/solver.rs` measures the same with criterion, for statistics over
+// many runs.
+
+use crate::backtracking::{SearchLimits, SearchOutcome, Strategy, Verbosity};
+use crate::notation::parse_board;
+use std::time::Duration;
+
+/// The boards, from easy to hard: the sample board, a generated one and one
+/// with long shortest solutions.
+pub const BENCH_BOARDS: [&str; 3] = [
+    "1tl,2dd,2t,1tl,ctl,1tl,s,er,f",
+    "2t,2l,s,1tr,2r,1br,f,1bl,1bl",
+    "1tl,s,1tr,1bl,f,1br,2t,2b,2t",
+];
+
+/// The searches, by the names of `solve --search`.
+pub const BENCH_SEARCHES: [&str; 3] = ["dfs", "bfs", "astar"];
+
+/// How one search did on one board.
+#[derive(Debug)]
+pub struct Timing {
+    pub board: &'static str,
+    pub search: &'static str,
+    /// length of the solution found, `None` if there is none
+    pub moves: Option<usize>,
+    pub nodes_expanded: usize,
+    /// the median of the runs
+    pub elapsed: Duration,
+}
+
+/// Solve each board with each search `runs` times.
+pub fn run(runs: usize) -> Vec<Timing> {
+    let mut timings = vec![];
+    for board_notation in BENCH_BOARDS {
+        let board = parse_board(board_notation).expect("the bench boards are valid");
+        for search in BENCH_SEARCHES {
+            let strategy = Strategy::from_name(search).expect("the bench searches exist");
+            let mut results: Vec<_> = (0..runs.max(1))
+                .map(|_| {
+                    strategy.solve_within(
+                        &board,
+                        None,
+                        &SearchLimits::default(),
+                        &mut Verbosity::Quiet,
+                    )
+                })
+                .collect();
+            results.sort_by_key(|(_, stats)| stats.elapsed);
+            let (outcome, stats) = results.swap_remove(results.len() / 2);
+            timings.push(Timing {
+                board: board_notation,
+                search,
+                moves: match outcome {
+                    SearchOutcome::Found(moves) => Some(moves.len()),
+                    _ => None,
+                },
+                nodes_expanded: stats.nodes_expanded,
+                elapsed: stats.elapsed,
+            });
+        }
+    }
+    timings
+}
+
+/// One row per board and a column per search, with the time and the length
+/// of the solution, e.g. `12.3 ms (16)`.
+pub fn comparison_table(timings: &[Timing]) -> Vec<String> {
+    let board_width = BENCH_BOARDS.iter().map(|board| board.len()).max().unwrap();
+    let mut lines = vec![format!(
+        "{:board_width$}{}",
+        "board",
+        BENCH_SEARCHES
+            .iter()
+            .map(|search| format!("  {:>16}", search))
+            .collect::<String>()
+    )];
+    for board in BENCH_BOARDS {
+        let cells: String = BENCH_SEARCHES
+            .iter()
+            .map(|search| {
+                let cell = timings
+                    .iter()
+                    .find(|timing| timing.board == board && timing.search == *search)
+                    .map_or("-".to_string(), |timing| {
+                        format!(
+                            "{:.1} ms ({})",
+                            timing.elapsed.as_secs_f64() * 1000.0,
+                            timing
+                                .moves
+                                .map_or("unsolved".to_string(), |moves| moves.to_string())
+                        )
+                    });
+                format!("  {:>16}", cell)
+            })
+            .collect();
+        lines.push(format!("{:board_width$}{}", board, cells));
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_comparison_table() {
+        let timings = [
+            Timing {
+                board: BENCH_BOARDS[0],
+                search: "bfs",
+                moves: Some(2),
+                nodes_expanded: 2,
+                elapsed: Duration::from_micros(25),
+            },
+            Timing {
+                board: BENCH_BOARDS[2],
+                search: "astar",
+                moves: Some(30),
+                nodes_expanded: 38938,
+                elapsed: Duration::from_millis(1813),
+            },
+        ];
+        assert_eq!(
+            comparison_table(&timings),
+            vec![
+                "board                                       dfs               bfs             astar",
+                "1tl,2dd,2t,1tl,ctl,1tl,s,er,f                 -        0.0 ms (2)                 -",
+                "2t,2l,s,1tr,2r,1br,f,1bl,1bl                  -                 -                 -",
+                "1tl,s,1tr,1bl,f,1br,2t,2b,2t                  -                 -    1813.0 ms (30)",
+            ]
+        );
+    }
+}
@@ -0,0 +1,1356 @@
+// command line subcommands ---------------------------------------------------
+
+use crate::daily;
+use crate::par::ParCache;
+use crate::par::DEFAULT_SECONDS_PER_MOVE;
+use crate::report::markdown_report;
+use crate::serve;
+use crate::session;
+use crate::stats::{GameStats, StatsStore};
+use crate::tui::{
+    observe_search_via_tui, play_game_via_tui, view_replay_via_tui, Rules, Terminal,
+    DEFAULT_DEMO_DELAY, DEFAULT_HINTS,
+};
+use asteroids_cli_game::analysis::{
+    analyze, count_optimal_solutions, count_reachable, explored_graph_dot, gods_number,
+    graph_stats, Difficulty, GodsNumber,
+};
+use asteroids_cli_game::backtracking::{
+    all_solutions, CostModel, SearchLimits, SearchObserver, SearchOutcome, Strategy, Verbosity,
+};
+use asteroids_cli_game::bench::{self, comparison_table};
+use asteroids_cli_game::engine::{Engine, Response};
+use asteroids_cli_game::generator::{
+    drill, generate, generate_with_difficulty, hardest_puzzle, STANDARD_SHAPES,
+};
+use asteroids_cli_game::notation::{board_to_notation, parse_board, parse_shapes, RecordedGame};
+use asteroids_cli_game::rendering::{Highlight, Renderer, Theme};
+use asteroids_cli_game::share::{self, SharedPuzzle};
+use asteroids_cli_game::visited::{DiskVisitedSet, VisitedSet};
+use asteroids_cli_game::{format_moves, parse_moves, Board, Direction, InvalidMove, MovingTile};
+use clap::{Args, Parser};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde_json::json;
+use std::collections::HashSet;
+use std::fs;
+use std::io::{BufRead, Write};
+use std::iter;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const USAGE: &str = "usage:
+    asteroids-cli-game [--simple-input] [--screen-reader] [--mouse] [--hints <number>] [--slide-counts-once] [--assist] [--optimal-counter] [--demo-delay <milliseconds>] [--cost moves|slides] [--seconds-per-move <number>] [--record <game file>] [--quick | --code <share code> | --resume-setup]
+    asteroids-cli-game <subcommand> [--json] ...
+    asteroids-cli-game verify --board <notation> --moves <file>
+    asteroids-cli-game replay [--view [--simple-input]] <game file>
+    asteroids-cli-game play [--moves <moves>] <notation>
+    asteroids-cli-game engine
+    asteroids-cli-game serve [--port <number>]
+    asteroids-cli-game solve [--optimal | --all | --unique] [--search dfs|bfs|iddfs|astar|pdb|bidir] [--max-states <number>] [--max-moves <number>] [--max-seconds <number>] [--mirror | --cost moves|slides] [--stats] (--file <file> | <notation>)
+    asteroids-cli-game analyze (--file <file> | <notation>)
+    asteroids-cli-game rate <notation>
+    asteroids-cli-game graph-stats <notation>
+    asteroids-cli-game dot <notation>
+    asteroids-cli-game reachable [--visited-file <file>] <notation>
+    asteroids-cli-game gods-number [--visited-file <file>] <shapes>
+    asteroids-cli-game generate [--moves <number> | --difficulty easy|medium|hard|expert] [--seed <number>]
+    asteroids-cli-game generate --hardest [<shapes>]
+    asteroids-cli-game bench [--runs <number>]
+    asteroids-cli-game observe [--simple-input] <notation>
+    asteroids-cli-game drill [--moves <number>] [--count <number>] [--play] <notation>
+    asteroids-cli-game daily [--simple-input] [--archive] [<date>]
+    asteroids-cli-game stats [--export <file>]
+    asteroids-cli-game session [--simple-input] (--file <file> | --random <count> [--seed <number>])
+    asteroids-cli-game share <notation>
+    asteroids-cli-game print <notation> [--theme ascii|unicode|color] [--coordinates] [--exit]";
+
+/// Run the subcommand given on the command line, if any.
+/// Returns the exit code, or `None` if the game should be played instead.
+/// With `--json`, subcommands print JSON instead of text, including errors.
+pub fn run(args: &[String]) -> Option<i32> {
+    let (command, args) = args.split_first()?;
+    let json = flag(args, "--json");
+    let result = match command.as_str() {
+        "verify" => verify(args, json),
+        "replay" => replay_game(args, json),
+        "play" => play_moves(args, json),
+        "engine" => run_engine(),
+        "serve" => serve_engine(args),
+        "solve" => run_command(command, args),
+        "analyze" => analyze_board(args, json),
+        "rate" => rate(args, json),
+        "graph-stats" => graph(args, json),
+        "dot" => dot(args),
+        "reachable" => reachable(args, json),
+        "gods-number" => hardest(args, json),
+        "generate" => generate_puzzle(args, json),
+        "bench" => benchmark(args, json),
+        "print" => print(args),
+        "share" => share_code(args),
+        "observe" => observe(args),
+        "drill" => drills(args, json),
+        "daily" => play_daily(args),
+        "stats" => export_stats(args),
+        "session" => play_session(args),
+        "help" | "--help" | "-h" => {
+            println!("{}", USAGE);
+            Ok(0)
+        }
+        _ => return None,
+    };
+    Some(result.unwrap_or_else(|msg| {
+        if json {
+            println!("{}", json!({ "error": msg }));
+        } else {
+            eprintln!("error: {}", msg);
+        }
+        2
+    }))
+}
+
+/// Options for playing the game, i.e. if no subcommand is given.
+pub struct GameOptions {
+    /// use line mode even if raw mode is available
+    pub simple_input: bool,
+    /// announce every action in a line of text; implies line mode
+    pub screen_reader: bool,
+    /// name the shape under the mouse pointer and move tiles by clicking them
+    pub mouse: bool,
+    pub rules: Rules,
+    /// play generated puzzles instead of setting up a board
+    pub quick_play: bool,
+    /// continue the board setup that was saved last
+    pub resume_setup: bool,
+    /// play the puzzle of a share code instead of setting up a board
+    pub shared: Option<SharedPuzzle>,
+    /// file to write the game to once it is over, see `RecordedGame`
+    pub record: Option<String>,
+}
+
+pub fn game_options(args: &[String]) -> Result<GameOptions, String> {
+    Ok(GameOptions {
+        simple_input: flag(args, "--simple-input"),
+        screen_reader: flag(args, "--screen-reader"),
+        mouse: flag(args, "--mouse"),
+        rules: Rules {
+            hints: number_option(args, "--hints", DEFAULT_HINTS)?,
+            slide_counts_once: flag(args, "--slide-counts-once"),
+            seconds_per_move: number_option(args, "--seconds-per-move", DEFAULT_SECONDS_PER_MOVE)?,
+            assist: flag(args, "--assist"),
+            demo_delay: optional_number(args, "--demo-delay")?
+                .map_or(DEFAULT_DEMO_DELAY, Duration::from_millis),
+            cost_model: cost_model(args)?,
+            optimal_counter: flag(args, "--optimal-counter"),
+        },
+        quick_play: flag(args, "--quick"),
+        resume_setup: flag(args, "--resume-setup"),
+        shared: option(args, "--code")?.map(share::decode).transpose()?,
+        record: option(args, "--record")?.map(str::to_string),
+    })
+}
+
+/// The options that take a value, e.g. `--board <notation>`, of all
+/// subcommands. `option` reads no others, so that `positional` skips the
+/// values of every option it may be given.
+const VALUE_OPTIONS: [&str; 18] = [
+    "--board",
+    "--code",
+    "--cost",
+    "--count",
+    "--demo-delay",
+    "--difficulty",
+    "--export",
+    "--file",
+    "--hints",
+    "--moves",
+    "--port",
+    "--random",
+    "--record",
+    "--runs",
+    "--seconds-per-move",
+    "--seed",
+    "--theme",
+    "--visited-file",
+];
+
+/// Value of the option `name`, e.g. `--board`, if given. `name` is one of
+/// `VALUE_OPTIONS`.
+fn option<'a>(args: &'a [String], name: &str) -> Result<Option<&'a str>, String> {
+    assert!(
+        VALUE_OPTIONS.contains(&name),
+        "{} is missing from VALUE_OPTIONS",
+        name
+    );
+    match args.iter().position(|arg| arg == name) {
+        Some(position) => match args.get(position + 1) {
+            Some(value) => Ok(Some(value)),
+            None => Err(format!("missing value for {}", name)),
+        },
+        None => Ok(None),
+    }
+}
+
+/// Value of the numeric option `name`, e.g. `--hints`, or `default` if not given.
+fn number_option<T: std::str::FromStr>(
+    args: &[String],
+    name: &str,
+    default: T,
+) -> Result<T, String> {
+    Ok(optional_number(args, name)?.unwrap_or(default))
+}
+
+/// Value of the numeric option `name`, if given.
+fn optional_number<T: std::str::FromStr>(args: &[String], name: &str) -> Result<Option<T>, String> {
+    option(args, name)?
+        .map(|value| {
+            value
+                .parse()
+                .map_err(|_| format!("invalid number '{}' for {}", value, name))
+        })
+        .transpose()
+}
+
+/// Whether the flag `name`, e.g. `--exit`, is given.
+fn flag(args: &[String], name: &str) -> bool {
+    args.iter().any(|arg| arg == name)
+}
+
+/// The first argument that is not an option (or its value), e.g. the board of
+/// `rate <notation>`.
+fn positional<'a>(args: &'a [String], name: &str) -> Result<&'a str, String> {
+    optional_positional(args)
+        .ok_or_else(|| format!("missing {}; see 'asteroids-cli-game help'", name))
+}
+
+/// Like `positional`, for subcommands where it may be left out.
+fn optional_positional(args: &[String]) -> Option<&str> {
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if VALUE_OPTIONS.contains(&arg.as_str()) {
+            args.next();
+        } else if !arg.starts_with("--") {
+            return Some(arg);
+        }
+    }
+    None
+}
+
+/// The board given in notation, or in a file given with `--file`, e.g. one
+/// written by a script.
+fn board_argument(args: &[String]) -> Result<Board, String> {
+    match option(args, "--file")? {
+        Some(path) => read_board(path),
+        None => parse_board(positional(args, "<notation>")?),
+    }
+}
+
+/// The board in notation in the file at `path`.
+fn read_board(path: &str) -> Result<Board, String> {
+    let text =
+        fs::read_to_string(path).map_err(|err| format!("unable to read {}: {}", path, err))?;
+    parse_board(text.trim()).map_err(|msg| format!("invalid board in {}: {}", path, msg))
+}
+
+/// The subcommands that clap parses; the others pick their options out of the
+/// arguments with `option` and `flag`.
+#[derive(Parser)]
+#[command(
+    name = "asteroids-cli-game",
+    bin_name = "asteroids-cli-game",
+    no_binary_name = true
+)]
+enum Command {
+    /// Print a shortest solution of a board
+    Solve(SolveOptions),
+}
+
+/// Run the subcommand `command`, one of `Command`, with its arguments.
+fn run_command(command: &str, args: &[String]) -> Result<i32, String> {
+    let args = iter::once(command).chain(args.iter().map(String::as_str));
+    let command = match Command::try_parse_from(args) {
+        Ok(command) => command,
+        // --help
+        Err(err) if !err.use_stderr() => {
+            print!("{}", err);
+            return Ok(0);
+        }
+        Err(err) => {
+            let msg = err.to_string();
+            return Err(msg.trim_start_matches("error: ").trim_end().to_string());
+        }
+    };
+    match command {
+        Command::Solve(options) => solve_board(&options),
+    }
+}
+
+fn required_option<'a>(args: &'a [String], name: &str) -> Result<&'a str, String> {
+    option(args, name)?.ok_or_else(|| format!("missing {}; see 'asteroids-cli-game help'", name))
+}
+
+/// Why a sequence of moves is not a solution.
+#[derive(Debug, PartialEq)]
+enum ReplayError {
+    IllegalMove(InvalidMove),
+    NotWon,
+}
+
+/// Play the moves one after the other; they are a solution if all of them are
+/// legal and the board is won afterwards.
+fn replay(board: &Board, moves: &[Direction]) -> Result<Board, ReplayError> {
+    let board = board.apply_moves(moves).map_err(ReplayError::IllegalMove)?;
+    if board.is_won() {
+        Ok(board)
+    } else {
+        Err(ReplayError::NotWon)
+    }
+}
+
+/// Check that the moves in a file solve the board.
+/// Exits with 0 for a solution, 1 otherwise.
+fn verify(args: &[String], json: bool) -> Result<i32, String> {
+    let board = parse_board(required_option(args, "--board")?)?;
+    let path = required_option(args, "--moves")?;
+    let text =
+        fs::read_to_string(path).map_err(|err| format!("unable to read {}: {}", path, err))?;
+    let moves = parse_moves(&text)?;
+    let result = replay(&board, &moves);
+    if json {
+        let report = match result {
+            Ok(_) => json!({ "valid": true, "moves": moves.len() }),
+            Err(ReplayError::IllegalMove(error)) => json!({
+                "valid": false,
+                "error": "illegal_move",
+                "move_index": error.index,
+                "move": error.direction.to_char().to_string(),
+                "reason": error.error.to_string(),
+            }),
+            Err(ReplayError::NotWon) => json!({
+                "valid": false,
+                "error": "not_won",
+                "moves": moves.len(),
+            }),
+        };
+        println!("{}", report);
+        return Ok(if result.is_ok() { 0 } else { 1 });
+    }
+    match result {
+        Ok(_) => {
+            println!("valid solution in {} moves", moves.len());
+            Ok(0)
+        }
+        Err(ReplayError::IllegalMove(error)) => {
+            println!("{}; the solution is invalid", error);
+            Ok(1)
+        }
+        Err(ReplayError::NotWon) => {
+            println!(
+                "all {} moves are possible, but the ship does not reach the exit",
+                moves.len()
+            );
+            Ok(1)
+        }
+    }
+}
+
+/// Play the moves of a recorded game, e.g. one written with `--record`, and
+/// show the board they lead to, or with `--view` step through them. Exits
+/// with 0 if all moves are possible, won or not, and with 1 at the first one
+/// that is not.
+fn replay_game(args: &[String], json: bool) -> Result<i32, String> {
+    let path = positional(args, "<game file>")?;
+    let text =
+        fs::read_to_string(path).map_err(|err| format!("unable to read {}: {}", path, err))?;
+    let game: RecordedGame = text
+        .parse()
+        .map_err(|msg| format!("invalid game in {}: {}", path, msg))?;
+    if flag(args, "--view") {
+        let mut terminal =
+            Terminal::new(flag(args, "--simple-input")).map_err(|err| err.to_string())?;
+        terminal.check_size()?;
+        view_replay_via_tui(&mut terminal, &game).map_err(|err| err.to_string())?;
+        return Ok(if game.board.apply_moves(&game.moves).is_ok() {
+            0
+        } else {
+            1
+        });
+    }
+    Ok(report_played(&game.board, &game.moves, json))
+}
+
+/// Play the moves given with `--moves`, or else read from standard input, on
+/// the board and show the board they lead to, e.g. in scripts. Exits with 0
+/// if all moves are possible, won or not, and with 1 at the first one that
+/// is not.
+fn play_moves(args: &[String], json: bool) -> Result<i32, String> {
+    let board = parse_board(positional(args, "<notation>")?)?;
+    let moves = match option(args, "--moves")? {
+        Some(moves) => moves.to_string(),
+        None => std::io::read_to_string(std::io::stdin())
+            .map_err(|err| format!("unable to read the moves: {}", err))?,
+    };
+    Ok(report_played(&board, &parse_moves(&moves)?, json))
+}
+
+/// Answer the commands of the engine protocol, one per line on standard
+/// input, until `quit` or the end of the input.
+fn run_engine() -> Result<i32, String> {
+    let mut engine = Engine::new();
+    let mut stdout = std::io::stdout();
+    for line in std::io::stdin().lock().lines() {
+        let line = line.map_err(|err| format!("unable to read a command: {}", err))?;
+        let response = engine.respond(&line);
+        // the other end waits for each response
+        writeln!(stdout, "{}", response)
+            .and_then(|_| stdout.flush())
+            .map_err(|err| format!("unable to respond: {}", err))?;
+        if response == Response::Bye {
+            break;
+        }
+    }
+    Ok(0)
+}
+
+/// Serve the engine over JSON-RPC until the process is stopped.
+fn serve_engine(args: &[String]) -> Result<i32, String> {
+    serve::serve(number_option(args, "--port", serve::DEFAULT_PORT)?)
+        .map_err(|err| format!("unable to serve: {}", err))?;
+    Ok(0)
+}
+
+/// Print the board after the moves, or the first move that is not possible,
+/// and return the exit code for it.
+fn report_played(board: &Board, moves: &[Direction], json: bool) -> i32 {
+    let result = board.apply_moves(moves);
+    if json {
+        let report = match &result {
+            Ok(board) => json!({
+                "valid": true,
+                "moves": moves.len(),
+                "won": board.is_won(),
+                "board": board_to_notation(board),
+            }),
+            Err(error) => json!({
+                "valid": false,
+                "error": "illegal_move",
+                "move_index": error.index,
+                "move": error.direction.to_char().to_string(),
+                "reason": error.error.to_string(),
+            }),
+        };
+        println!("{}", report);
+        return if result.is_ok() { 0 } else { 1 };
+    }
+    match result {
+        Ok(board) => {
+            println!("{}", board);
+            if board.is_won() {
+                println!("all {} moves are possible and win", moves.len());
+            } else {
+                println!(
+                    "all {} moves are possible; the ship has not reached the exit yet",
+                    moves.len()
+                );
+            }
+            0
+        }
+        Err(error) => {
+            println!("{}", error);
+            1
+        }
+    }
+}
+
+/// Where `solve --search pdb` caches its distance tables:
+/// `$ASTEROIDS_PATTERNS` if set, otherwise a file next to the statistics.
+fn patterns_path() -> Option<PathBuf> {
+    match std::env::var_os("ASTEROIDS_PATTERNS") {
+        Some(path) => Some(PathBuf::from(path)),
+        None => std::env::var_os("HOME").map(|home| {
+            PathBuf::from(home)
+                .join(".asteroids-cli-game")
+                .join("patterns.tsv")
+        }),
+    }
+}
+
+/// The cost model given with `--cost`, by default `moves`.
+fn cost_model(args: &[String]) -> Result<CostModel, String> {
+    match option(args, "--cost")? {
+        Some(name) => cost_model_named(name),
+        None => Ok(CostModel::Moves),
+    }
+}
+
+fn cost_model_named(name: &str) -> Result<CostModel, String> {
+    CostModel::from_name(name).ok_or(format!("unknown cost '{}'; use moves or slides", name))
+}
+
+fn strategy_named(name: &str) -> Result<Strategy, String> {
+    Strategy::from_name(name).ok_or(format!(
+        "unknown search '{}'; use dfs, bfs, iddfs, astar, pdb or bidir",
+        name
+    ))
+}
+
+/// Shows on stderr how far the search for a solution got.
+struct Progress;
+
+impl SearchObserver<Board> for Progress {
+    fn on_iteration(&mut self, depth_limit: usize) {
+        eprintln!("Searching solutions of {} moves ...", depth_limit);
+    }
+}
+
+/// Options of `solve`, see `solve_board`.
+#[derive(Args)]
+struct SolveOptions {
+    /// print only the number of moves of a shortest solution
+    #[arg(long, conflicts_with_all = ["all", "unique"])]
+    optimal: bool,
+    /// print every solution as soon as it is found
+    #[arg(long, conflicts_with = "unique")]
+    all: bool,
+    /// tell whether the shortest solution is the only one
+    #[arg(long)]
+    unique: bool,
+    /// the search strategy
+    #[arg(
+        long,
+        value_name = "dfs|bfs|iddfs|astar|pdb|bidir",
+        default_value = "bfs",
+        value_parser = strategy_named
+    )]
+    search: Strategy,
+    /// give up after expanding this many states
+    #[arg(long, value_name = "number")]
+    max_states: Option<usize>,
+    /// give up on solutions longer than this
+    #[arg(long, value_name = "number")]
+    max_moves: Option<usize>,
+    /// give up after this many seconds
+    #[arg(long, value_name = "number")]
+    max_seconds: Option<u64>,
+    /// treat mirror images as the same position
+    #[arg(long)]
+    mirror: bool,
+    /// whether a slide of several tiles counts as one move
+    #[arg(
+        long,
+        value_name = "moves|slides",
+        default_value = "moves",
+        value_parser = cost_model_named
+    )]
+    cost: CostModel,
+    /// report what the search took
+    #[arg(long)]
+    stats: bool,
+    /// print JSON instead of text
+    #[arg(long)]
+    json: bool,
+    /// read the board from a file instead
+    #[arg(long, value_name = "file", conflicts_with = "notation")]
+    file: Option<String>,
+    /// the board, e.g. 1tl,2dd,2t,1tl,ctl,1tl,s,er,f
+    #[arg(value_name = "notation", required_unless_present = "file")]
+    notation: Option<String>,
+}
+
+/// Print a shortest solution of the board, given in notation or with
+/// `--file`, or with `--optimal` only its number of moves, or with `--unique`
+/// whether it is the only one, see `check_unique`. `--search` picks the
+/// search strategy; `pdb` computes distance tables of the shapes on first
+/// use and caches them. The solution of `dfs` is the first one found, not
+/// necessarily a shortest one, so it cannot be combined with `--optimal`, and
+/// its JSON has `moves_count` instead of `optimal_moves`. `--stats` reports
+/// what the search took, on stderr unless the output is JSON. The search
+/// gives up at the limits given with `--max-states`, `--max-moves` and
+/// `--max-seconds`. `--mirror` treats mirror images as the same position,
+/// which halves the positions to search. With `--cost slides`, the solution is one with the
+/// fewest moves when a slide counts as one move. Exits with 1 if there is no
+/// solution and with 3 if the search gave up.
+fn solve_board(options: &SolveOptions) -> Result<i32, String> {
+    let json = options.json;
+    let board = match &options.file {
+        Some(path) => read_board(path)?,
+        None => parse_board(options.notation.as_deref().unwrap_or_default())?,
+    };
+    if options.all {
+        // one line per solution, as soon as it is found
+        for moves in all_solutions(&board, options.max_moves) {
+            if json {
+                println!("{}", json!({ "moves": format_moves(&moves) }));
+            } else {
+                println!("{} moves: {}", moves.len(), format_moves(&moves));
+            }
+        }
+        return Ok(0);
+    }
+    if options.unique {
+        return check_unique(&board, json);
+    }
+    let strategy = options.search;
+    let limits = SearchLimits {
+        max_nodes: options.max_states,
+        max_depth: options.max_moves,
+        max_duration: options.max_seconds.map(Duration::from_secs),
+        ..SearchLimits::default()
+    };
+    let observer: &mut dyn SearchObserver<Board> = if json {
+        &mut Verbosity::Quiet
+    } else {
+        &mut Progress
+    };
+    if options.optimal && !strategy.finds_shortest() {
+        return Err("--optimal needs a shortest solution, which dfs may not find".to_string());
+    }
+    let cost_model = options.cost;
+    if options.mirror && cost_model != CostModel::Moves {
+        return Err("--mirror only counts moves; leave out --cost".to_string());
+    }
+    let patterns = patterns_path();
+    let patterns = patterns.as_deref();
+    let (outcome, stats) = if options.mirror {
+        strategy.solve_up_to_mirroring(&board, patterns, &limits, observer)
+    } else {
+        strategy.solve_with_cost(&board, cost_model, patterns, &limits, observer)
+    };
+    if let SearchOutcome::Aborted(limit) = outcome {
+        if json {
+            println!("{}", json!({ "aborted": limit.to_string() }));
+        } else {
+            println!("gave up: {}", limit);
+        }
+        return Ok(3);
+    }
+    let solution = outcome.found();
+    let show_stats = options.stats;
+    if json {
+        let mut report = match &solution {
+            // only a shortest solution tells the optimal number of moves
+            Some(moves) if strategy.finds_shortest() => json!({
+                "solvable": true,
+                "optimal_moves": cost_model.cost(&board, moves),
+                "moves": format_moves(moves),
+            }),
+            Some(moves) => json!({
+                "solvable": true,
+                "moves_count": cost_model.cost(&board, moves),
+                "moves": format_moves(moves),
+            }),
+            None => json!({ "solvable": false }),
+        };
+        if show_stats {
+            report["stats"] = json!({
+                "nodes_expanded": stats.nodes_expanded,
+                "max_frontier": stats.max_frontier,
+                "dead_ends": stats.dead_ends,
+                "seconds": stats.elapsed.as_secs_f64(),
+            });
+        }
+        println!("{}", report);
+        return Ok(if solution.is_some() { 0 } else { 1 });
+    }
+    if show_stats {
+        eprintln!(
+            "Expanded {} states, up to {} at once, {} dead ends, in {:.3} s",
+            stats.nodes_expanded,
+            stats.max_frontier,
+            stats.dead_ends,
+            stats.elapsed.as_secs_f64()
+        );
+    }
+    let Some(moves) = solution else {
+        println!("unsolvable");
+        return Ok(1);
+    };
+    if options.optimal {
+        println!("{}", cost_model.cost(&board, &moves));
+        return Ok(0);
+    }
+    match cost_model {
+        CostModel::Moves => println!(
+            "Solution in {} moves: {}",
+            moves.len(),
+            format_moves(&moves)
+        ),
+        CostModel::Slides => println!(
+            "Solution in {} moves, {} counting slides once: {}",
+            moves.len(),
+            cost_model.cost(&board, &moves),
+            format_moves(&moves)
+        ),
+    }
+    let mut board = board;
+    println!("{}", board);
+    for direction in &moves {
+        let highlight = Highlight::of_move(&board, direction);
+        board = board.move_free_space(direction).unwrap();
+        println!("{:?}", direction);
+        for line in Renderer::Compact.draw_highlighted_board(
+            &board,
+            &MovingTile::no_move(),
+            highlight.as_ref(),
+        ) {
+            println!("{}", line);
+        }
+        println!();
+    }
+    Ok(0)
+}
+
+/// Print a difficulty rating of the board and the metrics it is derived from.
+fn rate(args: &[String], json: bool) -> Result<i32, String> {
+    let board = parse_board(positional(args, "<notation>")?)?;
+    let analysis = analyze(&board);
+    if json {
+        let rating = json!({
+            "score": analysis.difficulty(),
+            "difficulty": analysis.difficulty_class().map(Difficulty::name),
+            "metrics": {
+                "optimal_moves": analysis.optimal_moves,
+                "optimal_lines": analysis.optimal_lines,
+                "branching_factor": (analysis.branching_factor * 100.0).round() / 100.0,
+                "reachable_positions": analysis.reachable_positions,
+            },
+        });
+        println!("{}", rating);
+        return Ok(0);
+    }
+    match (analysis.difficulty(), analysis.difficulty_class()) {
+        (Some(score), Some(difficulty)) => {
+            println!("Difficulty: {} (score {})", difficulty.name(), score)
+        }
+        _ => println!("Difficulty: none, the board cannot be solved"),
+    }
+    if let Some(moves) = analysis.optimal_moves {
+        println!("Optimal moves: {}", moves);
+        println!("Optimal solutions: {}", analysis.optimal_lines);
+    }
+    println!("Branching factor: {:.2}", analysis.branching_factor);
+    println!("Reachable positions: {}", analysis.reachable_positions);
+    Ok(0)
+}
+
+/// Print the share code of the board, with the length of its shortest
+/// solution, to play it with `--code`.
+fn share_code(args: &[String]) -> Result<i32, String> {
+    let board = parse_board(positional(args, "<notation>")?)?;
+    let optimal_moves = ParCache::open_default().optimal_moves(&board);
+    println!("{}", share::encode(&board, optimal_moves));
+    Ok(0)
+}
+
+/// Print whether the board has a single shortest solution, or else how many.
+/// Exits with 1 if there is no solution and with 4 if there are several.
+fn check_unique(board: &Board, json: bool) -> Result<i32, String> {
+    let Some((moves, solutions)) = count_optimal_solutions(board) else {
+        if json {
+            println!("{}", json!({ "solvable": false }));
+        } else {
+            println!("unsolvable");
+        }
+        return Ok(1);
+    };
+    if json {
+        println!(
+            "{}",
+            json!({
+                "solvable": true,
+                "optimal_moves": moves,
+                "optimal_solutions": solutions,
+                "unique": solutions == 1,
+            })
+        );
+    } else if solutions == 1 {
+        println!("Unique solution in {} moves", moves);
+    } else {
+        println!("{} different solutions in {} moves", solutions, moves);
+    }
+    Ok(if solutions == 1 { 0 } else { 4 })
+}
+
+/// Print what a search of all positions reachable from the board, given in
+/// notation or with `--file`, finds: whether and in how many moves it can be
+/// solved, in how many ways, and how many moves are legal in the positions.
+fn analyze_board(args: &[String], json: bool) -> Result<i32, String> {
+    let board = board_argument(args)?;
+    let analysis = analyze(&board);
+    let histogram = analysis.legal_moves_histogram;
+    // the fewest and most legal moves in any reachable position
+    let least = histogram.iter().position(|&count| count > 0).unwrap();
+    let most = histogram.iter().rposition(|&count| count > 0).unwrap();
+    if json {
+        println!(
+            "{}",
+            json!({
+                "solvable": analysis.optimal_moves.is_some(),
+                "optimal_moves": analysis.optimal_moves,
+                "optimal_solutions": analysis.optimal_lines,
+                "reachable_positions": analysis.reachable_positions,
+                "branching_factor": {
+                    "mean": (analysis.branching_factor * 100.0).round() / 100.0,
+                    "min": least,
+                    "max": most,
+                    "histogram": histogram,
+                },
+            })
+        );
+        return Ok(0);
+    }
+    match analysis.optimal_moves {
+        Some(moves) => {
+            println!("Solvable: yes");
+            println!("Optimal moves: {}", moves);
+            println!("Optimal solutions: {}", analysis.optimal_lines);
+        }
+        None => println!("Solvable: no"),
+    }
+    println!("Reachable positions: {}", analysis.reachable_positions);
+    println!(
+        "Branching factor: {:.2} on average, {} to {}",
+        analysis.branching_factor, least, most
+    );
+    for (moves, count) in histogram
+        .iter()
+        .enumerate()
+        .skip(least)
+        .take(most - least + 1)
+    {
+        println!("  {} legal moves: {} positions", moves, count);
+    }
+    Ok(0)
+}
+
+/// Print statistics of the graph of all positions reachable from the board,
+/// e.g. for visualizations.
+fn graph(args: &[String], json: bool) -> Result<i32, String> {
+    let board = parse_board(positional(args, "<notation>")?)?;
+    let stats = graph_stats(&board);
+    if json {
+        let report = json!({
+            "nodes": stats.nodes,
+            "edges": stats.edges,
+            "diameter": stats.diameter,
+            "distance_histogram": stats.distance_histogram,
+            "won_states": stats.won_states,
+        });
+        println!("{}", report);
+        return Ok(0);
+    }
+    println!("Positions: {}", stats.nodes);
+    println!("Moves between them: {}", stats.edges);
+    println!("Diameter: {}", stats.diameter);
+    println!("Won positions: {}", stats.won_states);
+    println!("Positions by distance from the start:");
+    for (moves, count) in stats.distance_histogram.iter().enumerate() {
+        println!("  {} moves: {}", moves, count);
+    }
+    Ok(0)
+}
+
+/// Print the positions that the search for a solution explores, and the moves
+/// between them, in Graphviz DOT.
+fn dot(args: &[String]) -> Result<i32, String> {
+    let board = parse_board(positional(args, "<notation>")?)?;
+    println!("{}", explored_graph_dot(&board));
+    Ok(0)
+}
+
+/// Where an analysis keeps the positions it has seen: in the file given with
+/// `--visited-file`, for more positions than fit into memory, otherwise in
+/// memory.
+fn visited_set(args: &[String]) -> Result<Box<dyn VisitedSet>, String> {
+    match option(args, "--visited-file")? {
+        Some(path) => match DiskVisitedSet::create(Path::new(path)) {
+            Ok(visited) => Ok(Box::new(visited)),
+            Err(err) => Err(format!("unable to create {}: {}", path, err)),
+        },
+        None => Ok(Box::new(HashSet::<Board>::new())),
+    }
+}
+
+/// Print how many positions can be reached from the board, and how many of
+/// them are won. See `visited_set` for `--visited-file`.
+fn reachable(args: &[String], json: bool) -> Result<i32, String> {
+    let board = parse_board(positional(args, "<notation>")?)?;
+    let reachable =
+        count_reachable(&board, &mut *visited_set(args)?).map_err(|err| err.to_string())?;
+    if json {
+        println!(
+            "{}",
+            json!({ "positions": reachable.positions, "won": reachable.won })
+        );
+    } else {
+        println!(
+            "{} reachable positions, {} of them won",
+            reachable.positions, reachable.won
+        );
+    }
+    Ok(0)
+}
+
+/// Print the longest shortest solution of any arrangement of the shapes, given
+/// like a board, and a board that takes that long. See `visited_set` for
+/// `--visited-file`.
+fn hardest(args: &[String], json: bool) -> Result<i32, String> {
+    let shapes = parse_shapes(positional(args, "<shapes>")?)?;
+    let result = gods_number(&shapes, &mut *visited_set(args)?)?;
+    print_gods_number(&result, json);
+    Ok(0)
+}
+
+fn print_gods_number(result: &GodsNumber, json: bool) {
+    if json {
+        println!(
+            "{}",
+            json!({
+                "boards": result.boards,
+                "solvable": result.solvable,
+                "gods_number": result.hardest.map(|(_, moves)| moves),
+                "example": result.hardest.map(|(board, _)| board_to_notation(&board)),
+            })
+        );
+        return;
+    }
+    println!(
+        "{} arrangements, {} of them solvable and not won yet",
+        result.boards, result.solvable
+    );
+    match result.hardest {
+        Some((board, moves)) => {
+            println!(
+                "God's number: {}, e.g. {}",
+                moves,
+                board_to_notation(&board)
+            );
+            println!("{}", board);
+        }
+        None => println!("No arrangement is solvable."),
+    }
+}
+
+/// Print a random puzzle whose shortest solution takes `--moves` moves, the
+/// same one for the same `--seed`. With `--hardest`, print the hardest puzzle
+/// that the shapes, by default those of the game, can be set up as with the
+/// tiles turned any way, see `hardest_puzzle`.
+fn generate_puzzle(args: &[String], json: bool) -> Result<i32, String> {
+    if flag(args, "--hardest") {
+        let shapes = match optional_positional(args) {
+            Some(shapes) => parse_shapes(shapes)?,
+            None => STANDARD_SHAPES.to_vec(),
+        };
+        let result = hardest_puzzle(&shapes, &mut |done, total| {
+            if !json {
+                eprint!("\rSearched {} of {} orientations", done, total);
+                if done == total {
+                    eprintln!();
+                }
+            }
+        })?;
+        print_gods_number(&result, json);
+        return Ok(0);
+    }
+    let seed = number_option(args, "--seed", GameStats::now())?;
+    let mut rng = StdRng::seed_from_u64(seed);
+    let (board, moves) = match option(args, "--difficulty")? {
+        Some(name) => {
+            let difficulty = Difficulty::from_name(name).ok_or(format!(
+                "unknown difficulty '{}'; use easy, medium, hard or expert",
+                name
+            ))?;
+            generate_with_difficulty(difficulty, &HashSet::new(), &mut rng)
+        }
+        None => {
+            let moves = number_option(args, "--moves", 10)?;
+            if moves == 0 {
+                return Err("puzzles take at least one move".to_string());
+            }
+            generate(moves, &HashSet::new(), &mut rng)
+        }
+    };
+    let difficulty = analyze(&board).difficulty_class().map(Difficulty::name);
+    if json {
+        println!(
+            "{}",
+            json!({
+                "board": board_to_notation(&board),
+                "optimal_moves": moves,
+                "difficulty": difficulty,
+            })
+        );
+    } else {
+        println!(
+            "{} ({} moves, {})",
+            board_to_notation(&board),
+            moves,
+            difficulty.unwrap_or("unsolvable")
+        );
+    }
+    Ok(0)
+}
+
+/// Show the board without starting a game.
+fn print(args: &[String]) -> Result<i32, String> {
+    let board = parse_board(positional(args, "<notation>")?)?;
+    let theme = match option(args, "--theme")? {
+        Some(name) => Theme::from_name(name).ok_or(format!(
+            "unknown theme '{}'; use ascii, unicode or color",
+            name
+        ))?,
+        None => Theme::Unicode,
+    };
+    for line in theme.draw_board(&board, flag(args, "--coordinates"), flag(args, "--exit")) {
+        println!("{}", line);
+    }
+    Ok(0)
+}
+
+/// Watch the solver search for a solution of the board, step by step, and
+/// explore its frontier and dead ends.
+fn observe(args: &[String]) -> Result<i32, String> {
+    let board = parse_board(positional(args, "<notation>")?)?;
+    let mut terminal =
+        Terminal::new(flag(args, "--simple-input")).map_err(|err| err.to_string())?;
+    terminal.check_size()?;
+    observe_search_via_tui(&mut terminal, board).map_err(|err| err.to_string())?;
+    Ok(0)
+}
+
+/// Exercises that are won in a given number of moves, generated from a won
+/// position. With `--play`, they are played one after the other.
+fn drills(args: &[String], json: bool) -> Result<i32, String> {
+    let won = parse_board(positional(args, "<notation>")?)?;
+    if !won.is_won() {
+        return Err("the board is not won; drills start from a won position".to_string());
+    }
+    let moves = number_option(args, "--moves", 4)?;
+    let count = number_option(args, "--count", 1)?;
+    if moves == 0 {
+        return Err("drills take at least one move".to_string());
+    }
+    let mut rng = rand::thread_rng();
+    let boards = (0..count)
+        .map(|_| drill(&won, moves, &mut rng))
+        .collect::<Option<Vec<Board>>>()
+        .ok_or(format!("no position is {} moves away from the exit", moves))?;
+
+    if flag(args, "--play") {
+        let mut terminal =
+            Terminal::new(flag(args, "--simple-input")).map_err(|err| err.to_string())?;
+        terminal.check_size()?;
+        let mut results = vec![];
+        for board in &boards {
+            let rules = Rules {
+                hints: 0,
+                ..Rules::default()
+            };
+            let record = play_game_via_tui(&mut terminal, *board, rules, Some(moves))
+                .map_err(|err| err.to_string())?;
+            let won = record.history.last().unwrap().is_won();
+            results.push((won, record.moves));
+            if !won {
+                break;
+            }
+        }
+        drop(terminal);
+        for (index, (won, played)) in results.iter().enumerate() {
+            if *won {
+                println!(
+                    "Drill {}: won in {} moves (minimum {})",
+                    index + 1,
+                    played,
+                    moves
+                );
+            } else {
+                println!("Drill {}: not won", index + 1);
+            }
+        }
+        return Ok(0);
+    }
+
+    if json {
+        let drills: Vec<String> = boards.iter().map(board_to_notation).collect();
+        println!("{}", json!({ "moves": moves, "drills": drills }));
+    } else {
+        for board in &boards {
+            println!("Win in {}: {}", moves, board_to_notation(board));
+        }
+    }
+    Ok(0)
+}
+
+/// Play the daily puzzle of today or of the given date, or show the calendar
+/// of the past ones with `--archive`.
+fn play_daily(args: &[String]) -> Result<i32, String> {
+    let store = StatsStore::open_default();
+    let games = store
+        .as_ref()
+        .and_then(|store| store.load().ok())
+        .unwrap_or_default();
+    let today = daily::today();
+    if flag(args, "--archive") {
+        for line in daily::calendar(&games, today) {
+            println!("{}", line);
+        }
+        return Ok(0);
+    }
+    let day = match positional(args, "<date>") {
+        Ok(date) => daily::parse_date(date)?,
+        Err(_) => today,
+    };
+    daily::check_day(day, today)?;
+    let (board, optimal_moves) = daily::puzzle(day);
+
+    let mut terminal =
+        Terminal::new(flag(args, "--simple-input")).map_err(|err| err.to_string())?;
+    terminal.check_size()?;
+    let record = play_game_via_tui(&mut terminal, board, Rules::default(), Some(optimal_moves))
+        .map_err(|err| err.to_string())?;
+    drop(terminal);
+    let won = record.history.last().unwrap().is_won();
+    if let Some(store) = &store {
+        let game = GameStats {
+            finished_at: GameStats::now(),
+            board: board_to_notation(&board),
+            level: None,
+            optimal_moves: Some(optimal_moves),
+            moves: record.moves,
+            hints_used: record.hints_used,
+            won,
+            daily: Some(day),
+        };
+        if let Err(err) = store.append(&game) {
+            eprintln!("Unable to save the statistics: {}", err);
+        }
+    }
+    if won {
+        println!(
+            "Daily puzzle of {}: won in {} moves (minimum {})",
+            daily::format_date(day),
+            record.moves,
+            optimal_moves
+        );
+    } else {
+        println!("Daily puzzle of {}: not won", daily::format_date(day));
+    }
+    Ok(0)
+}
+
+/// Time the searches on the boards of `bench`, taking the median of `--runs`
+/// runs, 3 by default.
+fn benchmark(args: &[String], json: bool) -> Result<i32, String> {
+    let timings = bench::run(number_option(args, "--runs", 3)?);
+    if json {
+        for timing in &timings {
+            println!(
+                "{}",
+                json!({
+                    "board": timing.board,
+                    "search": timing.search,
+                    "moves": timing.moves,
+                    "nodes_expanded": timing.nodes_expanded,
+                    "seconds": timing.elapsed.as_secs_f64(),
+                })
+            );
+        }
+    } else {
+        for line in comparison_table(&timings) {
+            println!("{}", line);
+        }
+    }
+    Ok(0)
+}
+
+/// Summarize the statistics in Markdown, printed or written to the file given
+/// with `--export`.
+fn export_stats(args: &[String]) -> Result<i32, String> {
+    let store = StatsStore::open_default().ok_or("unable to locate the statistics file")?;
+    let games = store
+        .load()
+        .map_err(|err| format!("unable to read the statistics: {}", err))?;
+    let report = markdown_report(&games);
+    match option(args, "--export")? {
+        Some(path) => {
+            fs::write(path, report).map_err(|err| format!("unable to write {}: {}", path, err))?
+        }
+        None => print!("{}", report),
+    }
+    Ok(0)
+}
+
+/// Play several puzzles back to back: the boards in a file, one per line, or
+/// generated ones from consecutive seeds.
+fn play_session(args: &[String]) -> Result<i32, String> {
+    let puzzles = match option(args, "--file")? {
+        Some(path) => {
+            let text = fs::read_to_string(path)
+                .map_err(|err| format!("unable to read {}: {}", path, err))?;
+            session::parse_puzzles(&text, &mut ParCache::open_default())?
+        }
+        None => {
+            let count: usize = number_option(args, "--random", 0)?;
+            if count == 0 {
+                return Err("missing --file or --random; see 'asteroids-cli-game help'".to_string());
+            }
+            let seed = number_option(args, "--seed", GameStats::now())?;
+            session::random_puzzles(count, seed)
+        }
+    };
+    if puzzles.is_empty() {
+        return Err("there are no puzzles to play".to_string());
+    }
+    let store = StatsStore::open_default();
+    let mut terminal =
+        Terminal::new(flag(args, "--simple-input")).map_err(|err| err.to_string())?;
+    terminal.check_size()?;
+    let results = session::play(&mut terminal, &puzzles, Rules::default(), store.as_ref())
+        .map_err(|err| err.to_string())?;
+    drop(terminal);
+    for line in session::summary(&puzzles, &results) {
+        println!("{}", line);
+    }
+    Ok(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use asteroids_cli_game::sample_board;
+
+    #[test]
+    fn test_replay() {
+        let board = sample_board();
+        let solution = parse_moves("LL").unwrap();
+        assert!(replay(&board, &solution).unwrap().is_won());
+        assert_eq!(
+            replay(&board, &parse_moves("LD").unwrap()).err(),
+            Some(ReplayError::IllegalMove(InvalidMove {
+                index: 1,
+                direction: Direction::Down,
+                error: asteroids_cli_game::MoveError::OutOfBounds,
+            }))
+        );
+        assert_eq!(
+            replay(&board, &parse_moves("L").unwrap()).err(),
+            Some(ReplayError::NotWon)
+        );
+    }
+
+    #[test]
+    fn test_option() {
+        let args: Vec<String> = ["--board", "b", "--moves"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        assert_eq!(option(&args, "--board"), Ok(Some("b")));
+        assert_eq!(option(&args, "--seed"), Ok(None));
+        assert!(option(&args, "--moves").is_err());
+
+        let args: Vec<String> = ["--theme", "ascii", "--exit", "b"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        assert_eq!(positional(&args, "<notation>"), Ok("b"));
+        // the values of all options are skipped
+        for name in VALUE_OPTIONS {
+            let args: Vec<String> = [name, "9000", "b"]
+                .iter()
+                .map(|arg| arg.to_string())
+                .collect();
+            assert_eq!(positional(&args, "<notation>"), Ok("b"));
+        }
+        assert_eq!(optional_positional(&["--seed".to_string()]), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "--other is missing from VALUE_OPTIONS")]
+    fn test_unknown_option() {
+        let _ = option(&[], "--other");
+    }
+
+    #[test]
+    fn test_solve_options() {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        let notation = "1tl,2dd,2t,1tl,ctl,1tl,s,er,f";
+        let Ok(Command::Solve(options)) =
+            Command::try_parse_from(["solve", "--search", "iddfs", "--max-moves", "9", notation])
+        else {
+            panic!("solve options rejected");
+        };
+        assert_eq!(options.search, Strategy::IterativeDeepening);
+        assert_eq!(options.max_moves, Some(9));
+        assert_eq!(options.notation.as_deref(), Some(notation));
+        assert!(run_command("solve", &args(&["--max-moves", "nine", notation])).is_err());
+        assert!(run_command("solve", &args(&["--file", "board.txt", notation])).is_err());
+        assert!(run_command("solve", &args(&["--json"])).is_err());
+        assert!(run_command("solve", &args(&["--optimal", "--search", "dfs", notation])).is_err());
+        assert_eq!(
+            run_command(
+                "solve",
+                &args(&["--optimal", "--search", "iddfs", notation])
+            ),
+            Ok(0)
+        );
+    }
+
+    #[test]
+    fn test_code_option() {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        let code = share::encode(&sample_board(), Some(2));
+        let options = game_options(&args(&["--code", &code])).unwrap();
+        assert_eq!(
+            options.shared.map(|shared| shared.board),
+            Some(sample_board())
+        );
+        assert!(game_options(&args(&["--code", "nonsense"])).is_err());
+    }
+
+    #[test]
+    fn test_board_argument() {
+        let path = std::env::temp_dir().join(format!("asteroids-board-{}.txt", std::process::id()));
+        fs::write(&path, "1tl,2dd,2t,1tl,ctl,1tl,s,er,f\n").unwrap();
+        let args: Vec<String> = ["--optimal", "--file", path.to_str().unwrap()]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        assert_eq!(board_argument(&args), Ok(sample_board()));
+        assert_eq!(
+            board_argument(&args[..1]).err(),
+            Some("missing <notation>; see 'asteroids-cli-game help'".to_string())
+        );
+        fs::remove_file(&path).unwrap();
+        assert!(board_argument(&args)
+            .unwrap_err()
+            .starts_with("unable to read"));
+    }
+
+    #[test]
+    fn test_play_moves() {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        let board = "1tl,2dd,2t,1tl,ctl,1tl,s,er,f";
+        assert_eq!(play_moves(&args(&["--moves", "LL", board]), false), Ok(0));
+        assert_eq!(play_moves(&args(&["--moves", "L", board]), true), Ok(0));
+        assert_eq!(play_moves(&args(&["--moves", "LLLL", board]), false), Ok(1));
+        assert_eq!(
+            play_moves(&args(&["--moves", "LX", board]), false),
+            Err("invalid move 'X' at position 2; use U, D, L or R".to_string())
+        );
+    }
+
+    #[test]
+    fn test_replay_game() {
+        let path = std::env::temp_dir().join(format!("asteroids-game-{}.txt", std::process::id()));
+        let args = vec![path.to_str().unwrap().to_string()];
+        fs::write(&path, "board: 1tl,2dd,2t,1tl,ctl,1tl,s,er,f\nmoves: L\n").unwrap();
+        assert_eq!(replay_game(&args, false), Ok(0));
+        fs::write(&path, "board: 1tl,2dd,2t,1tl,ctl,1tl,s,er,f\nmoves: R\n").unwrap();
+        assert_eq!(replay_game(&args, true), Ok(1));
+        fs::write(&path, "moves: LL\n").unwrap();
+        assert!(replay_game(&args, false)
+            .unwrap_err()
+            .starts_with("invalid game in"));
+        fs::remove_file(&path).unwrap();
+    }
+}
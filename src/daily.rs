@@ -0,0 +1,225 @@
+// daily puzzles --------------------------------------------------------------
+//
+// One generated puzzle per day, the same for everybody: the day, counted since
+// the Unix epoch, seeds the generator. Past days stay playable.
+
+use crate::stats::GameStats;
+use asteroids_cli_game::generator::generate;
+use asteroids_cli_game::Board;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::collections::HashSet;
+
+/// Length of the shortest solution of the daily puzzles.
+const DAILY_MOVES: usize = 12;
+
+/// The day of the first daily puzzle.
+const FIRST_DAY: u64 = days_from_civil(2026, 1, 1);
+
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+/// Days since the Unix epoch of a date of the proleptic Gregorian calendar.
+const fn days_from_civil(year: i64, month: i64, day: i64) -> u64 {
+    // years start in March, so that the leap day is the last day of the year
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    (era * 146097 + day_of_era - 719468) as u64
+}
+
+/// Year, month and day of the days since the Unix epoch.
+fn civil_from_days(days: u64) -> (i64, i64, i64) {
+    let days = days as i64 + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400;
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
+
+/// Day of the week, 0 for Monday.
+fn weekday(day: u64) -> u64 {
+    // the epoch was a Thursday
+    (day + 3) % 7
+}
+
+/// The current day, in UTC.
+pub fn today() -> u64 {
+    GameStats::now() / (24 * 60 * 60)
+}
+
+/// Parse a date like `2026-10-16`.
+pub fn parse_date(text: &str) -> Result<u64, String> {
+    let invalid = || format!("invalid date '{}'; expected e.g. 2026-10-16", text);
+    let fields: Vec<i64> = text
+        .split('-')
+        .map(|field| field.parse().map_err(|_| invalid()))
+        .collect::<Result<_, _>>()?;
+    let [year, month, day] = fields[..] else {
+        return Err(invalid());
+    };
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || year < 1970 {
+        return Err(invalid());
+    }
+    let days = days_from_civil(year, month, day);
+    // e.g. the 31st of a shorter month
+    if civil_from_days(days) != (year, month, day) {
+        return Err(invalid());
+    }
+    Ok(days)
+}
+
+pub fn format_date(day: u64) -> String {
+    let (year, month, day) = civil_from_days(day);
+    format!("{}-{:02}-{:02}", year, month, day)
+}
+
+/// Check that there is a daily puzzle for the day.
+pub fn check_day(day: u64, today: u64) -> Result<(), String> {
+    if day < FIRST_DAY {
+        Err(format!(
+            "the first daily puzzle is the one of {}",
+            format_date(FIRST_DAY)
+        ))
+    } else if day > today {
+        Err(format!(
+            "the daily puzzle of {} is not out yet",
+            format_date(day)
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// The puzzle of the day and the length of its shortest solution.
+pub fn puzzle(day: u64) -> (Board, usize) {
+    let mut rng = StdRng::seed_from_u64(day);
+    generate(DAILY_MOVES, &HashSet::new(), &mut rng)
+}
+
+/// Calendar of all daily puzzles up to `today`, month by month, with the
+/// days marked that were won or played according to `games`.
+pub fn calendar(games: &[GameStats], today: u64) -> Vec<String> {
+    let mark = |day| {
+        let played: Vec<&GameStats> = games
+            .iter()
+            .filter(|game| game.daily == Some(day))
+            .collect();
+        if played.iter().any(|game| game.won) {
+            '*'
+        } else if !played.is_empty() {
+            '-'
+        } else {
+            ' '
+        }
+    };
+    let mut lines = vec![];
+    let mut week = String::new();
+    for day in FIRST_DAY..=today {
+        let (year, month, day_of_month) = civil_from_days(day);
+        if day_of_month == 1 || day == FIRST_DAY {
+            if !week.is_empty() {
+                lines.push(week.trim_end().to_string());
+            }
+            if !lines.is_empty() {
+                lines.push(String::new());
+            }
+            lines.push(format!("{} {}", MONTHS[month as usize - 1], year));
+            lines.push("Mo  Tu  We  Th  Fr  Sa  Su".to_string());
+            week = "    ".repeat(weekday(day) as usize);
+        } else if weekday(day) == 0 {
+            lines.push(week.trim_end().to_string());
+            week.clear();
+        }
+        week.push_str(&format!("{:>2}{} ", day_of_month, mark(day)));
+    }
+    if !week.is_empty() {
+        lines.push(week.trim_end().to_string());
+    }
+    lines.push(String::new());
+    lines.push("* won, - played".to_string());
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dates() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(format_date(FIRST_DAY), "2026-01-01");
+        assert_eq!(
+            parse_date("2024-02-29"),
+            Ok(days_from_civil(2024, 3, 1) - 1)
+        );
+        assert_eq!(format_date(parse_date("2026-10-16").unwrap()), "2026-10-16");
+        assert!(parse_date("2026-02-29").is_err());
+        assert!(parse_date("yesterday").is_err());
+        // a Thursday
+        assert_eq!(weekday(FIRST_DAY), 3);
+        assert!(check_day(FIRST_DAY - 1, FIRST_DAY).is_err());
+        assert!(check_day(FIRST_DAY + 1, FIRST_DAY).is_err());
+    }
+
+    #[test]
+    fn test_calendar() {
+        let game = |day, won| GameStats {
+            finished_at: 0,
+            board: String::new(),
+            level: None,
+            optimal_moves: None,
+            moves: 0,
+            hints_used: 0,
+            won,
+            daily: Some(day),
+        };
+        let games = [
+            game(FIRST_DAY, false),
+            game(FIRST_DAY + 1, false),
+            game(FIRST_DAY + 1, true),
+        ];
+        let lines = calendar(&games, FIRST_DAY + 32);
+        assert_eq!(lines[0], "January 2026");
+        assert_eq!(lines[2], "             1-  2*  3   4");
+        assert_eq!(lines[3], " 5   6   7   8   9  10  11");
+        assert_eq!(lines[8], "February 2026");
+        assert_eq!(lines[10], "                         1");
+        assert_eq!(lines[11], " 2");
+        assert_eq!(lines.last().unwrap(), "* won, - played");
+    }
+
+    #[test]
+    fn test_puzzle() {
+        let (board, optimal_moves) = puzzle(FIRST_DAY);
+        assert_eq!(puzzle(FIRST_DAY).0, board);
+        assert_eq!(
+            asteroids_cli_game::analysis::solve(&board).unwrap().len(),
+            optimal_moves
+        );
+    }
+}
@@ -0,0 +1,202 @@
+// announcements for screen readers -------------------------------------------
+//
+// One line of text per action, so that the game can be followed without
+// seeing the board.
+
+use asteroids_cli_game::{Board, BoardIndex2d, Direction, Shape, ShapeFamily};
+
+/// The position the ship leaves the board from.
+const IN_FRONT_OF_EXIT: BoardIndex2d = BoardIndex2d { x: 1, y: 2 };
+
+/// Small numbers as words, as they are easier to listen to.
+fn count(number: i32, unit: &str) -> String {
+    let word = match number {
+        1 => "one".to_string(),
+        2 => "two".to_string(),
+        _ => number.to_string(),
+    };
+    if number == 1 {
+        format!("{} {}", word, unit)
+    } else {
+        format!("{} {}s", word, unit)
+    }
+}
+
+fn direction_name(direction: &Direction) -> &'static str {
+    match direction {
+        Direction::Up => "up",
+        Direction::Down => "down",
+        Direction::Left => "left",
+        Direction::Right => "right",
+    }
+}
+
+/// What a tile is called in explanations, without its orientation.
+fn piece_name(shape: Shape) -> &'static str {
+    match shape.family() {
+        ShapeFamily::Free => "free space",
+        ShapeFamily::Ship => "ship",
+        ShapeFamily::Single => "single piece",
+        ShapeFamily::Diagonal => "diagonal piece",
+        ShapeFamily::Double => "double piece",
+        ShapeFamily::LargeEdge => "large edge piece",
+        ShapeFamily::LargeCorner => "large corner piece",
+    }
+}
+
+/// The tile that moves when the free space moves in `direction`, and where
+/// to; `None` if the move is not possible.
+fn moving_piece(board: &Board, direction: &Direction) -> Option<(&'static str, Board)> {
+    let tile = board.find_free_space().neighbor(direction)?;
+    let next = board.move_free_space(direction).ok()?;
+    Some((piece_name(board.shapes[tile.to_index()]), next))
+}
+
+/// Why the first of the moves, a shortest solution, helps, from what it and
+/// the next two moves do, e.g. "the single piece moves down and the large
+/// corner piece can move left next turn". `None` if there are no moves.
+pub fn explain_hint(board: &Board, moves: &[Direction]) -> Option<String> {
+    let (first, after_first) = moving_piece(board, moves.first()?)?;
+    let first_direction = direction_name(&moves[0].opposite());
+    if after_first.is_won() {
+        return Some(if first == "ship" {
+            format!("the ship moves {} to the exit", first_direction)
+        } else {
+            format!(
+                "the {} moves {} out of the ship's way",
+                first, first_direction
+            )
+        });
+    }
+    let mut explanation = format!("the {} moves {}", first, first_direction);
+    let Some((second, after_second)) = moves
+        .get(1)
+        .and_then(|direction| moving_piece(&after_first, direction))
+    else {
+        return Some(explanation);
+    };
+    let second_direction = direction_name(&moves[1].opposite());
+    explanation += &if after_second.is_won() && second == "ship" {
+        format!(
+            " and the ship can move {} to the exit next turn",
+            second_direction
+        )
+    } else if after_second.is_won() {
+        format!(
+            " and the {} can move {} out of the ship's way next turn",
+            second, second_direction
+        )
+    } else {
+        format!(
+            " and the {} can move {} next turn",
+            second, second_direction
+        )
+    };
+    if let Some(("ship", _)) = moves
+        .get(2)
+        .and_then(|direction| moving_piece(&after_second, direction))
+    {
+        explanation += &format!(
+            ", making way for the ship to move {}",
+            direction_name(&moves[2].opposite())
+        );
+    }
+    Some(explanation)
+}
+
+/// Where the ship is, relative to the exit.
+pub fn describe_ship(board: &Board) -> String {
+    let Some(index) = board.shapes.iter().position(|&shape| shape == Shape::Ship) else {
+        return "there is no ship".to_string();
+    };
+    let ship = BoardIndex2d::from_index(index);
+    let rows = IN_FRONT_OF_EXIT.y - ship.y;
+    let columns = (IN_FRONT_OF_EXIT.x - ship.x).abs();
+    match (rows, columns) {
+        (0, 0) if board.is_won() => "ship is at the exit".to_string(),
+        (0, 0) => "ship is in front of the exit, but blocked".to_string(),
+        (rows, 0) => format!("ship is {} from the exit", count(rows, "row")),
+        (0, columns) => format!("ship is {} from the exit", count(columns, "column")),
+        (rows, columns) => format!(
+            "ship is {} and {} from the exit",
+            count(rows, "row"),
+            count(columns, "column")
+        ),
+    }
+}
+
+/// Announcement after the free space moved in `direction`, leading to `board`
+/// at move number `moves`.
+pub fn describe_move(direction: &Direction, moves: usize, board: &Board) -> String {
+    format!(
+        "free space moved {}; move {}; {}",
+        direction_name(direction),
+        moves,
+        describe_ship(board)
+    )
+}
+
+/// Announcement after something other than a move led to `board`, e.g.
+/// `"move undone"`.
+pub fn describe_step(what: &str, moves: usize, board: &Board) -> String {
+    format!("{}; move {}; {}", what, moves, describe_ship(board))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use asteroids_cli_game::sample_board;
+
+    #[test]
+    fn test_describe_ship() {
+        let board = sample_board();
+        assert_eq!(describe_ship(&board), "ship is one column from the exit");
+        let left = board.move_free_space(&Direction::Left).unwrap();
+        let won = left.move_free_space(&Direction::Left).unwrap();
+        assert_eq!(describe_ship(&won), "ship is at the exit");
+
+        let mut far = Board::empty_board();
+        far.shapes[8] = Shape::Free;
+        far.shapes[0] = Shape::Ship;
+        assert_eq!(
+            describe_ship(&far),
+            "ship is two rows and one column from the exit"
+        );
+    }
+
+    #[test]
+    fn test_explain_hint() {
+        use Direction::*;
+        let board = sample_board();
+        assert_eq!(
+            explain_hint(&board, &[Left, Left]).unwrap(),
+            "the large edge piece moves right and the ship can move right to the exit next turn"
+        );
+        let left = board.move_free_space(&Left).unwrap();
+        assert_eq!(
+            explain_hint(&left, &[Left]).unwrap(),
+            "the ship moves right to the exit"
+        );
+        assert_eq!(explain_hint(&left, &[]), None);
+        // from a shortest solution of a harder board
+        let board =
+            asteroids_cli_game::notation::parse_board("1tr,1br,2t,1tl,s,1bl,2t,f,2b").unwrap();
+        assert_eq!(
+            explain_hint(&board, &[Right, Up, Left]).unwrap(),
+            "the double piece moves left and the single piece can move down next turn, making way for the ship to move right"
+        );
+    }
+
+    #[test]
+    fn test_describe_move() {
+        let board = sample_board().move_free_space(&Direction::Left).unwrap();
+        assert_eq!(
+            describe_move(&Direction::Left, 12, &board),
+            "free space moved left; move 12; ship is one column from the exit"
+        );
+        assert_eq!(
+            describe_step("move undone", 11, &board),
+            "move undone; move 11; ship is one column from the exit"
+        );
+    }
+}
@@ -0,0 +1,233 @@
+// engine protocol ------------------------------------------------------------
+//
+// A line-based protocol for other front-ends, in the spirit of UCI for chess:
+// each command line gets exactly one response line.
+//
+//     position <notation>     set up the board     position <notation> moves 0 playing
+//     move <direction> ...    play moves           position <notation> moves 2 won
+//     undo                    take one back        position <notation> moves 1 playing
+//     show                    the current board    position <notation> moves 1 playing
+//     legalmoves              possible moves       legalmoves left right
+//     solve                   a shortest solution  solution left left, or nosolution
+//     quit                    end the session      bye
+//
+// Directions are those of the 'free' space, `up`, `down`, `left`, `right`
+// or their first letters. A command that fails is answered with
+// `error <message>` and leaves the position as it was.
+
+use crate::analysis::solve;
+use crate::notation::{board_to_notation, parse_board};
+use crate::share;
+use crate::{Board, Direction};
+use std::fmt;
+
+/// The answer to one command line.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Response {
+    Position {
+        board: Board,
+        moves: usize,
+        won: bool,
+    },
+    LegalMoves(Vec<Direction>),
+    /// `None` if the board cannot be won
+    Solution(Option<Vec<Direction>>),
+    Error(String),
+    Bye,
+}
+
+impl fmt::Display for Response {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Response::Position { board, moves, won } => write!(
+                f,
+                "position {} moves {} {}",
+                board_to_notation(board),
+                moves,
+                if *won { "won" } else { "playing" }
+            ),
+            Response::LegalMoves(directions) => {
+                write!(f, "legalmoves{}", direction_names(directions))
+            }
+            Response::Solution(Some(moves)) => write!(f, "solution{}", direction_names(moves)),
+            Response::Solution(None) => write!(f, "nosolution"),
+            Response::Error(msg) => write!(f, "error {}", msg),
+            Response::Bye => write!(f, "bye"),
+        }
+    }
+}
+
+/// The names of the directions, each after a space.
+fn direction_names(directions: &[Direction]) -> String {
+    directions
+        .iter()
+        .map(|direction| format!(" {}", direction_name(direction)))
+        .collect()
+}
+
+/// The name of the direction in the protocol, e.g. `left`.
+pub fn direction_name(direction: &Direction) -> String {
+    format!("{:?}", direction).to_lowercase()
+}
+
+/// A direction by its name or first letter, in any case.
+pub fn parse_direction(word: &str) -> Result<Direction, String> {
+    match word.to_lowercase().as_str() {
+        "up" | "u" => Ok(Direction::Up),
+        "down" | "d" => Ok(Direction::Down),
+        "left" | "l" => Ok(Direction::Left),
+        "right" | "r" => Ok(Direction::Right),
+        _ => Err(format!(
+            "unknown direction '{}'; use up, down, left or right",
+            word
+        )),
+    }
+}
+
+/// A board in notation or as a share code.
+pub fn parse_position(text: &str) -> Result<Board, String> {
+    parse_board(text).or_else(|msg| {
+        share::decode(text)
+            .map(|shared| shared.board)
+            .map_err(|_| msg)
+    })
+}
+
+/// The state of a session: the boards from the position set up to the
+/// current one.
+#[derive(Default)]
+pub struct Engine {
+    boards: Vec<Board>,
+}
+
+impl Engine {
+    pub fn new() -> Engine {
+        Engine::default()
+    }
+
+    /// Start over from the board.
+    pub fn set_position(&mut self, board: Board) {
+        self.boards = vec![board];
+    }
+
+    /// The current board, `None` before a position is set up.
+    pub fn board(&self) -> Option<&Board> {
+        self.boards.last()
+    }
+
+    /// The moves played since the position was set up.
+    pub fn moves(&self) -> usize {
+        self.boards.len().saturating_sub(1)
+    }
+
+    /// Play the moves, all of them or none if one is not possible.
+    pub fn play(&mut self, directions: &[Direction]) -> Result<(), String> {
+        self.current()?
+            .apply_moves(directions)
+            .map_err(|error| error.to_string())?;
+        for direction in directions {
+            let board = self.current()?.move_free_space(direction).unwrap();
+            self.boards.push(board);
+        }
+        Ok(())
+    }
+
+    /// Answer a command line, see the protocol above.
+    pub fn respond(&mut self, line: &str) -> Response {
+        let mut words = line.split_whitespace();
+        let command = words.next().unwrap_or("");
+        let arguments: Vec<&str> = words.collect();
+        self.run(command, &arguments)
+            .unwrap_or_else(Response::Error)
+    }
+
+    fn run(&mut self, command: &str, arguments: &[&str]) -> Result<Response, String> {
+        match (command, arguments) {
+            ("position", [board]) => {
+                self.set_position(parse_position(board)?);
+                self.position()
+            }
+            ("position", _) => Err("usage: position <notation>".to_string()),
+            ("move", []) => Err("usage: move <direction> ...".to_string()),
+            ("move", directions) => {
+                let directions = directions
+                    .iter()
+                    .map(|word| parse_direction(word))
+                    .collect::<Result<Vec<_>, _>>()?;
+                self.play(&directions)?;
+                self.position()
+            }
+            ("undo", []) => {
+                self.current()?;
+                if self.boards.len() == 1 {
+                    return Err("nothing to undo".to_string());
+                }
+                self.boards.pop();
+                self.position()
+            }
+            ("show", []) => self.position(),
+            ("legalmoves", []) => Ok(Response::LegalMoves(self.current()?.legal_moves())),
+            ("solve", []) => Ok(Response::Solution(solve(self.current()?))),
+            ("quit", []) => Ok(Response::Bye),
+            ("undo" | "show" | "legalmoves" | "solve" | "quit", _) => {
+                Err(format!("{} takes no arguments", command))
+            }
+            ("", _) => Err("empty command".to_string()),
+            _ => Err(format!("unknown command '{}'", command)),
+        }
+    }
+
+    fn current(&self) -> Result<&Board, String> {
+        self.board()
+            .ok_or_else(|| "no position; set one up with 'position <notation>'".to_string())
+    }
+
+    fn position(&self) -> Result<Response, String> {
+        let board = *self.current()?;
+        Ok(Response::Position {
+            board,
+            moves: self.moves(),
+            won: board.is_won(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session() {
+        let mut engine = Engine::new();
+        let mut respond = |line: &str| engine.respond(line).to_string();
+        assert_eq!(
+            respond("legalmoves"),
+            "error no position; set one up with 'position <notation>'"
+        );
+        assert_eq!(
+            respond("position 1tl,2dd,2t,1tl,ctl,1tl,s,er,f"),
+            "position 1tl,2dd,2t,1tl,ctl,1tl,s,er,f moves 0 playing"
+        );
+        assert_eq!(respond("legalmoves"), "legalmoves left");
+        assert_eq!(respond("solve"), "solution left left");
+        assert_eq!(
+            respond("move left down"),
+            "error move 2 (Down) is not possible: the free space is at the edge of the board"
+        );
+        assert_eq!(
+            respond("move L left"),
+            "position 1tl,2dd,2t,1tl,ctl,1tl,f,s,er moves 2 won"
+        );
+        assert_eq!(
+            respond("undo"),
+            "position 1tl,2dd,2t,1tl,ctl,1tl,s,f,er moves 1 playing"
+        );
+        assert_eq!(
+            respond("move sideways"),
+            "error unknown direction 'sideways'; use up, down, left or right"
+        );
+        assert_eq!(respond("fly"), "error unknown command 'fly'");
+        assert_eq!(respond("solve now"), "error solve takes no arguments");
+        assert_eq!(respond("quit"), "bye");
+    }
+}
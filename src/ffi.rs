@@ -0,0 +1,276 @@
+// C interface ----------------------------------------------------------------
+//
+// The board, its moves and the solver for C and C++, built with the `ffi`
+// feature. Their header is `include/asteroids.h`, which build.rs generates;
+// the tests check that the checked-in copy is up to date, and write it with
+// `UPDATE_GOLDEN=1`. A board is a plain value of nine shape numbers, so there
+// is nothing to free, e.g.
+//
+//     AsteroidsBoard board;
+//     if (asteroids_board_from_notation("1tl,2dd,2t,1tl,ctl,1tl,s,er,f", &board) == 0) {
+//         asteroids_apply_move(&board, 'L');
+//         char moves[64];
+//         asteroids_solve(&board, moves, sizeof moves);  // 1, "L"
+//     }
+//
+// Functions return a negative number for invalid arguments, e.g. a null
+// pointer or a board that is not valid.
+
+use crate::analysis::solve;
+use crate::notation::{board_to_notation, parse_board};
+use crate::{format_moves, Board, Direction, Shape};
+use std::ffi::{c_char, CStr};
+
+/// An argument is not valid.
+pub const ASTEROIDS_INVALID: i32 = -1;
+/// The board cannot be won.
+pub const ASTEROIDS_UNSOLVABLE: i32 = -2;
+
+/// The shapes of a board row by row, starting top left, each by its position
+/// in the list of shapes of the notation, i.e. 0 for the free space and 1 for
+/// the ship.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AsteroidsBoard {
+    pub shapes: [u8; 9],
+}
+
+impl AsteroidsBoard {
+    fn from_board(board: &Board) -> AsteroidsBoard {
+        AsteroidsBoard {
+            shapes: board.shapes.map(|shape| shape as u8),
+        }
+    }
+
+    /// `None` if a shape number is unknown or the board not valid.
+    fn to_board(self) -> Option<Board> {
+        let mut shapes = [Shape::Free; 9];
+        for (shape, number) in shapes.iter_mut().zip(self.shapes) {
+            *shape = *Shape::all().get(number as usize)?;
+        }
+        let board = Board { shapes };
+        board.is_valid().then_some(board)
+    }
+}
+
+/// The board at `board`, `None` for a null pointer or an invalid board.
+///
+/// # Safety
+///
+/// `board` must be null or point to a board.
+unsafe fn read_board(board: *const AsteroidsBoard) -> Option<Board> {
+    board.as_ref()?.to_board()
+}
+
+/// Write the text with a terminating NUL if it fits into the buffer and
+/// return its length without the NUL, like `snprintf`.
+///
+/// # Safety
+///
+/// `buffer` must be null or point to `size` writable bytes.
+unsafe fn write_text(text: &str, buffer: *mut c_char, size: usize) -> i32 {
+    if !buffer.is_null() && text.len() < size {
+        std::ptr::copy_nonoverlapping(text.as_ptr(), buffer as *mut u8, text.len());
+        *buffer.add(text.len()) = 0;
+    }
+    text.len() as i32
+}
+
+/// Set up `out` from a board in notation. Returns 0, or `ASTEROIDS_INVALID`
+/// if the notation is not a valid board.
+///
+/// # Safety
+///
+/// `notation` must be null or a NUL-terminated string, and `out` null or
+/// point to a board.
+#[no_mangle]
+pub unsafe extern "C" fn asteroids_board_from_notation(
+    notation: *const c_char,
+    out: *mut AsteroidsBoard,
+) -> i32 {
+    if notation.is_null() || out.is_null() {
+        return ASTEROIDS_INVALID;
+    }
+    let Ok(notation) = CStr::from_ptr(notation).to_str() else {
+        return ASTEROIDS_INVALID;
+    };
+    match parse_board(notation) {
+        Ok(board) => {
+            *out = AsteroidsBoard::from_board(&board);
+            0
+        }
+        Err(_) => ASTEROIDS_INVALID,
+    }
+}
+
+/// Write the notation of the board with a terminating NUL into the buffer if
+/// it fits, like `snprintf`. Returns the length of the notation without the
+/// NUL, or `ASTEROIDS_INVALID`.
+///
+/// # Safety
+///
+/// `board` must be null or point to a board, and `buffer` null or point to
+/// `size` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn asteroids_board_to_notation(
+    board: *const AsteroidsBoard,
+    buffer: *mut c_char,
+    size: usize,
+) -> i32 {
+    match read_board(board) {
+        Some(board) => write_text(&board_to_notation(&board), buffer, size),
+        None => ASTEROIDS_INVALID,
+    }
+}
+
+/// Move the free space of the board in the direction `U`, `D`, `L` or `R`.
+/// Returns 0, 1 if the move is not possible, which leaves the board as it
+/// is, or `ASTEROIDS_INVALID`.
+///
+/// # Safety
+///
+/// `board` must be null or point to a board.
+#[no_mangle]
+pub unsafe extern "C" fn asteroids_apply_move(
+    board: *mut AsteroidsBoard,
+    direction: c_char,
+) -> i32 {
+    let (Some(current), Some(direction)) = (
+        read_board(board),
+        Direction::from_char(direction as u8 as char),
+    ) else {
+        return ASTEROIDS_INVALID;
+    };
+    match current.move_free_space(&direction) {
+        Ok(moved) => {
+            *board = AsteroidsBoard::from_board(&moved);
+            0
+        }
+        Err(_) => 1,
+    }
+}
+
+/// 1 if the ship can leave the board, 0 if not, or `ASTEROIDS_INVALID`.
+///
+/// # Safety
+///
+/// `board` must be null or point to a board.
+#[no_mangle]
+pub unsafe extern "C" fn asteroids_is_won(board: *const AsteroidsBoard) -> i32 {
+    match read_board(board) {
+        Some(board) => board.is_won() as i32,
+        None => ASTEROIDS_INVALID,
+    }
+}
+
+/// Write a shortest solution like `LLU` with a terminating NUL into the
+/// buffer if it fits. Returns its number of moves, `ASTEROIDS_UNSOLVABLE` or
+/// `ASTEROIDS_INVALID`.
+///
+/// # Safety
+///
+/// `board` must be null or point to a board, and `buffer` null or point to
+/// `size` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn asteroids_solve(
+    board: *const AsteroidsBoard,
+    buffer: *mut c_char,
+    size: usize,
+) -> i32 {
+    let Some(board) = read_board(board) else {
+        return ASTEROIDS_INVALID;
+    };
+    match solve(&board) {
+        Some(moves) => write_text(&format_moves(&moves), buffer, size),
+        None => ASTEROIDS_UNSOLVABLE,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sample_board;
+    use std::ffi::CString;
+    use std::fs;
+    use std::ptr;
+
+    fn text(buffer: &[c_char]) -> String {
+        unsafe { CStr::from_ptr(buffer.as_ptr()) }
+            .to_str()
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn test_game() {
+        let notation = CString::new("1tl,2dd,2t,1tl,ctl,1tl,s,er,f").unwrap();
+        let mut board = AsteroidsBoard { shapes: [0; 9] };
+        let mut buffer = [0 as c_char; 64];
+        unsafe {
+            assert_eq!(
+                asteroids_board_from_notation(notation.as_ptr(), &mut board),
+                0
+            );
+            assert_eq!(board, AsteroidsBoard::from_board(&sample_board()));
+            assert_eq!(asteroids_is_won(&board), 0);
+            assert_eq!(
+                asteroids_solve(&board, buffer.as_mut_ptr(), buffer.len()),
+                2
+            );
+            assert_eq!(text(&buffer), "LL");
+            assert_eq!(asteroids_apply_move(&mut board, b'D' as c_char), 1);
+            assert_eq!(asteroids_apply_move(&mut board, b'L' as c_char), 0);
+            assert_eq!(asteroids_apply_move(&mut board, b'l' as c_char), 0);
+            assert_eq!(asteroids_is_won(&board), 1);
+            assert_eq!(
+                asteroids_board_to_notation(&board, buffer.as_mut_ptr(), buffer.len()),
+                29
+            );
+            assert_eq!(text(&buffer), "1tl,2dd,2t,1tl,ctl,1tl,f,s,er");
+        }
+    }
+
+    #[test]
+    fn test_header() {
+        let generated = include_str!(concat!(env!("OUT_DIR"), "/asteroids.h"));
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/include/asteroids.h");
+        if std::env::var_os("UPDATE_GOLDEN").is_some() {
+            fs::write(path, generated).unwrap();
+            return;
+        }
+        assert!(
+            fs::read_to_string(path).unwrap() == generated,
+            "{} is out of date; run the tests with UPDATE_GOLDEN=1 to update it",
+            path
+        );
+    }
+
+    #[test]
+    fn test_invalid_arguments() {
+        let mut buffer = [1 as c_char; 4];
+        let board = AsteroidsBoard::from_board(&sample_board());
+        let invalid = AsteroidsBoard { shapes: [0; 9] };
+        let notation = CString::new("1tl,2dd").unwrap();
+        unsafe {
+            assert_eq!(
+                asteroids_board_from_notation(
+                    notation.as_ptr(),
+                    &mut AsteroidsBoard { shapes: [0; 9] }
+                ),
+                ASTEROIDS_INVALID
+            );
+            assert_eq!(asteroids_is_won(ptr::null()), ASTEROIDS_INVALID);
+            assert_eq!(asteroids_is_won(&invalid), ASTEROIDS_INVALID);
+            assert_eq!(
+                asteroids_apply_move(&mut board.clone(), b'X' as c_char),
+                ASTEROIDS_INVALID
+            );
+            // too small a buffer is left as it is
+            assert_eq!(
+                asteroids_board_to_notation(&board, buffer.as_mut_ptr(), buffer.len()),
+                29
+            );
+            assert_eq!(buffer, [1; 4]);
+        }
+    }
+}
@@ -0,0 +1,115 @@
+// property tests of the engine -----------------------------------------------
+//
+// Random boards and random move sequences from proptest, which shrinks a
+// failing case to a small one, checked for invariants that every feature
+// relies on. Run more cases than the default with e.g.
+//
+//     PROPTEST_CASES=10000 cargo test --release fuzz
+
+use crate::analysis::DIRECTIONS;
+use crate::backtracking::{self, SearchLimits, SearchOutcome, Verbosity};
+use crate::{Board, BoardIndex2d, Direction, MovingTile, Shape};
+use proptest::prelude::*;
+
+/// States that the solvers may expand per board; random boards without a
+/// ship explore all their positions in vain.
+const MAX_NODES: usize = 5_000;
+
+/// A valid board with the free space at `free_index` and the ship at
+/// `ship_index`. Every other cell gets the first shape that fits, trying all
+/// shapes but `Free` from the given offset on, so that shrinking the offsets
+/// to 0 keeps the board valid.
+fn board_from(free_index: usize, ship_index: usize, offsets: [usize; 9]) -> Option<Board> {
+    if free_index == ship_index {
+        return None;
+    }
+    let mut board =
+        Board::empty_board().set_shape(&BoardIndex2d::from_index(ship_index), &Shape::Ship);
+    for index in (0..9).filter(|&index| index != free_index && index != ship_index) {
+        let board_index = BoardIndex2d::from_index(index);
+        let shapes = &Shape::all()[1..];
+        board = (0..shapes.len())
+            .map(|step| shapes[(offsets[index] + step) % shapes.len()])
+            .map(|shape| board.set_shape(&board_index, &shape))
+            .find(|candidate| candidate.is_collission_free(&MovingTile::no_move()))?;
+    }
+    Some(board)
+}
+
+fn boards() -> impl Strategy<Value = Board> {
+    (
+        0..9usize,
+        0..9usize,
+        prop::array::uniform9(0..Shape::all().len() - 1),
+    )
+        .prop_filter_map(
+            "no shape fits a cell",
+            |(free_index, ship_index, offsets)| board_from(free_index, ship_index, offsets),
+        )
+}
+
+/// The board after the moves, skipping those that are not possible.
+fn replay(board: &Board, moves: &[Direction]) -> Board {
+    moves.iter().fold(*board, |board, direction| {
+        board.move_free_space(direction).unwrap_or(board)
+    })
+}
+
+proptest! {
+    #[test]
+    fn fuzz_legal_moves_keep_boards_valid(
+        board in boards(),
+        moves in prop::collection::vec(0..4usize, 0..60),
+    ) {
+        prop_assert!(board.is_valid());
+        let mut board = board;
+        for direction in moves.into_iter().map(|index| DIRECTIONS[index]) {
+            let Ok(moved) = board.move_free_space(&direction) else {
+                continue;
+            };
+            prop_assert!(moved.is_valid(), "moving {:?} on\n{}", direction, board);
+            prop_assert_eq!(
+                moved.shapes.iter().filter(|&&shape| shape == Shape::Free).count(),
+                1
+            );
+            prop_assert_eq!(moved.move_free_space(&direction.opposite()), Ok(board));
+            board = moved;
+        }
+    }
+}
+
+proptest! {
+    // solving takes a while, so fewer cases
+    #![proptest_config(ProptestConfig::with_cases(128))]
+
+    #[test]
+    fn fuzz_solutions_replay_to_won_board(
+        board in boards(),
+        moves in prop::collection::vec(0..4usize, 0..20),
+    ) {
+        // random moves make for positions that are not set up by hand
+        let board = replay(
+            &board,
+            &moves.into_iter().map(|index| DIRECTIONS[index]).collect::<Vec<_>>(),
+        );
+        prop_assume!(!board.is_won());
+        let limits = SearchLimits {
+            max_nodes: Some(MAX_NODES),
+            ..SearchLimits::default()
+        };
+        let (outcome, _) = backtracking::Strategy::BreadthFirst
+            .solve_within(&board, None, &limits, &mut Verbosity::Quiet);
+        let SearchOutcome::Found(solution) = outcome else {
+            return Ok(());
+        };
+        let replayed = board.apply_moves(&solution);
+        prop_assert!(replayed.is_ok(), "{:?} on\n{}", replayed, board);
+        prop_assert!(replayed.unwrap().is_won(), "{:?} does not win\n{}", solution, board);
+        // a shortest solution, as long as the one of the other optimal search
+        let (outcome, _) =
+            backtracking::Strategy::AStar.solve_within(&board, None, &limits, &mut Verbosity::Quiet);
+        if let SearchOutcome::Found(other) = outcome {
+            prop_assert_eq!(other.len(), solution.len());
+        }
+    }
+}
@@ -0,0 +1,352 @@
+// puzzle generator -----------------------------------------------------------
+
+use crate::analysis::{analyze, gods_number, solve, successors, Difficulty, GodsNumber};
+use crate::backtracking::distance_map;
+use crate::{Board, BoardIndex2d, MovingTile, Shape, ShapeFamily};
+use itertools::Itertools;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::cmp::Reverse;
+use std::collections::HashSet;
+
+/// The shapes that come with the game, turned as a new setup starts with them.
+pub const STANDARD_SHAPES: [Shape; 9] = [
+    Shape::OneTL,
+    Shape::TwoDiagUp,
+    Shape::TwoHorT,
+    Shape::OneTL,
+    Shape::LargeEdgeT,
+    Shape::LargeCornerTL,
+    Shape::OneTL,
+    Shape::Ship,
+    Shape::Free,
+];
+
+/// Random boards to try before settling for a puzzle that is easier than requested.
+const ATTEMPTS: usize = 20;
+
+/// Random walks to try before giving up on a drill.
+const DRILL_ATTEMPTS: usize = 1000;
+
+/// Random arrangements to try before giving up on completing a board.
+const COMPLETE_ATTEMPTS: usize = 1000;
+
+/// A random valid board with one ship and random tiles. It is not necessarily
+/// solvable.
+pub fn random_board(rng: &mut impl Rng) -> Board {
+    let tile_shapes: Vec<Shape> = Shape::all()
+        .into_iter()
+        .filter(|shape| !matches!(shape.family(), ShapeFamily::Free | ShapeFamily::Ship))
+        .collect();
+    'retry: loop {
+        let mut board = Board::empty_board();
+        let mut indices: Vec<usize> = (0..9).collect();
+        indices.shuffle(rng);
+        // the first index stays free
+        board.shapes[indices[1]] = Shape::Ship;
+        for &index in &indices[2..] {
+            let board_index = BoardIndex2d::from_index(index);
+            // give up on this board if the tile does not fit anywhere
+            let placed = (0..100).find_map(|_| {
+                let shape = tile_shapes.choose(rng).unwrap();
+                let candidate = board.set_shape(&board_index, shape);
+                candidate
+                    .is_collission_free(&MovingTile::no_move())
+                    .then_some(candidate)
+            });
+            match placed {
+                Some(candidate) => board = candidate,
+                None => continue 'retry,
+            }
+        }
+        return board;
+    }
+}
+
+/// Place `shapes` into the cells from `first_open` on, in random order and
+/// rotation, so that nothing collides and the board is not won yet. If
+/// `solvable` is set, the result is also a puzzle that can be solved.
+/// `None` if no such arrangement turns up.
+pub fn complete_board(
+    board: &Board,
+    first_open: usize,
+    shapes: &[Shape],
+    solvable: bool,
+    rng: &mut impl Rng,
+) -> Option<Board> {
+    assert_eq!(first_open + shapes.len(), 9, "one shape per open cell");
+    'attempt: for _ in 0..COMPLETE_ATTEMPTS {
+        let mut candidate = *board;
+        for index in first_open..9 {
+            candidate.shapes[index] = Shape::Free;
+        }
+        let mut shapes = shapes.to_vec();
+        shapes.shuffle(rng);
+        for (index, shape) in (first_open..9).zip(shapes) {
+            let board_index = BoardIndex2d::from_index(index);
+            let mut rotations: Vec<usize> = (0..4).collect();
+            rotations.shuffle(rng);
+            let placed = rotations.into_iter().find_map(|rotations| {
+                let shape = (0..rotations).fold(shape, |shape, _| shape.rotate(true));
+                let next = candidate.set_shape(&board_index, &shape);
+                next.is_collission_free(&MovingTile::no_move())
+                    .then_some(next)
+            });
+            match placed {
+                Some(next) => candidate = next,
+                None => continue 'attempt,
+            }
+        }
+        if !candidate.is_won() && (!solvable || solve(&candidate).is_some()) {
+            return Some(candidate);
+        }
+    }
+    None
+}
+
+/// A random puzzle whose shortest solution takes `optimal_moves` moves, or
+/// fewer if no such puzzle turns up. Puzzles whose `canonical_hash` is in
+/// `played` are skipped. Returns the board and the length of its shortest
+/// solution.
+pub fn generate(optimal_moves: usize, played: &HashSet<u64>, rng: &mut impl Rng) -> (Board, usize) {
+    let mut best: Option<(Board, usize)> = None;
+    let mut attempts = 0;
+    while attempts < ATTEMPTS || best.is_none() {
+        attempts += 1;
+        // all positions of a random board that lead to the exit
+        let mut distances = distance_map(&random_board(rng));
+        distances.retain(|board, _| !played.contains(&board.canonical_hash()));
+        let mut exact: Vec<&Board> = distances
+            .iter()
+            .filter(|(_, &distance)| distance as usize == optimal_moves)
+            .map(|(board, _)| board)
+            .collect();
+        // independent of the order of the hash map, so that a seed always
+        // leads to the same puzzle
+        exact.sort_by_key(|board| board.stable_hash());
+        if let Some(board) = exact.choose(rng) {
+            return (**board, optimal_moves);
+        }
+        let closest = distances
+            .iter()
+            .filter(|(_, &distance)| (1..optimal_moves).contains(&(distance as usize)))
+            .max_by_key(|(board, &distance)| (distance, board.stable_hash()));
+        if let Some((board, &distance)) = closest {
+            if best.is_none_or(|(_, best_distance)| distance as usize > best_distance) {
+                best = Some((*board, distance as usize));
+            }
+        }
+    }
+    best.unwrap()
+}
+
+/// A random puzzle of the difficulty, see `Analysis::difficulty_class`, with
+/// a shortest solution of a length typical for the difficulty. Puzzles whose
+/// `canonical_hash` is in `played` are skipped. Returns the board and the
+/// length of its shortest solution; if no puzzle of the difficulty turns up,
+/// the last one tried.
+pub fn generate_with_difficulty(
+    difficulty: Difficulty,
+    played: &HashSet<u64>,
+    rng: &mut impl Rng,
+) -> (Board, usize) {
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        let (board, optimal_moves) =
+            generate(rng.gen_range(difficulty.typical_moves()), played, rng);
+        if attempts >= ATTEMPTS || analyze(&board).difficulty_class() == Some(difficulty) {
+            return (board, optimal_moves);
+        }
+    }
+}
+
+/// An exercise that is won in exactly `moves` moves: a random walk of that
+/// many moves backward from a won position, each one move further away from
+/// the exit. The walk starts at `won` or at any won position reachable from
+/// it, as the tiles around the ship can still be moved once it is at the exit.
+/// `None` if the walks keep running into dead ends, e.g. because no position
+/// is that far from the exit.
+pub fn drill(won: &Board, moves: usize, rng: &mut impl Rng) -> Option<Board> {
+    let distances = distance_map(won);
+    let starts: Vec<&Board> = distances
+        .iter()
+        .filter(|(_, &distance)| distance == 0)
+        .map(|(board, _)| board)
+        .collect();
+    'attempt: for _ in 0..DRILL_ATTEMPTS {
+        let mut board = **starts.choose(rng)?;
+        for distance in 1..=moves {
+            let candidates: Vec<Board> = successors(&board)
+                .into_iter()
+                .map(|(_, next)| next)
+                .filter(|next| distances[next] as usize == distance)
+                .collect();
+            match candidates.choose(rng) {
+                Some(&next) => board = next,
+                None => continue 'attempt,
+            }
+        }
+        // every move can be undone, so the walk forward is a solution; verify
+        // independently that there is no shorter one
+        let solution = solve(&board).expect("the walk leads back to a won position");
+        assert_eq!(solution.len(), moves, "the drill is won in fewer moves");
+        return Some(board);
+    }
+    None
+}
+
+/// Every way to turn the shapes, each one sorted, without the mirror images of
+/// earlier ones.
+fn orientations(shapes: &[Shape]) -> Vec<Vec<Shape>> {
+    let sorted = |shapes: Vec<Shape>| shapes.into_iter().sorted_by_key(|&shape| shape as u8);
+    let mut seen = HashSet::new();
+    shapes
+        .iter()
+        .map(|&shape| {
+            // the ship and the free space stay as they are
+            (0..4)
+                .scan(shape, |turned, _| {
+                    let current = *turned;
+                    *turned = turned.rotate(true);
+                    Some(current)
+                })
+                .unique()
+                .collect::<Vec<Shape>>()
+        })
+        .multi_cartesian_product()
+        .map(|turned| sorted(turned).collect::<Vec<Shape>>())
+        .filter(|turned| {
+            let mirrored = sorted(turned.iter().map(Shape::mirror).collect());
+            let key = |shapes: &[Shape]| shapes.iter().map(|&shape| shape as u8).collect_vec();
+            let new = seen.insert(key(turned));
+            seen.insert(key(&mirrored.collect_vec()));
+            new
+        })
+        .collect()
+}
+
+/// The hardest puzzle that can be set up with the shapes, each tile turned
+/// any way: the board with the longest shortest solution of any orientation,
+/// see `gods_number`, and the arrangements of all orientations counted.
+/// Orientations that mirror others are left out, as mirror images take
+/// equally long to solve. `progress` is told how many orientations are done,
+/// and of how many. For the shapes of the game, this takes a quarter of an
+/// hour.
+pub fn hardest_puzzle(
+    shapes: &[Shape],
+    progress: &mut dyn FnMut(usize, usize),
+) -> Result<GodsNumber, String> {
+    let orientations = orientations(shapes);
+    let mut result = GodsNumber {
+        boards: 0,
+        solvable: 0,
+        hardest: None,
+    };
+    for (done, turned) in orientations.iter().enumerate() {
+        let found = gods_number(turned, &mut HashSet::new())?;
+        result.boards += found.boards;
+        result.solvable += found.solvable;
+        // the same tie-break as `gods_number`
+        let key = |(board, moves): (Board, usize)| (moves, Reverse(board.stable_hash()));
+        if found.hardest.map(key) > result.hardest.map(key) {
+            result.hardest = found.hardest;
+        }
+        progress(done + 1, orientations.len());
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_generate() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let board = random_board(&mut rng);
+        assert!(board.is_valid());
+        assert_eq!(
+            board
+                .shapes
+                .iter()
+                .filter(|&&shape| shape == Shape::Ship)
+                .count(),
+            1
+        );
+
+        let (board, optimal_moves) = generate(5, &HashSet::new(), &mut rng);
+        assert!(board.is_valid());
+        assert_eq!(optimal_moves, 5);
+        assert_eq!(solve(&board).unwrap().len(), 5);
+
+        let played = HashSet::from([board.mirror().canonical_hash()]);
+        let (other, _) = generate(5, &played, &mut rng);
+        assert_ne!(other.canonical_hash(), board.canonical_hash());
+
+        let (board, optimal_moves) =
+            generate_with_difficulty(Difficulty::Medium, &HashSet::new(), &mut rng);
+        assert!(Difficulty::Medium.typical_moves().contains(&optimal_moves));
+        assert_eq!(analyze(&board).difficulty_class(), Some(Difficulty::Medium));
+    }
+
+    #[test]
+    fn test_complete_board() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let sample = crate::sample_board();
+        // keep the first three tiles of the sample, rearrange the rest
+        let board = complete_board(&sample, 3, &sample.shapes[3..], true, &mut rng).unwrap();
+        assert_eq!(board.shapes[..3], sample.shapes[..3]);
+        assert!(board.is_valid());
+        assert!(solve(&board).is_some());
+
+        // the ship does not fit next to itself
+        let mut ships = Board::empty_board();
+        ships.shapes[0] = Shape::Ship;
+        assert!(complete_board(&ships, 1, &[Shape::Ship; 8], false, &mut rng).is_none());
+
+        // random fills are re-rolled rather than won already
+        let won = sample.apply_moves(&solve(&sample).unwrap()).unwrap();
+        for _ in 0..20 {
+            let board = complete_board(&won, 6, &won.shapes[6..], false, &mut rng).unwrap();
+            assert!(!board.is_won());
+        }
+    }
+
+    #[test]
+    fn test_drill() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let board = crate::sample_board();
+        let won = board.apply_moves(&solve(&board).unwrap()).unwrap();
+        for moves in 1..=4 {
+            let board = drill(&won, moves, &mut rng).unwrap();
+            assert_eq!(solve(&board).unwrap().len(), moves);
+        }
+    }
+
+    #[test]
+    fn test_orientations() {
+        let standard = orientations(&STANDARD_SHAPES);
+        // three small tiles in 20 ways, the others in 2, 4, 4 and 4 ways, and
+        // none of the combinations is its own mirror image
+        assert_eq!(standard.len(), 20 * 2 * 4 * 4 * 4 / 2);
+        let mut sample = crate::sample_board().shapes.to_vec();
+        sample.sort_by_key(|&shape| shape as u8);
+        assert!(
+            standard.contains(&sample)
+                != standard.contains(
+                    &sample
+                        .iter()
+                        .map(Shape::mirror)
+                        .sorted_by_key(|&shape| shape as u8)
+                        .collect()
+                )
+        );
+        // four small tiles in 35 ways and three straight ones in 20 ways, of
+        // which 3 and 6 are their own mirror image
+        let symmetric = crate::notation::parse_shapes("1tl,s,1tr,1bl,f,1br,2t,2b,2t").unwrap();
+        assert_eq!(orientations(&symmetric).len(), (35 * 20 + 3 * 6) / 2);
+    }
+}
@@ -0,0 +1,296 @@
+// history of a game ----------------------------------------------------------
+
+use asteroids_cli_game::{Board, Direction};
+
+struct Node {
+    board: Board,
+    parent: Option<usize>,
+    /// `false` if the board continues the move to the parent, e.g. for the
+    /// further hops of a slide that counts as one move
+    counted: bool,
+    /// in the order in which they were played first
+    children: Vec<usize>,
+    /// the child that was visited last; redo follows it
+    last_child: Option<usize>,
+}
+
+/// All positions of a game as a tree: undoing moves and playing a different
+/// one starts a new branch, and the old line is kept.
+/// Positions are identified by their index, the start is 0.
+pub struct HistoryTree {
+    nodes: Vec<Node>,
+    current: usize,
+}
+
+impl HistoryTree {
+    pub fn new(board: Board) -> HistoryTree {
+        HistoryTree {
+            nodes: vec![Node {
+                board,
+                parent: None,
+                counted: false,
+                children: vec![],
+                last_child: None,
+            }],
+            current: 0,
+        }
+    }
+
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    /// The current board.
+    pub fn board(&self) -> &Board {
+        &self.nodes[self.current].board
+    }
+
+    pub fn board_at(&self, node: usize) -> &Board {
+        &self.nodes[node].board
+    }
+
+    /// Number of moves from the start to the position.
+    pub fn depth_of(&self, node: usize) -> usize {
+        let mut depth = 0;
+        let mut node = node;
+        while let Some(parent) = self.nodes[node].parent {
+            if self.nodes[node].counted {
+                depth += 1;
+            }
+            node = parent;
+        }
+        depth
+    }
+
+    /// Number of moves from the start to the current position.
+    pub fn depth(&self) -> usize {
+        self.depth_of(self.current)
+    }
+
+    /// Make the move that leads to `board`. If it was made before from the
+    /// current position, its branch is followed instead of starting a new one.
+    pub fn play(&mut self, board: Board) {
+        self.add(board, true);
+    }
+
+    /// Like `play`, but as part of the last move instead of a move of its own.
+    pub fn continue_move(&mut self, board: Board) {
+        self.add(board, false);
+    }
+
+    fn add(&mut self, board: Board, counted: bool) {
+        let existing = self.nodes[self.current]
+            .children
+            .iter()
+            .copied()
+            .find(|&child| self.nodes[child].board == board);
+        let child = existing.unwrap_or_else(|| {
+            self.nodes.push(Node {
+                board,
+                parent: Some(self.current),
+                counted,
+                children: vec![],
+                last_child: None,
+            });
+            let child = self.nodes.len() - 1;
+            self.nodes[self.current].children.push(child);
+            child
+        });
+        self.go_to(child);
+    }
+
+    /// Go back one move; `false` at the start.
+    pub fn undo(&mut self) -> bool {
+        loop {
+            let node = &self.nodes[self.current];
+            let Some(parent) = node.parent else {
+                return false;
+            };
+            self.current = parent;
+            if node.counted {
+                return true;
+            }
+        }
+    }
+
+    /// Replay the move that was undone last; `false` if there is none.
+    pub fn redo(&mut self) -> bool {
+        let Some(child) = self.next(self.current) else {
+            return false;
+        };
+        self.current = child;
+        while let Some(child) = self.next(self.current) {
+            if self.nodes[child].counted {
+                break;
+            }
+            self.current = child;
+        }
+        true
+    }
+
+    /// Continue from any position of the tree. Redo then leads back along the
+    /// line that was just jumped to.
+    pub fn go_to(&mut self, node: usize) {
+        let mut child = node;
+        while let Some(parent) = self.nodes[child].parent {
+            self.nodes[parent].last_child = Some(child);
+            child = parent;
+        }
+        self.current = node;
+    }
+
+    pub fn parent(&self, node: usize) -> Option<usize> {
+        self.nodes[node].parent
+    }
+
+    /// The position that redo leads to from `node`.
+    pub fn next(&self, node: usize) -> Option<usize> {
+        self.nodes[node].last_child
+    }
+
+    /// The positions with the same parent as `node`, including itself.
+    pub fn siblings(&self, node: usize) -> &[usize] {
+        match self.nodes[node].parent {
+            Some(parent) => &self.nodes[parent].children,
+            None => &[0],
+        }
+    }
+
+    /// The closest position before `node` that is one of several lines, i.e.
+    /// where a different move was played as well; the start if there is none.
+    pub fn previous_branch(&self, node: usize) -> usize {
+        let mut node = node;
+        while let Some(parent) = self.nodes[node].parent {
+            node = parent;
+            if self.siblings(node).len() > 1 {
+                break;
+            }
+        }
+        node
+    }
+
+    /// The same after `node` along the line that redo follows; the end of the
+    /// line if there is none.
+    pub fn next_branch(&self, node: usize) -> usize {
+        let mut node = node;
+        while let Some(child) = self.next(node) {
+            node = child;
+            if self.siblings(node).len() > 1 {
+                break;
+            }
+        }
+        node
+    }
+
+    /// The boards from the start to the position.
+    pub fn line_to(&self, node: usize) -> Vec<Board> {
+        let mut boards = vec![self.nodes[node].board];
+        let mut node = node;
+        while let Some(parent) = self.nodes[node].parent {
+            boards.push(self.nodes[parent].board);
+            node = parent;
+        }
+        boards.reverse();
+        boards
+    }
+
+    /// The moves of the 'free' space from the start to the position.
+    pub fn moves_to(&self, node: usize) -> Vec<Direction> {
+        self.line_to(node)
+            .windows(2)
+            .map(|pair| {
+                pair[0]
+                    .find_free_space()
+                    .direction_to(&pair[1].find_free_space())
+                    .expect("a board and its parent differ by one move")
+            })
+            .collect()
+    }
+
+    /// The boards from the start to the current position.
+    pub fn line(&self) -> Vec<Board> {
+        self.line_to(self.current)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use asteroids_cli_game::sample_board;
+
+    #[test]
+    fn test_branches() {
+        let start = sample_board();
+        let left = start.move_free_space(&Direction::Left).unwrap();
+        let won = left.move_free_space(&Direction::Left).unwrap();
+        let mut tree = HistoryTree::new(start);
+        assert!(!tree.undo());
+        tree.play(left);
+        tree.play(won);
+        assert_eq!(tree.depth(), 2);
+        assert!(tree.undo());
+        assert_eq!(tree.board(), &left);
+
+        // a different move starts a new branch, the old one is kept
+        tree.play(start);
+        assert_eq!(tree.line(), vec![start, left, start]);
+        assert_eq!(
+            tree.moves_to(tree.current()),
+            vec![Direction::Left, Direction::Right]
+        );
+        assert_eq!(tree.siblings(tree.current()), &[2, 3]);
+        assert_eq!(tree.siblings(0), &[0]);
+
+        // playing a known move follows its branch
+        tree.undo();
+        tree.play(won);
+        assert_eq!(tree.current(), 2);
+
+        // redo follows the line visited last
+        tree.undo();
+        assert!(tree.redo());
+        assert_eq!(tree.board(), &won);
+        tree.go_to(3);
+        tree.undo();
+        assert!(tree.redo());
+        assert_eq!(tree.current(), 3);
+        assert!(!tree.redo());
+    }
+
+    #[test]
+    fn test_jump_between_branches() {
+        let start = sample_board();
+        let left = start.move_free_space(&Direction::Left).unwrap();
+        let won = left.move_free_space(&Direction::Left).unwrap();
+        let mut tree = HistoryTree::new(start);
+        tree.play(left);
+        assert_eq!(tree.previous_branch(1), 0);
+        assert_eq!(tree.next_branch(0), 1);
+        // the lines split after the first move
+        tree.play(won);
+        tree.undo();
+        tree.play(start);
+        tree.play(left);
+        assert_eq!(tree.previous_branch(4), 3);
+        assert_eq!(tree.previous_branch(3), 0);
+        assert_eq!(tree.next_branch(0), 3);
+        assert_eq!(tree.next_branch(3), 4);
+    }
+
+    #[test]
+    fn test_continued_moves() {
+        let start = sample_board();
+        let left = start.move_free_space(&Direction::Left).unwrap();
+        let won = left.move_free_space(&Direction::Left).unwrap();
+        let mut tree = HistoryTree::new(start);
+        tree.play(left);
+        tree.continue_move(won);
+        assert_eq!(tree.depth(), 1);
+        assert_eq!(tree.line().len(), 3);
+        // undo and redo take the whole move
+        assert!(tree.undo());
+        assert_eq!(tree.board(), &start);
+        assert!(tree.redo());
+        assert_eq!(tree.board(), &won);
+    }
+}
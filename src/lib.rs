@@ -0,0 +1,1577 @@
+//! The puzzle of the asteroids game: a ship and other tiles on a 3x3 board
+//! with one free space, into which a neighboring tile can slide. The puzzle
+//! is solved once the ship can leave through the exit in the middle of the
+//! bottom edge.
+//!
+//! The board and its moves are defined here; `notation` reads and writes
+//! boards as text, `backtracking` and `analysis` search for solutions,
+//! `generator` makes puzzles, `share` turns them into codes to exchange,
+//! `rendering` draws boards and `engine` answers a line-based protocol for
+//! other front-ends. With the `wasm` feature, `wasm` exposes the board and
+//! the solver to JavaScript, and with the `ffi` feature, `ffi` does the
+//! same for C. For example:
+//!
+//! ```
+//! use asteroids_cli_game::analysis::solve;
+//! use asteroids_cli_game::notation::parse_board;
+//! use asteroids_cli_game::{format_moves, Direction};
+//!
+//! let board = parse_board("1tl,2dd,2t,1tl,ctl,1tl,s,er,f").unwrap();
+//! let moves = solve(&board).unwrap();
+//! assert_eq!(format_moves(&moves), "LL");
+//! let after_first = board.move_free_space(&Direction::Left).unwrap();
+//! assert!(after_first.move_free_space(&Direction::Left).unwrap().is_won());
+//! ```
+//!
+//! The game itself, i.e. the text user interface and the command line, is
+//! the binary of this crate.
+
+pub mod analysis;
+pub mod backtracking;
+pub mod bench;
+pub mod engine;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(test)]
+mod fuzz;
+pub mod generator;
+pub mod notation;
+pub mod patterns;
+pub mod rendering;
+#[cfg(test)]
+mod representation_bench;
+pub mod share;
+pub mod visited;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// The shape of a tile, drawn on the 2x2 grid points of its cell: `X` are
+/// points of the tile, `o` points of the cell that it leaves empty and `y`
+/// points of neighboring cells that it covers.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Shape {
+    /// ```text
+    /// oo
+    /// oo
+    /// ```
+    Free,
+    /// ```text
+    /// yXXy
+    ///  XX
+    /// ```
+    Ship,
+    /// ```text
+    /// Xo
+    /// oo
+    /// ```
+    OneTL,
+    /// ```text
+    /// oX
+    /// oo
+    /// ```
+    OneTR,
+    /// ```text
+    /// oo
+    /// Xo
+    /// ```
+    OneBL,
+    /// ```text
+    /// oo
+    /// oX
+    /// ```
+    OneBR,
+    /// ```text
+    /// Xo
+    /// oX
+    /// ```
+    TwoDiagDown,
+    /// ```text
+    /// oX
+    /// Xo
+    /// ```
+    TwoDiagUp,
+    /// ```text
+    /// XX
+    /// oo
+    /// ```
+    TwoHorT,
+    /// ```text
+    /// Xo
+    /// Xo
+    /// ```
+    TwoHorL,
+    /// ```text
+    /// oo
+    /// XX
+    /// ```
+    TwoHorB,
+    /// ```text
+    /// oX
+    /// oX
+    /// ```
+    TwoHorR,
+    /// ```text
+    /// yy
+    /// XX
+    /// oo
+    /// ```
+    LargeEdgeT,
+    /// ```text
+    /// yXo
+    /// yXo
+    /// ```
+    LargeEdgeL,
+    /// ```text
+    /// oo
+    /// XX
+    /// yy
+    /// ```
+    LargeEdgeB,
+    /// ```text
+    /// oXy
+    /// oXy
+    /// ```
+    LargeEdgeR,
+    /// ```text
+    /// yy
+    /// yXo
+    ///  oo
+    /// ```
+    LargeCornerTL,
+    /// ```text
+    ///  yy
+    /// oXy
+    /// oo
+    /// ```
+    LargeCornerTR,
+    /// ```text
+    ///  oo
+    /// yXo
+    /// yy
+    /// ```
+    LargeCornerBL,
+    /// ```text
+    /// oo
+    /// oXy
+    /// oyy
+    /// ```
+    LargeCornerBR,
+}
+
+impl Shape {
+    /// Rotate the shape by 90°; the ship cannot rotate and stays as it is.
+    pub fn rotate(&self, clockwise: bool) -> Shape {
+        // looked up, as boards are rotated often, e.g. for `canonical_hash`
+        static ROTATED: OnceLock<Vec<[Shape; 2]>> = OnceLock::new();
+        let rotated = ROTATED.get_or_init(|| {
+            Shape::all()
+                .iter()
+                .map(|shape| [false, true].map(|clockwise| shape.rotate_points(clockwise)))
+                .collect()
+        });
+        rotated[*self as usize][clockwise as usize]
+    }
+
+    /// The shape whose points are the ones of this shape rotated by 90°
+    /// around the center of the cell.
+    fn rotate_points(&self, clockwise: bool) -> Shape {
+        if *self == Shape::Ship {
+            return Shape::Ship; // cannot rotate
+        }
+        let points: Vec<Point> = self
+            .get_points()
+            .iter()
+            .map(|point| {
+                if clockwise {
+                    Point {
+                        x: 1 - point.y,
+                        y: point.x,
+                    }
+                } else {
+                    Point {
+                        x: point.y,
+                        y: 1 - point.x,
+                    }
+                }
+            })
+            .collect();
+        Shape::from_points(&points).expect("the rotations of all shapes but the ship are shapes")
+    }
+
+    /// The shape with exactly these grid points, in any order, relative to
+    /// the top left point of its cell; no points is the free space.
+    pub fn from_points(points: &[Point]) -> Option<Shape> {
+        let wanted: HashSet<&Point> = points.iter().collect();
+        Shape::all().into_iter().find(|shape| {
+            let points = shape.get_points();
+            points.len() == wanted.len() && points.iter().all(|point| wanted.contains(point))
+        })
+    }
+
+    /// Mirror the shape horizontally, i.e. swap left and right.
+    pub fn mirror(&self) -> Shape {
+        match self {
+            Shape::OneTL => Shape::OneTR,
+            Shape::OneTR => Shape::OneTL,
+            Shape::OneBL => Shape::OneBR,
+            Shape::OneBR => Shape::OneBL,
+            Shape::TwoDiagDown => Shape::TwoDiagUp,
+            Shape::TwoDiagUp => Shape::TwoDiagDown,
+            Shape::TwoHorL => Shape::TwoHorR,
+            Shape::TwoHorR => Shape::TwoHorL,
+            Shape::LargeEdgeL => Shape::LargeEdgeR,
+            Shape::LargeEdgeR => Shape::LargeEdgeL,
+            Shape::LargeCornerTL => Shape::LargeCornerTR,
+            Shape::LargeCornerTR => Shape::LargeCornerTL,
+            Shape::LargeCornerBL => Shape::LargeCornerBR,
+            Shape::LargeCornerBR => Shape::LargeCornerBL,
+            // symmetric with respect to a vertical axis
+            Shape::Free
+            | Shape::Ship
+            | Shape::TwoHorT
+            | Shape::TwoHorB
+            | Shape::LargeEdgeT
+            | Shape::LargeEdgeB => *self,
+        }
+    }
+}
+
+/// A point of the grid that tiles are drawn and checked for collisions on,
+/// two by two points per cell.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+#[cfg(test)]
+mod test4 {
+    use super::*;
+    use std::hash::{DefaultHasher, Hash, Hasher};
+
+    fn get_hash<T: Hash>(t: &T) -> u64 {
+        let mut s = DefaultHasher::new();
+        t.hash(&mut s);
+        s.finish()
+    }
+
+    #[test]
+    fn test_points_equal() {
+        let point1 = Point { x: 1, y: 2 };
+        let point2 = Point { x: 1, y: 2 };
+        let point3 = Point { x: 1, y: 3 };
+        assert_eq!(point1, point2);
+        assert_eq!(
+            get_hash(&point1),
+            get_hash(&point2),
+            "hashes of equal objects should be equal."
+        );
+        assert!(point1 == point2);
+        assert_ne!(point1, point3);
+        assert_ne!(get_hash(&point1), get_hash(&point3));
+        assert!(point1 != point3);
+    }
+
+    #[test]
+    fn test_shape_rotation() {
+        for shape in Shape::all() {
+            assert_eq!(shape, shape.rotate(true).rotate(false));
+            for clockwise in [false, true] {
+                let turned = (0..4).fold(shape, |turned, _| turned.rotate(clockwise));
+                assert_eq!(turned, shape, "{:?}", shape);
+            }
+            assert_eq!(Shape::all()[shape as usize], shape);
+            assert_eq!(Shape::from_points(&shape.get_points()), Some(shape));
+        }
+        assert_eq!(Shape::OneTL.rotate(true), Shape::OneTR);
+        assert_eq!(Shape::LargeEdgeT.rotate(false), Shape::LargeEdgeL);
+        assert_eq!(Shape::TwoDiagDown.rotate(true), Shape::TwoDiagUp);
+        assert_eq!(Shape::Ship.rotate(true), Shape::Ship);
+        assert_eq!(Shape::from_points(&[Point { x: 2, y: 2 }]), None);
+    }
+
+    #[test]
+    fn test_shape_metadata() {
+        let all = Shape::all();
+        assert_eq!(all.iter().collect::<HashSet<_>>().len(), 20);
+        assert_eq!(
+            all.iter().map(Shape::family).collect::<HashSet<_>>().len(),
+            7
+        );
+        for shape in all {
+            // the rotations of a shape are the shapes of its family
+            let family: Vec<Shape> = all
+                .into_iter()
+                .filter(|other| other.family() == shape.family())
+                .collect();
+            let rotations = shape.rotations();
+            assert_eq!(rotations[0], shape);
+            assert_eq!(rotations.len(), family.len(), "{:?}", shape);
+            assert!(rotations.iter().all(|rotated| family.contains(rotated)));
+            assert!(rotations
+                .iter()
+                .all(|rotated| rotated.cell_count() == shape.cell_count()));
+        }
+        assert_eq!(
+            Shape::OneBL.rotations(),
+            vec![Shape::OneBL, Shape::OneTL, Shape::OneTR, Shape::OneBR]
+        );
+        assert_eq!(Shape::Ship.rotations(), vec![Shape::Ship]);
+        assert_eq!(Shape::Ship.cell_count(), 6);
+        assert_eq!(Shape::TwoDiagUp.cell_count(), 2);
+        assert_eq!(Shape::Free.cell_count(), 0);
+    }
+
+    fn sorted(mut points: Vec<Point>) -> Vec<Point> {
+        points.sort_by_key(|point| (point.y, point.x));
+        points
+    }
+
+    #[test]
+    fn test_shape_mirror() {
+        for shape in Shape::all() {
+            assert_eq!(shape, shape.mirror().mirror());
+            // mirror around the vertical axis through the center of the tile
+            let mirrored_points = shape
+                .get_points()
+                .iter()
+                .map(|point| Point {
+                    x: 1 - point.x,
+                    y: point.y,
+                })
+                .collect();
+            assert_eq!(
+                sorted(shape.mirror().get_points()),
+                sorted(mirrored_points),
+                "{:?}",
+                shape
+            );
+        }
+    }
+
+    #[test]
+    fn test_shape_rotation_matches_points() {
+        for shape in Shape::all() {
+            if shape == Shape::Ship {
+                continue; // cannot rotate
+            }
+            // rotate clockwise around the center of the tile
+            let rotated_points = shape
+                .get_points()
+                .iter()
+                .map(|point| Point {
+                    x: 1 - point.y,
+                    y: point.x,
+                })
+                .collect();
+            assert_eq!(
+                sorted(shape.rotate(true).get_points()),
+                sorted(rotated_points),
+                "{:?}",
+                shape
+            );
+        }
+    }
+}
+
+// Central square is an actual 2x2 square.
+// Positions where an occupancy can be are indicated by x,y coordinates.
+//
+// ┌───────────────────────────────────────────────►
+// │                                                x
+// │
+// │          │               │
+// │    -1,-1 │ 0,-1          │
+// │          │               │
+// │          │               │
+// │   ───────┼───────────────┼────────────
+// │          │               │
+// │     -1,0 │  0,0      1,0 │  2,0
+// │          │               │
+// │          │               │
+// │          │               │
+// │          │  0,1      1,1 │
+// │          │               │
+// │   ───────┼───────────────┼────────────
+// │          │               │
+// │          │               │
+// │          │               │
+// │          │               │
+// │
+// │
+// ▼ y
+
+impl Shape {
+    /// The grid points of the shape, relative to the top left point of its
+    /// cell.
+    pub fn get_points(&self) -> Vec<Point> {
+        match self {
+            Shape::Free => vec![],
+            Shape::Ship => vec![
+                Point { x: 0, y: 0 },
+                Point { x: 1, y: 0 },
+                Point { x: 0, y: 1 },
+                Point { x: 1, y: 1 },
+                Point { x: 2, y: 0 },
+                Point { x: -1, y: 0 },
+            ],
+            Shape::OneTL => vec![Point { x: 0, y: 0 }],
+            Shape::OneTR => vec![Point { x: 1, y: 0 }],
+            Shape::OneBL => vec![Point { x: 0, y: 1 }],
+            Shape::OneBR => vec![Point { x: 1, y: 1 }],
+            Shape::TwoDiagDown => vec![Point { x: 0, y: 0 }, Point { x: 1, y: 1 }],
+            Shape::TwoDiagUp => vec![Point { x: 0, y: 1 }, Point { x: 1, y: 0 }],
+            Shape::TwoHorT => vec![Point { x: 0, y: 0 }, Point { x: 1, y: 0 }],
+            Shape::TwoHorL => vec![Point { x: 0, y: 0 }, Point { x: 0, y: 1 }],
+            Shape::TwoHorB => vec![Point { x: 0, y: 1 }, Point { x: 1, y: 1 }],
+            Shape::TwoHorR => vec![Point { x: 1, y: 0 }, Point { x: 1, y: 1 }],
+            Shape::LargeEdgeT => vec![
+                Point { x: 0, y: -1 },
+                Point { x: 1, y: -1 },
+                Point { x: 0, y: 0 },
+                Point { x: 1, y: 0 },
+            ],
+            Shape::LargeEdgeL => vec![
+                Point { x: -1, y: 0 },
+                Point { x: 0, y: 0 },
+                Point { x: -1, y: 1 },
+                Point { x: 0, y: 1 },
+            ],
+            Shape::LargeEdgeB => vec![
+                Point { x: 0, y: 1 },
+                Point { x: 1, y: 1 },
+                Point { x: 0, y: 2 },
+                Point { x: 1, y: 2 },
+            ],
+            Shape::LargeEdgeR => vec![
+                Point { x: 1, y: 0 },
+                Point { x: 2, y: 0 },
+                Point { x: 1, y: 1 },
+                Point { x: 2, y: 1 },
+            ],
+            Shape::LargeCornerTL => vec![
+                Point { x: -1, y: -1 },
+                Point { x: 0, y: -1 },
+                Point { x: -1, y: 0 },
+                Point { x: 0, y: 0 },
+            ],
+            Shape::LargeCornerTR => vec![
+                Point { x: 1, y: -1 },
+                Point { x: 2, y: -1 },
+                Point { x: 1, y: 0 },
+                Point { x: 2, y: 0 },
+            ],
+            Shape::LargeCornerBL => vec![
+                Point { x: -1, y: 1 },
+                Point { x: 0, y: 1 },
+                Point { x: -1, y: 2 },
+                Point { x: 0, y: 2 },
+            ],
+            Shape::LargeCornerBR => vec![
+                Point { x: 1, y: 1 },
+                Point { x: 2, y: 1 },
+                Point { x: 1, y: 2 },
+                Point { x: 2, y: 2 },
+            ],
+        }
+    }
+
+    /// All shapes, the free space and the ship first.
+    pub fn all() -> [Shape; 20] {
+        [
+            Shape::Free,
+            Shape::Ship,
+            Shape::OneTL,
+            Shape::OneTR,
+            Shape::OneBL,
+            Shape::OneBR,
+            Shape::TwoDiagDown,
+            Shape::TwoDiagUp,
+            Shape::TwoHorT,
+            Shape::TwoHorL,
+            Shape::TwoHorB,
+            Shape::TwoHorR,
+            Shape::LargeEdgeT,
+            Shape::LargeEdgeL,
+            Shape::LargeEdgeB,
+            Shape::LargeEdgeR,
+            Shape::LargeCornerTL,
+            Shape::LargeCornerTR,
+            Shape::LargeCornerBL,
+            Shape::LargeCornerBR,
+        ]
+    }
+
+    pub fn family(&self) -> ShapeFamily {
+        match self {
+            Shape::Free => ShapeFamily::Free,
+            Shape::Ship => ShapeFamily::Ship,
+            Shape::OneTL | Shape::OneTR | Shape::OneBL | Shape::OneBR => ShapeFamily::Single,
+            Shape::TwoDiagDown | Shape::TwoDiagUp => ShapeFamily::Diagonal,
+            Shape::TwoHorT | Shape::TwoHorL | Shape::TwoHorB | Shape::TwoHorR => {
+                ShapeFamily::Double
+            }
+            Shape::LargeEdgeT | Shape::LargeEdgeL | Shape::LargeEdgeB | Shape::LargeEdgeR => {
+                ShapeFamily::LargeEdge
+            }
+            Shape::LargeCornerTL
+            | Shape::LargeCornerTR
+            | Shape::LargeCornerBL
+            | Shape::LargeCornerBR => ShapeFamily::LargeCorner,
+        }
+    }
+
+    /// The number of grid points that the shape covers, 0 for the free space.
+    pub fn cell_count(&self) -> usize {
+        self.get_points().len()
+    }
+
+    /// The different orientations of the shape, rotating clockwise from this
+    /// one; the free space and the ship have only one.
+    pub fn rotations(&self) -> Vec<Shape> {
+        let mut rotations = vec![*self];
+        let mut rotated = self.rotate(true);
+        while rotated != *self {
+            rotations.push(rotated);
+            rotated = rotated.rotate(true);
+        }
+        rotations
+    }
+
+    /// Human readable name, e.g. "large corner, top-left".
+    pub fn name(&self) -> &'static str {
+        match self {
+            Shape::Free => "free space",
+            Shape::Ship => "ship",
+            Shape::OneTL => "single, top-left",
+            Shape::OneTR => "single, top-right",
+            Shape::OneBL => "single, bottom-left",
+            Shape::OneBR => "single, bottom-right",
+            Shape::TwoDiagDown => "two, diagonal down",
+            Shape::TwoDiagUp => "two, diagonal up",
+            Shape::TwoHorT => "two, top",
+            Shape::TwoHorL => "two, left",
+            Shape::TwoHorB => "two, bottom",
+            Shape::TwoHorR => "two, right",
+            Shape::LargeEdgeT => "large edge, top",
+            Shape::LargeEdgeL => "large edge, left",
+            Shape::LargeEdgeB => "large edge, bottom",
+            Shape::LargeEdgeR => "large edge, right",
+            Shape::LargeCornerTL => "large corner, top-left",
+            Shape::LargeCornerTR => "large corner, top-right",
+            Shape::LargeCornerBL => "large corner, bottom-left",
+            Shape::LargeCornerBR => "large corner, bottom-right",
+        }
+    }
+}
+
+/// The kinds of shapes, each made up of the rotations of one shape.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ShapeFamily {
+    Free,
+    Ship,
+    Single,
+    Diagonal,
+    Double,
+    LargeEdge,
+    LargeCorner,
+}
+
+/// The shapes on the board, row by row starting top left.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Board {
+    pub shapes: [Shape; 9],
+}
+
+/// A direction on the board; moves are the directions that the free space
+/// moves in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    /// The direction that the tile moves in when the free space moves in
+    /// this one.
+    pub fn opposite(&self) -> Direction {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        }
+    }
+
+    /// Unit step in grid coordinates.
+    pub fn grid_delta(&self) -> (i32, i32) {
+        match self {
+            Direction::Up => (0, -1),
+            Direction::Down => (0, 1),
+            Direction::Left => (-1, 0),
+            Direction::Right => (1, 0),
+        }
+    }
+
+    /// Parse a direction from its initial (`U`, `D`, `L`, `R`; case insensitive).
+    pub fn from_char(character: char) -> Option<Direction> {
+        match character.to_ascii_uppercase() {
+            'U' => Some(Direction::Up),
+            'D' => Some(Direction::Down),
+            'L' => Some(Direction::Left),
+            'R' => Some(Direction::Right),
+            _ => None,
+        }
+    }
+
+    /// The initial of the direction, see `from_char`.
+    pub fn to_char(self) -> char {
+        match self {
+            Direction::Up => 'U',
+            Direction::Down => 'D',
+            Direction::Left => 'L',
+            Direction::Right => 'R',
+        }
+    }
+}
+
+/// Parse a sequence of moves like "ULLD"; whitespace is ignored.
+pub fn parse_moves(text: &str) -> Result<Vec<Direction>, String> {
+    text.chars()
+        .filter(|character| !character.is_whitespace())
+        .enumerate()
+        .map(|(index, character)| {
+            Direction::from_char(character).ok_or(format!(
+                "invalid move '{}' at position {}; use U, D, L or R",
+                character,
+                index + 1
+            ))
+        })
+        .collect()
+}
+
+/// Write a sequence of moves like "ULLD", see `parse_moves`.
+pub fn format_moves(moves: &[Direction]) -> String {
+    moves.iter().map(|direction| direction.to_char()).collect()
+}
+
+/// A cell of the board, `x` from left to right and `y` from top to bottom,
+/// each 0 to 2.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct BoardIndex2d {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl BoardIndex2d {
+    /// index to index the array of shapes
+    pub fn to_index(self) -> usize {
+        (self.y * 3 + self.x) as usize
+    }
+    /// The cell of the index into the array of shapes, see `to_index`.
+    pub fn from_index(index: usize) -> BoardIndex2d {
+        BoardIndex2d {
+            x: (index % 3) as i32,
+            y: (index / 3) as i32,
+        }
+    }
+
+    /// Returns the neighbor in the given direction, if it exists.
+    pub fn neighbor(&self, direction: &Direction) -> Option<BoardIndex2d> {
+        let naive_index = match direction {
+            Direction::Up => BoardIndex2d {
+                x: self.x,
+                y: self.y - 1,
+            },
+            Direction::Down => BoardIndex2d {
+                x: self.x,
+                y: self.y + 1,
+            },
+            Direction::Left => BoardIndex2d {
+                x: self.x - 1,
+                y: self.y,
+            },
+            Direction::Right => BoardIndex2d {
+                x: self.x + 1,
+                y: self.y,
+            },
+        };
+        if naive_index.x < 0 || naive_index.x >= 3 || naive_index.y < 0 || naive_index.y >= 3 {
+            None
+        } else {
+            Some(naive_index)
+        }
+    }
+
+    /// Returns the direction in which `other` is a direct neighbor, if it is one.
+    pub fn direction_to(&self, other: &BoardIndex2d) -> Option<Direction> {
+        [
+            Direction::Up,
+            Direction::Down,
+            Direction::Left,
+            Direction::Right,
+        ]
+        .into_iter()
+        .find(|direction| self.neighbor(direction) == Some(*other))
+    }
+}
+
+fn has_duplicates<T: std::hash::Hash + std::cmp::Eq>(vec: &[T]) -> bool {
+    let mut seen = HashSet::new();
+    for value in vec {
+        if !seen.insert(value) {
+            return true;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_has_duplicates() {
+        // Test case with duplicates
+        let vec_with_duplicates = vec![1, 2, 3, 2, 4];
+        assert!(has_duplicates(&vec_with_duplicates));
+
+        // Test case without duplicates
+        let vec_without_duplicates = vec![1, 2, 3, 4, 5];
+        assert!(!has_duplicates(&vec_without_duplicates));
+
+        // Test case with empty vector
+        let empty_vec: Vec<i32> = vec![];
+        assert!(!has_duplicates(&empty_vec));
+
+        // Test case with a struct
+        let mut points = vec![Point { x: 1, y: 2 }, Point { x: 3, y: 4 }];
+        assert!(!has_duplicates(&points));
+        points.push(points[0]);
+        assert!(has_duplicates(&points));
+    }
+
+    #[test]
+    fn test_parse_moves() {
+        let moves = parse_moves("ul L\nd").unwrap();
+        assert!(matches!(
+            moves[..],
+            [
+                Direction::Up,
+                Direction::Left,
+                Direction::Left,
+                Direction::Down
+            ]
+        ));
+        assert!(parse_moves("").unwrap().is_empty());
+        assert_eq!(
+            parse_moves("UUX").err().unwrap(),
+            "invalid move 'X' at position 3; use U, D, L or R"
+        );
+    }
+
+    #[test]
+    fn test_direction_to() {
+        let center = BoardIndex2d { x: 1, y: 1 };
+        assert!(matches!(
+            center.direction_to(&BoardIndex2d { x: 1, y: 0 }),
+            Some(Direction::Up)
+        ));
+        assert!(matches!(
+            center.direction_to(&BoardIndex2d { x: 2, y: 1 }),
+            Some(Direction::Right)
+        ));
+        assert!(center.direction_to(&BoardIndex2d { x: 2, y: 2 }).is_none());
+        assert!(center.direction_to(&center).is_none());
+    }
+}
+
+/// Describe how the tile at `board_index` moves on the grid in direction `grid_dx, grid_dy`.
+pub struct MovingTile {
+    pub board_index: BoardIndex2d,
+    pub grid_dx: i32,
+    pub grid_dy: i32,
+}
+
+impl MovingTile {
+    /// Nothing moves.
+    pub fn no_move() -> MovingTile {
+        MovingTile {
+            board_index: BoardIndex2d { x: 0, y: 0 },
+            grid_dx: 0,
+            grid_dy: 0,
+        }
+    }
+}
+
+/// convert to the grid coordinates that are used for drawing/collision detection
+pub fn grid_coordinates_of(board_index: &BoardIndex2d) -> Point {
+    Point {
+        x: 1 + 2 * board_index.x,
+        y: 1 + 2 * board_index.y,
+    }
+}
+
+fn map_shape_points_to_grid_points(
+    shape: &Shape,
+    moving_tile: &MovingTile,
+    board_index: &BoardIndex2d,
+) -> Vec<Point> {
+    let dx: i32;
+    let dy: i32;
+    if *board_index == moving_tile.board_index {
+        dx = moving_tile.grid_dx;
+        dy = moving_tile.grid_dy;
+    } else {
+        dx = 0;
+        dy = 0;
+    }
+
+    let grid_coordinates = grid_coordinates_of(board_index);
+
+    shape
+        .get_points()
+        .iter()
+        .map(|point| Point {
+            x: point.x + grid_coordinates.x + dx,
+            y: point.y + grid_coordinates.y + dy,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests2 {
+    use itertools::Itertools;
+
+    use super::*;
+
+    #[test]
+    fn test_map_shape_points_to_grid_points() {
+        let top_left = BoardIndex2d { x: 0, y: 0 };
+        let shape = Shape::OneTR;
+        let local_points = shape.get_points();
+
+        let moving_top_left_tile = MovingTile {
+            board_index: top_left,
+            grid_dx: 0,
+            grid_dy: 0,
+        };
+
+        assert_eq!(
+            map_shape_points_to_grid_points(&shape, &moving_top_left_tile, &top_left),
+            local_points
+                .iter()
+                .map(|point| Point {
+                    x: point.x + 1,
+                    y: point.y + 1
+                })
+                .collect_vec()
+        );
+
+        let center = BoardIndex2d { x: 1, y: 1 };
+
+        assert_eq!(
+            map_shape_points_to_grid_points(&shape, &moving_top_left_tile, &center),
+            local_points
+                .iter()
+                .map(|point| Point {
+                    x: point.x + 3,
+                    y: point.y + 3
+                })
+                .collect_vec()
+        );
+
+        // now apply some dx
+
+        let dx = 2;
+
+        let moving_center_tile = MovingTile {
+            board_index: center,
+            grid_dx: dx,
+            grid_dy: 0,
+        };
+
+        // global points move accordingly
+
+        assert_eq!(
+            map_shape_points_to_grid_points(&shape, &moving_center_tile, &center),
+            local_points
+                .iter()
+                .map(|point| Point {
+                    x: point.x + 3 + dx,
+                    y: point.y + 3
+                })
+                .collect_vec()
+        );
+
+        // unless the moving tile is a differnt tile, then they stay the same
+
+        assert_eq!(
+            map_shape_points_to_grid_points(&shape, &moving_top_left_tile, &center),
+            local_points
+                .iter()
+                .map(|point| Point {
+                    x: point.x + 3,
+                    y: point.y + 3
+                })
+                .collect_vec()
+        );
+    }
+}
+
+impl Board {
+    /// A board with nothing but free spaces, to place shapes on.
+    pub fn empty_board() -> Board {
+        Board {
+            shapes: [Shape::Free; 9],
+        }
+    }
+
+    /// The board with the shape at the given cell.
+    pub fn set_shape(&self, board_index: &BoardIndex2d, shape: &Shape) -> Board {
+        let mut new_shapes = self.shapes;
+        new_shapes[board_index.to_index()] = *shape;
+        Board { shapes: new_shapes }
+    }
+
+    /// Whether the board has exactly one free space and no overlapping tiles.
+    pub fn is_valid(&self) -> bool {
+        // has exactly one free space
+        let mut free_count = 0;
+
+        for shape in &self.shapes {
+            if let Shape::Free = shape {
+                free_count += 1;
+            }
+        }
+
+        // and has no collissions
+        free_count == 1 && self.is_collission_free(&MovingTile::no_move())
+    }
+
+    /// Whether the ship is in front of the exit and can leave.
+    pub fn is_won(&self) -> bool {
+        let in_front_of_exit = BoardIndex2d { x: 1, y: 2 };
+        if self.shapes[in_front_of_exit.to_index()] != Shape::Ship {
+            false
+        } else {
+            let leave_board = MovingTile {
+                board_index: in_front_of_exit,
+                grid_dx: 0,
+                grid_dy: 1,
+            };
+            self.is_collission_free(&leave_board)
+        }
+    }
+
+    /// The cell of the free space; panics if there is none.
+    pub fn find_free_space(&self) -> BoardIndex2d {
+        for (i, shape) in self.shapes.iter().enumerate() {
+            if let Shape::Free = shape {
+                return BoardIndex2d::from_index(i);
+            }
+        }
+
+        panic!("No free space found");
+    }
+
+    /// Move the free space in the given direction iff it is possible (i.e. a valid move).
+    pub fn move_free_space(
+        &self,
+        space_moves_in_direction: &Direction,
+    ) -> Result<Board, MoveError> {
+        self.move_free_space_at(self.find_free_space(), space_moves_in_direction)
+    }
+
+    /// Like `move_free_space`, with the free space at the given position, for
+    /// boards with several cells without a shape, see `patterns`.
+    pub fn move_free_space_at(
+        &self,
+        free_space_position: BoardIndex2d,
+        space_moves_in_direction: &Direction,
+    ) -> Result<Board, MoveError> {
+        let neighbor_position = free_space_position
+            .neighbor(space_moves_in_direction)
+            .ok_or(MoveError::OutOfBounds)?;
+        let neighbor_moves_in_direction = space_moves_in_direction.opposite();
+
+        // check collission for move and final position
+        let blocked = match neighbor_moves_in_direction {
+            Direction::Up => [(0, -1), (0, -2)],
+            Direction::Down => [(0, 1), (0, 2)],
+            Direction::Left => [(-1, 0), (-2, 0)],
+            Direction::Right => [(1, 0), (2, 0)],
+        }
+        .map(|(dx, dy)| MovingTile {
+            board_index: neighbor_position,
+            grid_dx: dx,
+            grid_dy: dy,
+        })
+        .into_iter()
+        .find(|moving_tile| !self.is_collission_free(moving_tile));
+        if let Some(moving_tile) = blocked {
+            // only worked out for moves that are not possible, as it takes longer
+            let by = self
+                .collisions(&moving_tile)
+                .into_iter()
+                .map(|(board_index, _)| board_index)
+                .find(|&board_index| board_index != neighbor_position)
+                .expect("a collision involves two tiles");
+            return Err(MoveError::Blocked { by });
+        }
+
+        // collission free, hence construct the new situation
+        let mut new_shapes = self.shapes;
+        let free_space_index = free_space_position.to_index();
+        let neighbor_index = neighbor_position.to_index();
+        new_shapes.swap(free_space_index, neighbor_index);
+        Ok(Board { shapes: new_shapes })
+    }
+
+    /// The board after playing the moves one after the other; the error is
+    /// the first move that is not possible.
+    pub fn apply_moves(&self, moves: &[Direction]) -> Result<Board, InvalidMove> {
+        moves
+            .iter()
+            .enumerate()
+            .try_fold(*self, |board, (index, direction)| {
+                board
+                    .move_free_space(direction)
+                    .map_err(|error| InvalidMove {
+                        index,
+                        direction: *direction,
+                        error,
+                    })
+            })
+    }
+
+    /// The directions in which the free space can move, i.e. those for which
+    /// `move_free_space` returns a board.
+    pub fn legal_moves(&self) -> Vec<Direction> {
+        analysis::DIRECTIONS
+            .into_iter()
+            .filter(|direction| self.move_free_space(direction).is_ok())
+            .collect()
+    }
+
+    /// Mirror the whole board horizontally.
+    /// The exit is in the center of the bottom edge, so it stays where it is.
+    pub fn mirror(&self) -> Board {
+        let mut new_shapes = [Shape::Free; 9];
+        for (index, shape) in self.shapes.iter().enumerate() {
+            let board_index = BoardIndex2d::from_index(index);
+            let mirrored_index = BoardIndex2d {
+                x: 2 - board_index.x,
+                y: board_index.y,
+            };
+            new_shapes[mirrored_index.to_index()] = shape.mirror();
+        }
+        Board { shapes: new_shapes }
+    }
+
+    /// Rotate the whole board by 90°.
+    /// Note: The ship cannot rotate, so the result may contain collissions.
+    pub fn rotate(&self, clockwise: bool) -> Board {
+        let mut new_shapes = [Shape::Free; 9];
+        for (index, shape) in self.shapes.iter().enumerate() {
+            let board_index = BoardIndex2d::from_index(index);
+            let rotated_index = if clockwise {
+                BoardIndex2d {
+                    x: 2 - board_index.y,
+                    y: board_index.x,
+                }
+            } else {
+                BoardIndex2d {
+                    x: board_index.y,
+                    y: 2 - board_index.x,
+                }
+            };
+            new_shapes[rotated_index.to_index()] = shape.rotate(clockwise);
+        }
+        Board { shapes: new_shapes }
+    }
+
+    /// The board and its variants that are mirrored and/or rotated, i.e. all
+    /// boards that are equivalent up to symmetry (as far as the shapes go;
+    /// the ship cannot rotate).
+    pub fn symmetries(&self) -> [Board; 8] {
+        let mut boards = [*self; 8];
+        for index in 1..4 {
+            boards[index] = boards[index - 1].rotate(true);
+        }
+        for index in 4..8 {
+            boards[index] = boards[index - 4].mirror();
+        }
+        boards
+    }
+
+    /// FNV-1a hash of the shapes; unlike `Hash`, it is stable across builds
+    /// and platforms.
+    pub fn stable_hash(&self) -> u64 {
+        self.shapes.iter().fold(0xcbf29ce484222325, |hash, shape| {
+            (hash ^ *shape as u64).wrapping_mul(0x100000001b3)
+        })
+    }
+
+    /// The shapes cell by cell, five bits each, as a key for the boards that a
+    /// search has seen. Pieces are stored by the cell they are in, so boards
+    /// that only differ in which of two identical pieces (e.g. two `OneTL`)
+    /// is where are the same board and have the same key.
+    pub fn canonical_key(&self) -> u64 {
+        self.shapes
+            .iter()
+            .fold(0, |key, shape| key << 5 | *shape as u64)
+    }
+
+    /// The board or its mirror image, whichever has the smaller key; the same
+    /// for both of them.
+    pub fn mirror_canonical(&self) -> Board {
+        let mirror = self.mirror();
+        if mirror.canonical_key() < self.canonical_key() {
+            mirror
+        } else {
+            *self
+        }
+    }
+
+    /// Hash that is the same for boards that are mirror images or rotations of
+    /// each other, e.g. to recognize puzzles that are the same in disguise.
+    pub fn canonical_hash(&self) -> u64 {
+        self.symmetries()
+            .iter()
+            .map(Board::stable_hash)
+            .min()
+            .unwrap()
+    }
+
+    /// Check if the constellation on the board is collission free given the
+    /// move indicated by `moving_tile`. The `moving_tile` allows to specify a
+    /// direction in grid coordinates, hence it is possible to check a
+    /// collission for an intermediate state, i.e. _during_ movement.
+    /// Note: The moving tile pertains the actual tile, not the free space.
+    pub fn is_collission_free(&self, moving_tile: &MovingTile) -> bool {
+        let mut occupied_points: Vec<Point> = vec![];
+        for y in 0..3 {
+            for x in 0..3 {
+                let board_index = BoardIndex2d { x, y };
+                let shape = &self.shapes[board_index.to_index()];
+                map_shape_points_to_grid_points(shape, moving_tile, &board_index)
+                    .into_iter()
+                    .for_each(|point| occupied_points.push(point));
+            }
+        }
+        !has_duplicates(&occupied_points[..])
+    }
+
+    /// Like `is_collission_free`, but naming the collisions: the tiles that
+    /// overlap with another one, together with the grid points they share.
+    /// Empty if there are no collisions.
+    pub fn collisions(&self, moving_tile: &MovingTile) -> Vec<(BoardIndex2d, Point)> {
+        let occupied_points: Vec<(BoardIndex2d, Point)> = (0..9)
+            .map(BoardIndex2d::from_index)
+            .flat_map(|board_index| {
+                let shape = &self.shapes[board_index.to_index()];
+                map_shape_points_to_grid_points(shape, moving_tile, &board_index)
+                    .into_iter()
+                    .map(move |point| (board_index, point))
+            })
+            .collect();
+        occupied_points
+            .iter()
+            .filter(|(board_index, point)| {
+                occupied_points.iter().any(|(other_index, other_point)| {
+                    other_point == point && other_index != board_index
+                })
+            })
+            .copied()
+            .collect()
+    }
+}
+
+/// Why the free space cannot move in a direction, see
+/// `Board::move_free_space`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MoveError {
+    /// the free space is at the edge of the board
+    OutOfBounds,
+    /// the tile next to the free space would overlap with the one at `by`,
+    /// on its way or where it ends up
+    Blocked { by: BoardIndex2d },
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MoveError::OutOfBounds => write!(f, "the free space is at the edge of the board"),
+            MoveError::Blocked { by } => write!(f, "tile {} is in the way", by.to_index() + 1),
+        }
+    }
+}
+
+impl std::error::Error for MoveError {}
+
+/// A move of a sequence that is not possible, see `Board::apply_moves`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct InvalidMove {
+    /// of the move in the sequence, zero based
+    pub index: usize,
+    pub direction: Direction,
+    pub error: MoveError,
+}
+
+impl fmt::Display for InvalidMove {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "move {} ({:?}) is not possible: {}",
+            self.index + 1,
+            self.direction,
+            self.error
+        )
+    }
+}
+
+impl std::error::Error for InvalidMove {}
+
+/// How a game went.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GameRecord {
+    /// the boards from the start to the end of the game, along the line
+    /// that was played last
+    pub history: Vec<Board>,
+    /// number of moves along the history, according to the rules
+    pub moves: usize,
+    pub hints_used: u32,
+    /// until the game was won, or ended otherwise
+    pub elapsed: Duration,
+}
+
+impl GameRecord {
+    /// The moves from one board of the history to the next, `None` if two of
+    /// them are not one move apart.
+    pub fn played_moves(&self) -> Option<Vec<Direction>> {
+        self.history
+            .windows(2)
+            .map(|boards| {
+                let direction = boards[0]
+                    .find_free_space()
+                    .direction_to(&boards[1].find_free_space())?;
+                (boards[0].move_free_space(&direction).ok()? == boards[1]).then_some(direction)
+            })
+            .collect()
+    }
+}
+
+/// A valid board, the sample of the game, e.g. for tests and examples.
+pub fn sample_board() -> Board {
+    Board {
+        shapes: [
+            Shape::OneTL,
+            Shape::TwoDiagDown,
+            Shape::TwoHorT,
+            Shape::OneTL,
+            Shape::LargeCornerTL,
+            Shape::OneTL,
+            Shape::Ship,
+            Shape::LargeEdgeR,
+            Shape::Free,
+        ],
+    }
+}
+
+#[cfg(test)]
+mod test3 {
+    use super::*;
+
+    #[test]
+    fn test_is_collission_free_1() {
+        let mut board = super::Board::empty_board();
+        board.shapes[0] = Shape::Ship;
+        board.shapes[1] = Shape::Ship;
+
+        assert!(!board.is_collission_free(&MovingTile::no_move()));
+        let collisions = board.collisions(&MovingTile::no_move());
+        assert!(!collisions.is_empty());
+        assert!(collisions
+            .iter()
+            .all(|(board_index, _)| board_index.to_index() < 2));
+        assert!(sample_board().collisions(&MovingTile::no_move()).is_empty());
+    }
+
+    #[test]
+    fn test_is_collission_free_2() {
+        let mut board = super::Board::empty_board();
+        board.shapes[6] = Shape::Ship;
+        board.shapes[7] = Shape::TwoDiagDown;
+        println!("{board}");
+
+        assert!(!board.is_collission_free(&MovingTile::no_move()));
+    }
+
+    #[test]
+    fn test_is_collission_free_3() {
+        let mut board = super::Board::empty_board();
+        board.shapes[6] = Shape::Ship;
+        board.shapes[1] = Shape::Ship;
+        println!("{board}");
+
+        assert!(
+            board.is_collission_free(&MovingTile {
+                board_index: BoardIndex2d::from_index(1),
+                grid_dx: 0,
+                grid_dy: 0
+            }),
+            "before move",
+        );
+        assert!(
+            board.is_collission_free(&MovingTile {
+                board_index: BoardIndex2d::from_index(1),
+                grid_dx: 0,
+                grid_dy: 1
+            }),
+            "move 1"
+        );
+        assert!(
+            board.is_collission_free(&MovingTile {
+                board_index: BoardIndex2d::from_index(1),
+                grid_dx: 0,
+                grid_dy: 2
+            }),
+            "move 2"
+        );
+        assert!(
+            !board.is_collission_free(&MovingTile {
+                board_index: BoardIndex2d::from_index(1),
+                grid_dx: 0,
+                grid_dy: 3
+            }),
+            "move 3 -- now we have a collission"
+        );
+    }
+
+    #[test]
+    fn test_mirror_board() {
+        let board = sample_board();
+        let mirrored = board.mirror();
+        assert!(mirrored.is_valid());
+        assert_eq!(mirrored.shapes[8], Shape::Ship);
+        assert_eq!(mirrored.shapes[7], Shape::LargeEdgeL);
+        assert_eq!(mirrored.shapes[0], Shape::TwoHorT);
+        assert_eq!(mirrored.mirror().shapes, board.shapes);
+    }
+
+    #[test]
+    fn test_rotate_board() {
+        let board = sample_board();
+        let rotated = board.rotate(true);
+        assert_eq!(rotated.shapes[0], Shape::Ship);
+        assert_eq!(rotated.shapes[2], Shape::OneTR);
+        assert_eq!(rotated.rotate(false).shapes, board.shapes);
+    }
+
+    #[test]
+    fn test_canonical_hash() {
+        let board = sample_board();
+        for variant in board.symmetries() {
+            assert_eq!(variant.canonical_hash(), board.canonical_hash());
+        }
+        let moved = board.move_free_space(&Direction::Left).unwrap();
+        assert_ne!(moved.canonical_hash(), board.canonical_hash());
+        // stable across builds
+        assert_eq!(board.stable_hash(), 0x57b39e93a01d8207);
+    }
+
+    #[test]
+    fn test_canonical_key() {
+        use backtracking::{BreadthFirst, DepthFirst, SearchLimits, SearchStrategy, State};
+
+        let board = notation::parse_board("2t,2l,s,1tr,2r,1br,f,1bl,1bl").unwrap();
+        // no two reachable boards share a key
+        let boards = backtracking::distance_map(&board);
+        let keys: HashSet<u64> = boards.keys().map(Board::canonical_key).collect();
+        assert_eq!(keys.len(), boards.len());
+
+        // a board that searches tell apart by the whole board
+        #[derive(Clone)]
+        struct WholeBoard(Board);
+        impl State for WholeBoard {
+            type Key = Board;
+            fn key(&self) -> Board {
+                self.0
+            }
+            fn is_final(&self) -> bool {
+                self.0.is_won()
+            }
+            fn get_possible_successors(&self) -> impl Iterator<Item = Self> + use<> {
+                self.0.get_possible_successors().map(WholeBoard)
+            }
+        }
+        // the same solution with the same number of states expanded either way
+        fn compare(
+            strategy: &(impl SearchStrategy<Board> + SearchStrategy<WholeBoard>),
+            board: Board,
+        ) {
+            let limits = SearchLimits::default();
+            let (outcome, stats) =
+                SearchStrategy::<Board>::search(strategy, &board, &limits, &mut ());
+            let (whole, whole_stats) = SearchStrategy::<WholeBoard>::search(
+                strategy,
+                &WholeBoard(board),
+                &limits,
+                &mut (),
+            );
+            let whole: Option<Vec<Board>> = whole
+                .found()
+                .map(|states| states.into_iter().map(|state| state.0).collect());
+            assert!(whole.is_some());
+            assert_eq!(outcome.found(), whole);
+            assert_eq!(stats.nodes_expanded, whole_stats.nodes_expanded);
+        }
+        compare(&DepthFirst, board);
+        compare(&BreadthFirst, board);
+    }
+
+    #[test]
+    fn test_apply_moves() {
+        let board = sample_board();
+        assert_eq!(board.apply_moves(&[]), Ok(board));
+        assert!(board
+            .apply_moves(&parse_moves("LL").unwrap())
+            .unwrap()
+            .is_won());
+        let error = board
+            .apply_moves(&parse_moves("LRLU").unwrap())
+            .unwrap_err();
+        assert_eq!(
+            error,
+            InvalidMove {
+                index: 3,
+                direction: Direction::Up,
+                error: MoveError::Blocked {
+                    by: BoardIndex2d { x: 0, y: 2 }
+                }
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "move 4 (Up) is not possible: tile 7 is in the way"
+        );
+        assert_eq!(
+            board.move_free_space(&Direction::Right),
+            Err(MoveError::OutOfBounds)
+        );
+    }
+
+    #[test]
+    fn test_legal_moves() {
+        let board = sample_board();
+        assert_eq!(board.legal_moves(), vec![Direction::Left]);
+        for (board, _) in backtracking::distance_map(&board).into_iter().take(200) {
+            let directions: Vec<Direction> = analysis::successors(&board)
+                .into_iter()
+                .map(|(direction, _)| direction)
+                .collect();
+            assert_eq!(board.legal_moves(), directions);
+        }
+    }
+
+    fn sample_record() -> GameRecord {
+        let board = sample_board();
+        let moves = parse_moves("LRL").unwrap();
+        GameRecord {
+            history: (0..=moves.len())
+                .map(|count| board.apply_moves(&moves[..count]).unwrap())
+                .collect(),
+            moves: 3,
+            hints_used: 1,
+            elapsed: Duration::from_millis(4200),
+        }
+    }
+
+    #[test]
+    fn test_played_moves() {
+        let mut record = sample_record();
+        assert_eq!(record.played_moves(), Some(parse_moves("LRL").unwrap()));
+        record.history.remove(0);
+        assert_eq!(record.played_moves(), Some(parse_moves("RL").unwrap()));
+        record.history.push(sample_board().mirror());
+        assert_eq!(record.played_moves(), None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_roundtrip() {
+        let record = sample_record();
+        let json = serde_json::to_string(&record).unwrap();
+        assert!(json.starts_with(r#"{"history":[{"shapes":["OneTL","TwoDiagDown","#));
+        assert_eq!(serde_json::from_str::<GameRecord>(&json).unwrap(), record);
+        assert_eq!(
+            serde_json::to_string(&parse_moves("UL").unwrap()).unwrap(),
+            r#"["Up","Left"]"#
+        );
+    }
+}
+
+/// Randomized self-play: random legal moves on random valid boards.
+/// Set `SELF_PLAY_SEED` to replay a failing run.
+#[cfg(test)]
+mod test5 {
+    use super::*;
+    use generator::random_board;
+    use rand::rngs::StdRng;
+    use rand::seq::SliceRandom;
+    use rand::{Rng, SeedableRng};
+
+    const BOARDS: usize = 100;
+    const MOVES_PER_BOARD: usize = 100;
+
+    fn free_count(board: &Board) -> usize {
+        board
+            .shapes
+            .iter()
+            .filter(|&&shape| shape == Shape::Free)
+            .count()
+    }
+
+    #[test]
+    fn test_random_self_play() {
+        let seed = std::env::var("SELF_PLAY_SEED")
+            .ok()
+            .and_then(|seed| seed.parse().ok())
+            .unwrap_or_else(|| rand::thread_rng().gen());
+        let mut rng = StdRng::seed_from_u64(seed);
+        for _ in 0..BOARDS {
+            let mut board = random_board(&mut rng);
+            assert!(board.is_valid(), "seed {}:\n{}", seed, board);
+            for _ in 0..MOVES_PER_BOARD {
+                // some random boards are stuck right away
+                let Some(&direction) = board.legal_moves().choose(&mut rng) else {
+                    break;
+                };
+                let new_board = board.move_free_space(&direction).unwrap();
+                let context = format!("seed {}, moving {:?} on\n{}", seed, direction, board);
+                assert!(new_board.is_valid(), "{}", context);
+                assert_eq!(free_count(&new_board), 1, "{}", context);
+                let undone = new_board.move_free_space(&direction.opposite());
+                assert_eq!(
+                    undone.map(|undone| undone.shapes),
+                    Ok(board.shapes),
+                    "{}",
+                    context
+                );
+                board = new_board;
+            }
+        }
+    }
+}
@@ -0,0 +1,261 @@
+// board notation -------------------------------------------------------------
+//
+// A board is written as the codes of its nine shapes, row by row starting top
+// left, separated by commas, e.g. the sample board
+//
+//     1tl,2dd,2t,1tl,ctl,1tl,s,er,f
+//
+// Codes (case-insensitive):
+//   f                    free space
+//   s                    ship
+//   1tl 1tr 1bl 1br      one square in the corner top left, top right, ...
+//   2dd 2du              two squares on the diagonal down/up
+//   2t 2l 2b 2r          two squares along the top, left, bottom, right edge
+//   et el eb er          large shape along the top, left, bottom, right edge
+//   ctl ctr cbl cbr      large shape in the corner top left, top right, ...
+//
+// Boards and shapes implement `FromStr` for the notation, and `to_code` writes
+// it, as `Display` draws a board.
+//
+// A game is written as its starting board and the moves of the 'free' space,
+// e.g. a solution of the sample board
+//
+//     board: 1tl,2dd,2t,1tl,ctl,1tl,s,er,f
+//     moves: LL
+
+use crate::{format_moves, parse_moves, Board, Direction, Shape};
+use std::str::FromStr;
+
+const SHAPE_CODES: [(Shape, &str); 20] = [
+    (Shape::Free, "f"),
+    (Shape::Ship, "s"),
+    (Shape::OneTL, "1tl"),
+    (Shape::OneTR, "1tr"),
+    (Shape::OneBL, "1bl"),
+    (Shape::OneBR, "1br"),
+    (Shape::TwoDiagDown, "2dd"),
+    (Shape::TwoDiagUp, "2du"),
+    (Shape::TwoHorT, "2t"),
+    (Shape::TwoHorL, "2l"),
+    (Shape::TwoHorB, "2b"),
+    (Shape::TwoHorR, "2r"),
+    (Shape::LargeEdgeT, "et"),
+    (Shape::LargeEdgeL, "el"),
+    (Shape::LargeEdgeB, "eb"),
+    (Shape::LargeEdgeR, "er"),
+    (Shape::LargeCornerTL, "ctl"),
+    (Shape::LargeCornerTR, "ctr"),
+    (Shape::LargeCornerBL, "cbl"),
+    (Shape::LargeCornerBR, "cbr"),
+];
+
+fn shape_for_code(code: &str) -> Option<Shape> {
+    SHAPE_CODES
+        .iter()
+        .find(|(_, other)| other.eq_ignore_ascii_case(code))
+        .map(|(shape, _)| *shape)
+}
+
+fn code_for_shape(shape: &Shape) -> &'static str {
+    SHAPE_CODES
+        .iter()
+        .find(|(other, _)| other == shape)
+        .map(|(_, code)| *code)
+        .unwrap()
+}
+
+/// The notation of the board, see `parse_board`.
+pub fn board_to_notation(board: &Board) -> String {
+    shapes_to_notation(&board.shapes)
+}
+
+/// Any number of shapes in the notation of boards, e.g. the shapes that are
+/// still to be placed.
+pub fn shapes_to_notation(shapes: &[Shape]) -> String {
+    shapes
+        .iter()
+        .map(code_for_shape)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Parse shapes written with `shapes_to_notation`; an empty text is no shapes.
+pub fn parse_shapes(text: &str) -> Result<Vec<Shape>, String> {
+    if text.trim().is_empty() {
+        return Ok(vec![]);
+    }
+    text.split(',')
+        .map(str::trim)
+        .enumerate()
+        .map(|(index, code)| {
+            shape_for_code(code)
+                .ok_or_else(|| format!("unknown shape '{}' at position {}", code, index + 1))
+        })
+        .collect()
+}
+
+impl Shape {
+    /// The code of the shape in the notation.
+    pub fn to_code(&self) -> &'static str {
+        code_for_shape(self)
+    }
+}
+
+impl FromStr for Shape {
+    type Err = String;
+
+    fn from_str(code: &str) -> Result<Shape, String> {
+        shape_for_code(code.trim()).ok_or_else(|| format!("unknown shape '{}'", code.trim()))
+    }
+}
+
+impl Board {
+    /// The notation of the board, which `str::parse` reads back.
+    pub fn to_code(&self) -> String {
+        board_to_notation(self)
+    }
+}
+
+impl FromStr for Board {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Board, String> {
+        parse_board(text)
+    }
+}
+
+/// Parse a board from its notation and check that it is valid.
+pub fn parse_board(text: &str) -> Result<Board, String> {
+    let shapes = parse_shapes(text)?;
+    let Ok(shapes) = shapes.try_into() else {
+        return Err(format!(
+            "a board consists of 9 shapes separated by commas, found {}",
+            text.split(',').count()
+        ));
+    };
+    let board = Board { shapes };
+    if !board.is_valid() {
+        return Err(
+            "invalid board; it needs exactly one free space and no overlapping shapes".to_string(),
+        );
+    }
+    Ok(board)
+}
+
+/// A game as a starting board and the moves played on it, which need not
+/// be possible nor win; see `Board::apply_moves` to check them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecordedGame {
+    pub board: Board,
+    pub moves: Vec<Direction>,
+}
+
+impl RecordedGame {
+    /// The game as text, which `str::parse` reads back.
+    pub fn to_text(&self) -> String {
+        format!(
+            "board: {}\nmoves: {}\n",
+            board_to_notation(&self.board),
+            format_moves(&self.moves)
+        )
+    }
+}
+
+impl FromStr for RecordedGame {
+    type Err = String;
+
+    /// Lines starting with `#` are skipped, and the moves may go on over
+    /// several lines.
+    fn from_str(text: &str) -> Result<RecordedGame, String> {
+        let mut lines = text
+            .lines()
+            .filter(|line| !line.trim().is_empty() && !line.trim_start().starts_with('#'));
+        let mut field = |name: &str| match lines.next().and_then(|line| line.split_once(':')) {
+            Some((key, value)) if key.trim() == name => Ok(value.to_string()),
+            _ => Err(format!("expected the line '{}: ...'", name)),
+        };
+        let board = parse_board(&field("board")?)?;
+        let mut moves = field("moves")?;
+        moves.extend(lines);
+        Ok(RecordedGame {
+            board,
+            moves: parse_moves(&moves)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backtracking::distance_map;
+    use crate::sample_board;
+
+    #[test]
+    fn test_parse_board() {
+        let board = parse_board("1tl,2dd,2t,1tl,ctl,1tl,s,er,f").unwrap();
+        assert_eq!(board.shapes, sample_board().shapes);
+        let board = parse_board(" 1TL, 2dd,2t,1tl,CTL,1tl,S,er,f ").unwrap();
+        assert_eq!(board.shapes, sample_board().shapes);
+
+        assert!(parse_board("1tl,2dd,2t").is_err());
+        assert_eq!(
+            parse_board("1tl,2dd,2t,1tl,ctl,1tl,s,x,f").err(),
+            Some("unknown shape 'x' at position 8".to_string())
+        );
+        // two free spaces
+        assert!(parse_board("1tl,2dd,2t,1tl,ctl,1tl,s,f,f").is_err());
+
+        assert_eq!(parse_shapes(""), Ok(vec![]));
+        assert_eq!(parse_shapes("s, 1TL"), Ok(vec![Shape::Ship, Shape::OneTL]));
+        assert_eq!(shapes_to_notation(&[Shape::Ship, Shape::OneTL]), "s,1tl");
+    }
+
+    #[test]
+    fn test_board_to_notation() {
+        let notation = board_to_notation(&sample_board());
+        assert_eq!(notation, "1tl,2dd,2t,1tl,ctl,1tl,s,er,f");
+        assert_eq!(parse_board(&notation).unwrap(), sample_board());
+    }
+
+    #[test]
+    fn test_from_str_roundtrip() {
+        assert_eq!(SHAPE_CODES.map(|(shape, _)| shape), Shape::all());
+        for (shape, code) in SHAPE_CODES {
+            assert_eq!(shape.to_code(), code);
+            assert_eq!(code.parse(), Ok(shape));
+            assert_eq!(code.to_uppercase().parse(), Ok(shape));
+        }
+        assert_eq!("x".parse::<Shape>(), Err("unknown shape 'x'".to_string()));
+
+        assert_eq!(sample_board().to_code(), "1tl,2dd,2t,1tl,ctl,1tl,s,er,f");
+        for board in distance_map(&sample_board()).into_keys() {
+            assert_eq!(board.to_code().parse(), Ok(board));
+        }
+        assert!("1tl,2dd,2t".parse::<Board>().is_err());
+    }
+
+    #[test]
+    fn test_recorded_game() {
+        let game = RecordedGame {
+            board: sample_board(),
+            moves: vec![Direction::Left, Direction::Left],
+        };
+        let text = game.to_text();
+        assert_eq!(text, "board: 1tl,2dd,2t,1tl,ctl,1tl,s,er,f\nmoves: LL\n");
+        assert_eq!(text.parse(), Ok(game.clone()));
+        let text = "# by hand\nboard: 1tl,2dd,2t,1tl,ctl,1tl,s,er,f\nmoves: L\n\nL\n";
+        assert_eq!(text.parse(), Ok(game));
+        let started: RecordedGame = "board: 1tl,2dd,2t,1tl,ctl,1tl,s,er,f\nmoves:"
+            .parse()
+            .unwrap();
+        assert_eq!(started.moves, vec![]);
+
+        assert_eq!(
+            "moves: LL".parse::<RecordedGame>(),
+            Err("expected the line 'board: ...'".to_string())
+        );
+        assert!("board: 1tl,2dd,2t,1tl,ctl,1tl,s,er,f\nmoves: LX"
+            .parse::<RecordedGame>()
+            .is_err());
+    }
+}
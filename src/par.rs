@@ -0,0 +1,150 @@
+// par times ------------------------------------------------------------------
+//
+// The par time of a puzzle is its shortest solution played at a fixed pace.
+// Shortest solutions are cached in a file, as solving hard puzzles takes a
+// while.
+
+use asteroids_cli_game::analysis::solve;
+use asteroids_cli_game::Board;
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::Duration;
+
+pub const DEFAULT_SECONDS_PER_MOVE: u32 = 5;
+
+/// Time to solve a puzzle with the given shortest solution.
+pub fn par_time(optimal_moves: usize, seconds_per_move: u32) -> Duration {
+    Duration::from_secs(optimal_moves as u64 * seconds_per_move as u64)
+}
+
+/// Score of a won game: 100 for a shortest solution without hints within par
+/// time, less for detours, hints and time over par.
+pub fn score(
+    moves: usize,
+    optimal_moves: usize,
+    hints_used: u32,
+    elapsed: Duration,
+    par: Duration,
+) -> u32 {
+    let efficiency = optimal_moves as f64 / moves.max(1) as f64 / (1 + hints_used) as f64;
+    let pace = if elapsed <= par {
+        1.0
+    } else {
+        par.as_secs_f64() / elapsed.as_secs_f64()
+    };
+    (100.0 * efficiency * pace).round() as u32
+}
+
+/// Key of the cache: the same for a board and its mirror image, which have
+/// solutions of the same length. Unlike `canonical_hash`, rotations are not
+/// included, as they move the exit relative to the ship.
+fn key(board: &Board) -> u64 {
+    board.stable_hash().min(board.mirror().stable_hash())
+}
+
+/// Lengths of shortest solutions, `None` for puzzles without solution.
+/// Stored as one line per puzzle, key and length separated by a tab.
+pub struct ParCache {
+    /// where new entries are appended, if anywhere
+    path: Option<PathBuf>,
+    optimal_moves: HashMap<u64, Option<usize>>,
+}
+
+impl ParCache {
+    /// `$ASTEROIDS_PAR` if set, otherwise a file next to the statistics. The
+    /// cache is kept in memory only if neither is known or it cannot be read.
+    pub fn open_default() -> ParCache {
+        let path = match std::env::var_os("ASTEROIDS_PAR") {
+            Some(path) => Some(PathBuf::from(path)),
+            None => std::env::var_os("HOME").map(|home| {
+                PathBuf::from(home)
+                    .join(".asteroids-cli-game")
+                    .join("par.tsv")
+            }),
+        };
+        let text = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .unwrap_or_default();
+        let optimal_moves = text
+            .lines()
+            .filter_map(|line| {
+                let (key, moves) = line.split_once('\t')?;
+                let moves = match moves {
+                    "-" => None,
+                    _ => Some(moves.parse().ok()?),
+                };
+                Some((key.parse().ok()?, moves))
+            })
+            .collect();
+        ParCache {
+            path,
+            optimal_moves,
+        }
+    }
+
+    /// A cache that is not stored anywhere.
+    #[cfg(test)]
+    pub fn in_memory() -> ParCache {
+        ParCache {
+            path: None,
+            optimal_moves: HashMap::new(),
+        }
+    }
+
+    /// Length of the shortest solution of the board, solving it only if it is
+    /// not cached yet.
+    pub fn optimal_moves(&mut self, board: &Board) -> Option<usize> {
+        let key = key(board);
+        if let Some(&moves) = self.optimal_moves.get(&key) {
+            return moves;
+        }
+        let moves = solve(board).map(|moves| moves.len());
+        self.optimal_moves.insert(key, moves);
+        if let Some(path) = &self.path {
+            // the cache only saves time, so failing to write it is no problem
+            let _ = fs::create_dir_all(path.parent().unwrap_or(path)).and_then(|_| {
+                let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+                let moves = moves.map_or("-".to_string(), |moves| moves.to_string());
+                writeln!(file, "{}\t{}", key, moves)
+            });
+        }
+        moves
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use asteroids_cli_game::sample_board;
+
+    #[test]
+    fn test_score() {
+        let par = par_time(2, DEFAULT_SECONDS_PER_MOVE);
+        assert_eq!(par, Duration::from_secs(10));
+        assert_eq!(score(2, 2, 0, Duration::from_secs(4), par), 100);
+        assert_eq!(score(4, 2, 0, Duration::from_secs(4), par), 50);
+        assert_eq!(score(2, 2, 1, Duration::from_secs(4), par), 50);
+        assert_eq!(score(2, 2, 0, Duration::from_secs(40), par), 25);
+    }
+
+    #[test]
+    fn test_cache() {
+        let path = std::env::temp_dir().join(format!("asteroids-par-{}.tsv", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let mut cache = ParCache {
+            path: Some(path.clone()),
+            ..ParCache::in_memory()
+        };
+        assert_eq!(cache.optimal_moves(&sample_board()), Some(2));
+        let text = fs::read_to_string(&path).unwrap();
+        assert_eq!(text, format!("{}\t2\n", key(&sample_board())));
+        // the mirror image is not solved again
+        assert_eq!(cache.optimal_moves.len(), 1);
+        assert_eq!(cache.optimal_moves(&sample_board().mirror()), Some(2));
+        assert_eq!(fs::read_to_string(&path).unwrap(), text);
+        fs::remove_file(&path).unwrap();
+    }
+}
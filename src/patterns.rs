@@ -0,0 +1,230 @@
+// pattern database -----------------------------------------------------------
+//
+// Distances to the exit in abstractions of a board that keep only the ship
+// and one other piece. The cells of the other pieces stay, but without their
+// shapes, so that nothing collides with them. Every move on the board is also
+// a move in each abstraction, so none of their distances is more than the one
+// on the board, and the largest of them is a heuristic for A* that finds
+// shortest solutions. An abstraction has 504 positions, one table of
+// distances per shape of the piece kept; the tables can be cached in a file,
+// which the caller picks.
+
+use crate::analysis::DIRECTIONS;
+use crate::backtracking::Heuristic;
+use crate::notation::{parse_shapes, shapes_to_notation};
+use crate::{Board, BoardIndex2d, Shape};
+use std::collections::{HashMap, VecDeque};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+/// Distance of the positions of an abstraction from which the ship cannot
+/// reach the exit, and of the indices that are no positions at all.
+const UNREACHABLE: u8 = u8::MAX;
+
+/// The first line of a cache file. A file with another first line, e.g. one
+/// written by a version with other tables, is ignored and written anew.
+const CACHE_HEADER: &str = "asteroids pattern database\t1";
+
+/// Index into a table of the position with the free space, the ship and the
+/// piece kept in the given cells.
+fn index(free_space: usize, ship: usize, piece: usize) -> usize {
+    (free_space * 9 + ship) * 9 + piece
+}
+
+/// The distances of all positions of the abstraction that keeps a piece of
+/// the shape, by `index`.
+fn distance_table(shape: Shape) -> Vec<u8> {
+    let mut distances = vec![UNREACHABLE; 9 * 9 * 9];
+    // the positions that each position is reached from by one move
+    let mut predecessors: Vec<Vec<usize>> = vec![vec![]; distances.len()];
+    let mut queue = VecDeque::new();
+    for free_space in 0..9 {
+        for ship in (0..9).filter(|&ship| ship != free_space) {
+            for piece in (0..9).filter(|&piece| piece != free_space && piece != ship) {
+                // the free space is not the only cell without a shape, so it
+                // is given explicitly
+                let mut shapes = [Shape::Free; 9];
+                shapes[ship] = Shape::Ship;
+                shapes[piece] = shape;
+                let board = Board { shapes };
+                let position = index(free_space, ship, piece);
+                if board.is_won() {
+                    distances[position] = 0;
+                    queue.push_back(position);
+                }
+                let free_space_position = BoardIndex2d::from_index(free_space);
+                for direction in DIRECTIONS {
+                    if board
+                        .move_free_space_at(free_space_position, &direction)
+                        .is_err()
+                    {
+                        continue;
+                    }
+                    let neighbor = free_space_position.neighbor(&direction).unwrap().to_index();
+                    let moved = |cell| if cell == neighbor { free_space } else { cell };
+                    predecessors[index(neighbor, moved(ship), moved(piece))].push(position);
+                }
+            }
+        }
+    }
+    while let Some(position) = queue.pop_front() {
+        for &previous in &predecessors[position] {
+            if distances[previous] == UNREACHABLE {
+                distances[previous] = distances[position] + 1;
+                queue.push_back(previous);
+            }
+        }
+    }
+    distances
+}
+
+/// The distance tables for the shapes of some boards.
+pub struct PatternDatabase {
+    tables: HashMap<Shape, Vec<u8>>,
+}
+
+impl PatternDatabase {
+    /// The tables for the shapes of the board and of its mirror image, see
+    /// `open`.
+    pub fn for_board(path: Option<&Path>, board: &Board) -> PatternDatabase {
+        let mut shapes = board.shapes.to_vec();
+        shapes.extend(board.mirror().shapes);
+        PatternDatabase::open(path, &shapes)
+    }
+
+    /// The tables for the shapes, read from the file at `path` if they are
+    /// in it, otherwise computed and added to it; `None` to compute them all.
+    /// After `CACHE_HEADER`, one line per shape: the shape and the distances
+    /// separated by commas, separated by a tab.
+    pub fn open(path: Option<&Path>, shapes: &[Shape]) -> PatternDatabase {
+        let text = path
+            .and_then(|path| fs::read_to_string(path).ok())
+            .unwrap_or_default();
+        let mut lines = text.lines();
+        let current = lines.next() == Some(CACHE_HEADER);
+        let mut tables: HashMap<Shape, Vec<u8>> = lines
+            .filter(|_| current)
+            .filter_map(|line| {
+                let (shape, distances) = line.split_once('\t')?;
+                let [shape] = parse_shapes(shape).ok()?[..] else {
+                    return None;
+                };
+                let distances: Vec<u8> = distances
+                    .split(',')
+                    .map(|distance| distance.parse().ok())
+                    .collect::<Option<_>>()?;
+                (distances.len() == 9 * 9 * 9).then_some((shape, distances))
+            })
+            .collect();
+        let mut new_lines = vec![];
+        for &shape in shapes {
+            if shape == Shape::Free || shape == Shape::Ship || tables.contains_key(&shape) {
+                continue;
+            }
+            let distances = distance_table(shape);
+            let cells: Vec<String> = distances
+                .iter()
+                .map(|distance| distance.to_string())
+                .collect();
+            new_lines.push(format!(
+                "{}\t{}",
+                shapes_to_notation(&[shape]),
+                cells.join(",")
+            ));
+            tables.insert(shape, distances);
+        }
+        if let (Some(path), false) = (path, new_lines.is_empty()) {
+            // the cache only saves time, so failing to write it is no problem
+            let _ = fs::create_dir_all(path.parent().unwrap_or(path)).and_then(|_| {
+                let mut file = if current {
+                    OpenOptions::new().append(true).open(path)?
+                } else {
+                    let mut file = fs::File::create(path)?;
+                    writeln!(file, "{}", CACHE_HEADER)?;
+                    file
+                };
+                writeln!(file, "{}", new_lines.join("\n"))
+            });
+        }
+        PatternDatabase { tables }
+    }
+}
+
+/// The most moves that any abstraction takes. Abstractions from which the
+/// ship cannot reach the exit show that the board is lost, but a heuristic
+/// cannot tell, so they are left out.
+impl Heuristic<Board> for PatternDatabase {
+    fn estimate(&self, board: &Board) -> usize {
+        let Some(ship) = board.shapes.iter().position(|&shape| shape == Shape::Ship) else {
+            return 0;
+        };
+        let free_space = board.find_free_space().to_index();
+        board
+            .shapes
+            .iter()
+            .enumerate()
+            .filter_map(|(piece, shape)| {
+                let distance = self.tables.get(shape)?[index(free_space, ship, piece)];
+                (distance != UNREACHABLE).then_some(distance as usize)
+            })
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::solve;
+    use crate::backtracking::{distance_map, ExitDistance};
+    use crate::notation::parse_board;
+    use crate::sample_board;
+
+    #[test]
+    fn test_admissible() {
+        let board = parse_board("1tl,s,1tr,1bl,f,1br,2t,2b,2t").unwrap();
+        let patterns = PatternDatabase::open(None, &board.shapes);
+        let distances = distance_map(&board);
+        let mut better = 0;
+        for (position, distance) in &distances {
+            let estimate = patterns.estimate(position);
+            assert!(estimate <= *distance as usize, "{}", position);
+            if estimate > ExitDistance.estimate(position) {
+                better += 1;
+            }
+        }
+        assert!(
+            better > distances.len() / 2,
+            "{} of {}",
+            better,
+            distances.len()
+        );
+    }
+
+    #[test]
+    fn test_cache() {
+        let path =
+            std::env::temp_dir().join(format!("asteroids-patterns-{}.tsv", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let board = sample_board();
+        let patterns = PatternDatabase::open(Some(&path), &board.shapes);
+        let text = fs::read_to_string(&path).unwrap();
+        // the header and one line per shape other than the free space and the
+        // ship
+        assert_eq!(text.lines().next(), Some(CACHE_HEADER));
+        assert_eq!(text.lines().count(), 6);
+        let cached = PatternDatabase::open(Some(&path), &board.shapes);
+        assert_eq!(cached.tables, patterns.tables);
+        assert_eq!(fs::read_to_string(&path).unwrap(), text);
+        assert_eq!(patterns.estimate(&board), solve(&board).unwrap().len());
+
+        // a cache of another version is written anew
+        let old = text.lines().skip(1).collect::<Vec<_>>().join("\n");
+        fs::write(&path, old.replace(",0,", ",9,")).unwrap();
+        let rebuilt = PatternDatabase::open(Some(&path), &board.shapes);
+        assert_eq!(rebuilt.tables, patterns.tables);
+        assert_eq!(fs::read_to_string(&path).unwrap(), text);
+        fs::remove_file(&path).unwrap();
+    }
+}
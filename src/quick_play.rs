@@ -0,0 +1,132 @@
+// quick play -----------------------------------------------------------------
+//
+// Generated puzzles one after the other, as long as the player solves them.
+// The level of the next puzzle adapts to how efficiently the recent ones were
+// solved.
+
+use crate::stats::{GameStats, StatsStore};
+use crate::tui::{play_game_via_tui, Rules, Terminal};
+use asteroids_cli_game::generator::generate;
+use asteroids_cli_game::notation::{board_to_notation, parse_board};
+use std::collections::HashSet;
+
+pub const MAX_LEVEL: u32 = 10;
+
+/// Quick-play games that the next level is based on.
+const RECENT_GAMES: usize = 3;
+
+/// Solved this efficiently on average, the next puzzle is harder ...
+const LEVEL_UP_EFFICIENCY: f64 = 0.9;
+/// ... and below this, it is easier.
+const LEVEL_DOWN_EFFICIENCY: f64 = 0.5;
+
+/// Length of the shortest solution of puzzles of the level.
+pub fn optimal_moves_for_level(level: u32) -> usize {
+    4 * level as usize
+}
+
+/// Level of the next puzzle: one up after near-optimal solves, one down after
+/// struggles, otherwise the level of the last quick-play game.
+pub fn next_level(games: &[GameStats]) -> u32 {
+    let recent: Vec<&GameStats> = games
+        .iter()
+        .filter(|game| game.level.is_some())
+        .rev()
+        .take(RECENT_GAMES)
+        .collect();
+    let Some(last_level) = recent.first().and_then(|game| game.level) else {
+        return 1;
+    };
+    let efficiency = recent.iter().map(|game| game.efficiency()).sum::<f64>() / recent.len() as f64;
+    let level = if efficiency >= LEVEL_UP_EFFICIENCY {
+        last_level + 1
+    } else if efficiency < LEVEL_DOWN_EFFICIENCY {
+        last_level.saturating_sub(1)
+    } else {
+        last_level
+    };
+    level.clamp(1, MAX_LEVEL)
+}
+
+/// Play generated puzzles until one is not solved. Puzzles that were played
+/// before, even mirrored or rotated, are not repeated.
+/// Returns the statistics of the games played.
+pub fn play(
+    terminal: &mut Terminal,
+    rules: Rules,
+    store: Option<&StatsStore>,
+) -> crossterm::Result<Vec<GameStats>> {
+    let mut games = store
+        .and_then(|store| store.load().ok())
+        .unwrap_or_default();
+    let first_game = games.len();
+    let mut played: HashSet<u64> = games
+        .iter()
+        .filter_map(|game| parse_board(&game.board).ok())
+        .map(|board| board.canonical_hash())
+        .collect();
+    let mut rng = rand::thread_rng();
+    loop {
+        let level = next_level(&games);
+        let (board, optimal_moves) = generate(optimal_moves_for_level(level), &played, &mut rng);
+        played.insert(board.canonical_hash());
+        let record = play_game_via_tui(terminal, board, rules, Some(optimal_moves))?;
+        let won = record.history.last().unwrap().is_won();
+        let game = GameStats {
+            finished_at: GameStats::now(),
+            board: board_to_notation(&board),
+            level: Some(level),
+            optimal_moves: Some(optimal_moves),
+            moves: record.moves,
+            hints_used: record.hints_used,
+            won,
+            daily: None,
+        };
+        if let Some(store) = store {
+            // statistics are nice to have, but not worth interrupting the game
+            let _ = store.append(&game);
+        }
+        games.push(game);
+        if !won {
+            return Ok(games.split_off(first_game));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(level: Option<u32>, moves: usize, won: bool) -> GameStats {
+        GameStats {
+            finished_at: 0,
+            board: String::new(),
+            level,
+            optimal_moves: Some(8),
+            moves,
+            hints_used: 0,
+            won,
+            daily: None,
+        }
+    }
+
+    #[test]
+    fn test_next_level() {
+        assert_eq!(next_level(&[]), 1);
+        // games outside of quick play do not count
+        assert_eq!(next_level(&[game(None, 8, true)]), 1);
+        assert_eq!(next_level(&[game(Some(3), 8, true)]), 4);
+        assert_eq!(next_level(&[game(Some(3), 12, true)]), 3);
+        assert_eq!(next_level(&[game(Some(3), 8, false)]), 2);
+        assert_eq!(next_level(&[game(Some(1), 30, true)]), 1);
+        assert_eq!(next_level(&[game(Some(MAX_LEVEL), 8, true)]), MAX_LEVEL);
+        // based on the recent games
+        let games = [
+            game(Some(2), 8, false),
+            game(Some(2), 8, true),
+            game(Some(3), 8, true),
+            game(Some(4), 9, true),
+        ];
+        assert_eq!(next_level(&games), 5);
+    }
+}
@@ -0,0 +1,729 @@
+// drawing --------------------------------------------------------------------
+
+use crate::{
+    grid_coordinates_of, Board, BoardIndex2d, Direction, MovingTile, Point, Shape, ShapeFamily,
+};
+use std::fmt;
+use std::fmt::Display;
+
+/// Foreground colors as ANSI escape codes, so that the library does without
+/// a terminal crate, e.g. in the browser.
+#[derive(Clone, Copy)]
+enum Color {
+    Red = 31,
+    Green = 32,
+    Yellow = 33,
+    Blue = 34,
+    Magenta = 35,
+    Cyan = 36,
+    DarkGrey = 90,
+}
+
+impl Color {
+    fn paint(self, text: &str) -> String {
+        format!("\x1b[{}m{}\x1b[39m", self as u8, text)
+    }
+}
+
+fn drawing_character_for(shape: &Shape) -> char {
+    match shape.family() {
+        ShapeFamily::Free => 'o',
+        ShapeFamily::Ship => 'V',
+        ShapeFamily::Single => 'x',
+        ShapeFamily::Diagonal => '+',
+        ShapeFamily::Double => '|',
+        ShapeFamily::LargeEdge => '#',
+        ShapeFamily::LargeCorner => '%',
+    }
+}
+
+/// Marks grid points where tiles overlap.
+const COLLISION: char = '!';
+
+fn drawing_points_for(shape: &Shape) -> Vec<Point> {
+    match shape {
+        Shape::Free => vec![
+            Point { x: 0, y: 0 },
+            Point { x: 1, y: 0 },
+            Point { x: 0, y: 1 },
+            Point { x: 1, y: 1 },
+        ],
+        _ => shape.get_points(),
+    }
+}
+
+/// The tile that moved last and the cell it vacated, to make clear what
+/// changed with the move.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Highlight {
+    /// where the tile is now
+    pub moved: BoardIndex2d,
+    /// where the tile was, i.e. the free space now
+    pub vacated: BoardIndex2d,
+}
+
+impl Highlight {
+    /// Marks the cells of the highlighted tile ...
+    const MOVED: char = '*';
+    /// ... and of the cell it vacated.
+    const VACATED: char = '.';
+
+    /// The highlight for moving the free space of `board` in `direction`,
+    /// if the free space can go there.
+    pub fn of_move(board: &Board, direction: &Direction) -> Option<Highlight> {
+        let free_space = board.find_free_space();
+        Some(Highlight {
+            moved: free_space,
+            vacated: free_space.neighbor(direction)?,
+        })
+    }
+}
+
+/// Draw the 8x8 grid of the board, without border.
+/// The tile given by `moving_tile` is drawn at its shifted position.
+fn draw_grid(
+    board: &Board,
+    moving_tile: &MovingTile,
+    highlight: Option<&Highlight>,
+) -> [[char; 8]; 8] {
+    let mut grid = [[' '; 8]; 8];
+    // draw the free space first, so that tiles moving into it are drawn on top
+    let mut board_indices: Vec<BoardIndex2d> = (0..9).map(BoardIndex2d::from_index).collect();
+    board_indices.sort_by_key(|board_index| board.shapes[board_index.to_index()] != Shape::Free);
+    for board_index in board_indices {
+        let shape = &board.shapes[board_index.to_index()];
+        let (dx, dy) = if board_index == moving_tile.board_index {
+            (moving_tile.grid_dx, moving_tile.grid_dy)
+        } else {
+            (0, 0)
+        };
+        let grid_coordinates = grid_coordinates_of(&board_index);
+        let character = match highlight {
+            Some(highlight) if highlight.moved == board_index => Highlight::MOVED,
+            Some(highlight) if highlight.vacated == board_index => Highlight::VACATED,
+            _ => drawing_character_for(shape),
+        };
+        drawing_points_for(shape)
+            .iter()
+            .map(|point| Point {
+                x: point.x + grid_coordinates.x + dx,
+                y: point.y + grid_coordinates.y + dy,
+            })
+            .filter(|point| (0..8).contains(&point.x) && (0..8).contains(&point.y))
+            .for_each(|point| grid[point.y as usize][point.x as usize] = character);
+    }
+    grid
+}
+
+/// Split `text` into lines of at most `width` characters, breaking at spaces
+/// where possible.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = vec![String::new()];
+    for word in text.split(' ') {
+        let line = lines.last_mut().unwrap();
+        let line_length = line.chars().count();
+        if line_length > 0 && line_length + 1 + word.chars().count() > width {
+            lines.push(word.to_string());
+        } else {
+            if line_length > 0 {
+                line.push(' ');
+            }
+            line.push_str(word);
+        }
+    }
+    lines
+}
+
+/// Everything that is shown in one frame of the text user interface.
+pub struct Frame<'a> {
+    pub board: &'a Board,
+    pub moving_tile: &'a MovingTile,
+    /// e.g. move count and time
+    pub status: String,
+    /// abbreviated status, for small terminals
+    pub short_status: String,
+    pub message: String,
+    /// explanation of the keys
+    pub help: String,
+    pub highlight: Option<Highlight>,
+    /// grid points that more than one tile is drawn on
+    pub collisions: &'a [Point],
+}
+
+/// How frames are drawn, depending on the space available.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Renderer {
+    /// Double width cells with a frame around the board showing the exit,
+    /// followed by status, message and help.
+    Full,
+    /// Board with a single character border, abbreviated status and message.
+    Compact,
+}
+
+impl Renderer {
+    /// Minimal terminal size (width, height) needed by the renderer. For
+    /// `Full`, that is the board, the status, a message of two lines and a
+    /// help of five.
+    pub fn min_size(self) -> (u16, u16) {
+        match self {
+            Renderer::Full => (40, 18),
+            Renderer::Compact => (10, 12),
+        }
+    }
+
+    /// The most detailed renderer that shows all of the frame on a terminal of
+    /// the given size; `Full` only if its wrapped message and help fit, too.
+    pub fn for_frame(frame: &Frame, width: u16, height: u16) -> Renderer {
+        let (min_width, min_height) = Renderer::Full.min_size();
+        if width >= min_width
+            && height >= min_height
+            && Renderer::Full.render(frame, width).len() <= height as usize
+        {
+            Renderer::Full
+        } else {
+            Renderer::Compact
+        }
+    }
+
+    /// Draw the board, including its border, as lines of characters.
+    pub fn draw_board(self, board: &Board, moving_tile: &MovingTile) -> Vec<String> {
+        self.draw_highlighted_board(board, moving_tile, None)
+    }
+
+    /// Like `draw_board`, but marking the tile that moved last.
+    pub fn draw_highlighted_board(
+        self,
+        board: &Board,
+        moving_tile: &MovingTile,
+        highlight: Option<&Highlight>,
+    ) -> Vec<String> {
+        self.draw_bordered_grid(draw_grid(board, moving_tile, highlight))
+    }
+
+    fn draw_bordered_grid(self, grid: [[char; 8]; 8]) -> Vec<String> {
+        match self {
+            Renderer::Full => Theme::Unicode.draw_grid(&grid),
+            Renderer::Compact => {
+                let mut lines = vec!["+--------+".to_string()];
+                for row in grid {
+                    lines.push(format!("|{}|", row.iter().collect::<String>()));
+                }
+                lines.push("+--------+".to_string());
+                lines
+            }
+        }
+    }
+
+    /// The tile drawn at the given position, relative to the top left corner
+    /// of the board's border. Positions on the edge of the grid belong to the
+    /// closest tile.
+    pub fn tile_at(self, column: u16, row: u16) -> Option<BoardIndex2d> {
+        let cell_width = match self {
+            Renderer::Full => 2,
+            Renderer::Compact => 1,
+        };
+        if !(1..=8 * cell_width).contains(&column) || !(1..=8).contains(&row) {
+            return None;
+        }
+        // every tile covers two rows and columns of the grid, starting at 1
+        let tile = |grid: u16| (grid.saturating_sub(1) / 2).min(2) as i32;
+        Some(BoardIndex2d {
+            x: tile((column - 1) / cell_width),
+            y: tile(row - 1),
+        })
+    }
+
+    /// Draw a whole frame for a terminal that is `width` characters wide.
+    pub fn render(self, frame: &Frame, width: u16) -> Vec<String> {
+        let mut grid = draw_grid(frame.board, frame.moving_tile, frame.highlight.as_ref());
+        for point in frame.collisions {
+            grid[point.y as usize][point.x as usize] = COLLISION;
+        }
+        let mut lines = self.draw_bordered_grid(grid);
+        match self {
+            Renderer::Full => {
+                lines.push(frame.status.clone());
+                lines.extend(wrap(&frame.message, width as usize));
+                lines.extend(wrap(&frame.help, width as usize));
+            }
+            Renderer::Compact => {
+                lines.push(frame.short_status.clone());
+                lines.push(frame.message.clone());
+            }
+        }
+        lines
+    }
+}
+
+/// Characters and colors used to draw boards with double width cells.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Theme {
+    Ascii,
+    Unicode,
+    /// Unicode with a color for every kind of shape
+    Color,
+}
+
+impl Theme {
+    pub fn from_name(name: &str) -> Option<Theme> {
+        match name.to_lowercase().as_str() {
+            "ascii" => Some(Theme::Ascii),
+            "unicode" => Some(Theme::Unicode),
+            "color" | "colour" => Some(Theme::Color),
+            _ => None,
+        }
+    }
+
+    /// Corners top left, top right, bottom left, bottom right, then the
+    /// horizontal and vertical edge.
+    fn border(self) -> [char; 6] {
+        match self {
+            Theme::Ascii => ['+', '+', '+', '+', '-', '|'],
+            Theme::Unicode | Theme::Color => ['┌', '┐', '└', '┘', '─', '│'],
+        }
+    }
+
+    fn cell(self, character: char) -> String {
+        let cell = format!("{}{}", character, character);
+        if self != Theme::Color {
+            return cell;
+        }
+        let color = match character {
+            'V' => Color::Red,
+            'x' => Color::Yellow,
+            '+' => Color::Green,
+            '|' => Color::Cyan,
+            '#' => Color::Blue,
+            '%' => Color::Magenta,
+            _ => Color::DarkGrey,
+        };
+        color.paint(&cell)
+    }
+
+    /// Draw the grid with a border around it that shows the exit.
+    fn draw_grid(self, grid: &[[char; 8]; 8]) -> Vec<String> {
+        let [top_left, top_right, bottom_left, bottom_right, horizontal, vertical] = self.border();
+        let edge = |length| horizontal.to_string().repeat(length);
+        let mut lines = vec![format!("{}{}{}", top_left, edge(16), top_right)];
+        for row in grid {
+            let cells: String = row.iter().map(|&cell| self.cell(cell)).collect();
+            lines.push(format!("{}{}{}", vertical, cells, vertical));
+        }
+        // the exit is below the bottom center tile
+        lines.push(format!(
+            "{}{}{}{}{}",
+            bottom_left,
+            edge(6),
+            " ".repeat(4),
+            edge(6),
+            bottom_right
+        ));
+        lines
+    }
+
+    /// Draw the board, optionally labelling the rows and columns of the grid
+    /// with their coordinates and marking the exit.
+    pub fn draw_board(self, board: &Board, coordinates: bool, exit: bool) -> Vec<String> {
+        let grid = draw_grid(board, &MovingTile::no_move(), None);
+        let mut lines = self.draw_grid(&grid);
+        let mut indent = "";
+        if coordinates {
+            indent = "  ";
+            let last = lines.len() - 1;
+            for (row, line) in lines.iter_mut().enumerate() {
+                let label = if (1..last).contains(&row) {
+                    format!("{} ", row - 1)
+                } else {
+                    indent.to_string()
+                };
+                line.insert_str(0, &label);
+            }
+            let header: String = (0..8).map(|column| format!("{:<2}", column)).collect();
+            lines.insert(0, format!("{} {}", indent, header.trim_end()));
+        }
+        if exit {
+            // below the gap in the bottom edge
+            lines.push(format!("{}{}exit", indent, " ".repeat(7)));
+        }
+        lines
+    }
+}
+
+/// One line per move with right-aligned move numbers. Moves on an optimal
+/// line are green, deviations from it yellow, if `color` is set.
+pub fn move_log(moves: &[(Direction, Option<bool>)], color: bool) -> Vec<String> {
+    let width = moves.len().to_string().len();
+    moves
+        .iter()
+        .enumerate()
+        .map(|(index, (direction, optimal))| {
+            let (note, note_color) = match optimal {
+                Some(true) => ("optimal", Some(Color::Green)),
+                Some(false) => ("deviation", Some(Color::Yellow)),
+                None => ("", None),
+            };
+            let line = format!(
+                "{:>width$}. {:<5}  {}",
+                index + 1,
+                format!("{:?}", direction),
+                note
+            );
+            let line = line.trim_end().to_string();
+            match note_color {
+                Some(note_color) if color => note_color.paint(&line),
+                _ => line,
+            }
+        })
+        .collect()
+}
+
+/// Split the drawing of a board into its grid. Without the border of
+/// `Display`, the drawing is up to 8 lines, missing ones at the end empty.
+fn parse_grid(text: &str) -> Result<[[char; 8]; 8], String> {
+    let mut lines: Vec<Vec<char>> = text
+        .lines()
+        .map(|line| line.trim_end().chars().collect())
+        .collect();
+    let bordered: Vec<&Vec<char>> = lines.iter().filter(|line| !line.is_empty()).collect();
+    if bordered.len() == 10 && bordered[0].iter().collect::<String>() == "+--------+" {
+        lines = bordered[1..9]
+            .iter()
+            .map(|line| match line.as_slice() {
+                ['|', row @ .., '|'] => Ok(row.to_vec()),
+                _ => Err("expected each row of the board between '|'".to_string()),
+            })
+            .collect::<Result<_, _>>()?;
+    }
+    if lines.len() > 8 {
+        return Err(format!(
+            "expected at most 8 rows of the board, found {}",
+            lines.len()
+        ));
+    }
+    let mut grid = [[' '; 8]; 8];
+    for (y, line) in lines.iter().enumerate() {
+        if line.len() > 8 {
+            return Err(format!("row {} is longer than 8 characters", y + 1));
+        }
+        for (x, &character) in line.iter().enumerate() {
+            if !"oVx+|#% ".contains(character) {
+                return Err(format!(
+                    "unknown character '{}' in row {}, column {}",
+                    character,
+                    y + 1,
+                    x + 1
+                ));
+            }
+            grid[y][x] = character;
+        }
+    }
+    Ok(grid)
+}
+
+/// Find the shapes of the cells from `index` on, such that every shape is
+/// drawn where the grid shows its character.
+fn find_shapes(grid: &[[char; 8]; 8], shapes: &mut [Shape; 9], index: usize) -> Option<Board> {
+    if index == 9 {
+        let board = Board { shapes: *shapes };
+        return (board.is_valid() && draw_grid(&board, &MovingTile::no_move(), None) == *grid)
+            .then_some(board);
+    }
+    let grid_coordinates = grid_coordinates_of(&BoardIndex2d::from_index(index));
+    for shape in Shape::all() {
+        let fits = drawing_points_for(&shape).iter().all(|point| {
+            let x = point.x + grid_coordinates.x;
+            let y = point.y + grid_coordinates.y;
+            // tiles are drawn over the free space
+            !(0..8).contains(&x)
+                || !(0..8).contains(&y)
+                || shape == Shape::Free
+                || grid[y as usize][x as usize] == drawing_character_for(&shape)
+        });
+        if fits {
+            shapes[index] = shape;
+            if let Some(board) = find_shapes(grid, shapes, index + 1) {
+                return Some(board);
+            }
+        }
+    }
+    None
+}
+
+impl Board {
+    /// Parse the drawing that `Display` prints, e.g. to write down a board
+    /// as it is laid out in front of you. The border may be left out.
+    pub fn from_ascii(text: &str) -> Result<Board, String> {
+        let grid = parse_grid(text)?;
+        find_shapes(&grid, &mut [Shape::Free; 9], 0)
+            .ok_or_else(|| "no valid board is drawn like this".to_string())
+    }
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for line in Renderer::Compact.draw_board(self, &MovingTile::no_move()) {
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{sample_board, Direction};
+    use std::fs;
+    use std::path::Path;
+
+    #[test]
+    fn test_wrap() {
+        assert_eq!(wrap("", 10), vec![""]);
+        assert_eq!(
+            wrap("use the keys to move", 10),
+            vec!["use the", "keys to", "move"]
+        );
+        assert_eq!(wrap("incomprehensible", 10), vec!["incomprehensible"]);
+    }
+
+    #[test]
+    fn test_move_log() {
+        let mut moves = vec![(Direction::Up, Some(false)); 9];
+        moves.push((Direction::Left, Some(true)));
+        moves.push((Direction::Right, None));
+        let log = move_log(&moves, false);
+        assert_eq!(log[0], " 1. Up     deviation");
+        assert_eq!(log[9], "10. Left   optimal");
+        assert_eq!(log[10], "11. Right");
+        assert!(move_log(&moves, true)[0].contains("\x1b["));
+    }
+
+    #[test]
+    fn test_from_ascii() {
+        let board = sample_board();
+        assert_eq!(Board::from_ascii(&board.to_string()), Ok(board));
+        let drawing = board.to_string();
+        let lines: Vec<&str> = drawing.lines().collect();
+        let without_border: Vec<&str> = lines[1..9]
+            .iter()
+            .map(|line| line[1..9].trim_end())
+            .collect();
+        assert_eq!(Board::from_ascii(&without_border.join("\n")), Ok(board));
+        assert_eq!(
+            Board::from_ascii(&format!("{}\n x", without_border.join("\n"))),
+            Err("expected at most 8 rows of the board, found 9".to_string())
+        );
+        for board in crate::backtracking::distance_map(&board).into_keys() {
+            assert_eq!(Board::from_ascii(&board.to_string()), Ok(board));
+        }
+
+        assert_eq!(
+            Board::from_ascii(&drawing.replacen('V', "Q", 1)),
+            Err("unknown character 'Q' in row 6, column 1".to_string())
+        );
+        assert_eq!(
+            Board::from_ascii(&drawing.replacen('x', " ", 1)),
+            Err("no valid board is drawn like this".to_string())
+        );
+    }
+
+    #[test]
+    fn test_tile_at() {
+        assert_eq!(Renderer::Full.tile_at(0, 1), None);
+        assert_eq!(Renderer::Full.tile_at(1, 9), None);
+        assert_eq!(
+            Renderer::Full.tile_at(1, 1),
+            Some(BoardIndex2d { x: 0, y: 0 })
+        );
+        assert_eq!(
+            Renderer::Full.tile_at(8, 4),
+            Some(BoardIndex2d { x: 1, y: 1 })
+        );
+        assert_eq!(
+            Renderer::Full.tile_at(16, 8),
+            Some(BoardIndex2d { x: 2, y: 2 })
+        );
+        assert_eq!(
+            Renderer::Compact.tile_at(8, 2),
+            Some(BoardIndex2d { x: 2, y: 0 })
+        );
+        assert_eq!(Renderer::Compact.tile_at(9, 2), None);
+    }
+
+    #[test]
+    fn test_renderer_for_frame() {
+        let board = sample_board();
+        let mut frame = Frame {
+            board: &board,
+            moving_tile: &MovingTile::no_move(),
+            status: "Move 12 | Time 1:05".to_string(),
+            short_status: "M12 1:05".to_string(),
+            message: String::new(),
+            help: "Use arrow keys to move the 'free' space, or 'q' to quit.".to_string(),
+            highlight: None,
+            collisions: &[],
+        };
+        assert_eq!(Renderer::for_frame(&frame, 80, 24), Renderer::Full);
+        assert_eq!(Renderer::for_frame(&frame, 30, 24), Renderer::Compact);
+        assert_eq!(Renderer::for_frame(&frame, 80, 12), Renderer::Compact);
+        // rather than cutting off the end of the help
+        frame.help = "Use arrow keys to move the 'free' space. ".repeat(12);
+        assert_eq!(Renderer::for_frame(&frame, 40, 18), Renderer::Compact);
+        assert_eq!(Renderer::for_frame(&frame, 80, 24), Renderer::Full);
+    }
+
+    const RENDERERS: [Renderer; 2] = [Renderer::Full, Renderer::Compact];
+
+    /// Compare `actual` with the checked-in file `testdata/golden/<name>.txt`.
+    /// Run the tests with `UPDATE_GOLDEN=1` to (re-)write the files instead.
+    fn assert_golden(name: &str, actual: &str) {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("testdata/golden")
+            .join(format!("{}.txt", name));
+        if std::env::var_os("UPDATE_GOLDEN").is_some() {
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, actual).unwrap();
+            return;
+        }
+        let expected = fs::read_to_string(&path).unwrap_or_else(|_| {
+            panic!(
+                "missing {}; run the tests with UPDATE_GOLDEN=1 to create it",
+                path.display()
+            )
+        });
+        assert_eq!(
+            actual,
+            expected,
+            "rendering differs from {}",
+            path.display()
+        );
+    }
+
+    fn renderer_name(renderer: Renderer) -> String {
+        format!("{:?}", renderer).to_lowercase()
+    }
+
+    #[test]
+    fn test_golden_shapes() {
+        for renderer in RENDERERS {
+            let mut actual = String::new();
+            for shape in Shape::all() {
+                // the shape in the corners and the center
+                let mut board = Board::empty_board();
+                for index in [0, 4, 8] {
+                    board.shapes[index] = shape;
+                }
+                actual += &format!("{:?}\n", shape);
+                for line in renderer.draw_board(&board, &MovingTile::no_move()) {
+                    actual += &line;
+                    actual += "\n";
+                }
+            }
+            assert_golden(&format!("{}_shapes", renderer_name(renderer)), &actual);
+        }
+    }
+
+    #[test]
+    fn test_golden_animation() {
+        let board = sample_board();
+        let free_space = board.find_free_space();
+        for renderer in RENDERERS {
+            let mut actual = String::new();
+            for direction in [
+                Direction::Up,
+                Direction::Down,
+                Direction::Left,
+                Direction::Right,
+            ] {
+                // the tile next to the free space, half way into it
+                if let Some(board_index) = free_space.neighbor(&direction) {
+                    let (grid_dx, grid_dy) = direction.opposite().grid_delta();
+                    let moving_tile = MovingTile {
+                        board_index,
+                        grid_dx,
+                        grid_dy,
+                    };
+                    actual += &format!("tile {:?} moving\n", board_index);
+                    for line in renderer.draw_board(&board, &moving_tile) {
+                        actual += &line;
+                        actual += "\n";
+                    }
+                }
+            }
+            assert_golden(&format!("{}_animation", renderer_name(renderer)), &actual);
+        }
+    }
+
+    #[test]
+    fn test_golden_exit() {
+        // the ship in front of the exit and on its way out
+        let board = sample_board()
+            .move_free_space(&Direction::Left)
+            .and_then(|board| board.move_free_space(&Direction::Left))
+            .unwrap();
+        let in_front_of_exit = BoardIndex2d { x: 1, y: 2 };
+        assert!(board.is_won());
+        for renderer in RENDERERS {
+            let mut actual = String::new();
+            for grid_dy in [0, 1] {
+                let leaving = MovingTile {
+                    board_index: in_front_of_exit,
+                    grid_dx: 0,
+                    grid_dy,
+                };
+                for line in renderer.draw_board(&board, &leaving) {
+                    actual += &line;
+                    actual += "\n";
+                }
+            }
+            assert_golden(&format!("{}_exit", renderer_name(renderer)), &actual);
+        }
+    }
+
+    #[test]
+    fn test_golden_print() {
+        let board = sample_board();
+        let actual = Theme::Ascii.draw_board(&board, true, true).join("\n") + "\n";
+        assert_golden("print_ascii", &actual);
+        assert_eq!(
+            Theme::Unicode.draw_board(&board, false, false),
+            Renderer::Full.draw_board(&board, &MovingTile::no_move())
+        );
+        assert!(Theme::Color.draw_board(&board, false, false)[1].contains('\x1b'));
+    }
+
+    #[test]
+    fn test_highlight() {
+        let board = sample_board();
+        let after = board.move_free_space(&Direction::Left).unwrap();
+        let highlight = Highlight::of_move(&board, &Direction::Left).unwrap();
+        let lines = Renderer::Compact.draw_highlighted_board(
+            &after,
+            &MovingTile::no_move(),
+            Some(&highlight),
+        );
+        // the edge moved right into the bottom right corner
+        assert_eq!(lines[6], "|VVVV. **|");
+        assert_eq!(lines[7], "| VV.. **|");
+        assert_eq!(Highlight::of_move(&board, &Direction::Down), None);
+    }
+
+    #[test]
+    fn test_golden_frame() {
+        let board = sample_board();
+        let frame = Frame {
+            board: &board,
+            moving_tile: &MovingTile::no_move(),
+            status: "Move 12 | Time 1:05".to_string(),
+            short_status: "M12 1:05".to_string(),
+            message: "invalid move.".to_string(),
+            help: "Use arrow keys to move the 'free' space, or 'q' to quit.".to_string(),
+            highlight: None,
+            collisions: &[],
+        };
+        for renderer in RENDERERS {
+            let (width, _) = renderer.min_size();
+            let actual = renderer.render(&frame, width).join("\n") + "\n";
+            assert_golden(&format!("{}_frame", renderer_name(renderer)), &actual);
+        }
+    }
+}
@@ -0,0 +1,145 @@
+// statistics report ----------------------------------------------------------
+//
+// A summary of the statistics file in Markdown, e.g. to share it.
+
+use crate::daily::format_date;
+use crate::stats::GameStats;
+use std::collections::BTreeMap;
+
+/// How the game was started.
+fn mode(game: &GameStats) -> String {
+    match (game.daily, game.level) {
+        (Some(day), _) => format!("daily {}", format_date(day)),
+        (None, Some(level)) => format!("quick play, level {}", level),
+        (None, None) => "own board".to_string(),
+    }
+}
+
+/// Like `mode`, but all daily puzzles together.
+fn preset(game: &GameStats) -> String {
+    match game.daily {
+        Some(_) => "daily".to_string(),
+        None => mode(game),
+    }
+}
+
+fn percent(fraction: f64) -> String {
+    format!("{:.0}%", 100.0 * fraction)
+}
+
+/// Longest run of won games, and the run that the last game belongs to.
+fn streaks(games: &[GameStats]) -> (usize, usize) {
+    let mut longest = 0;
+    let mut current = 0;
+    for game in games {
+        current = if game.won { current + 1 } else { 0 };
+        longest = longest.max(current);
+    }
+    (longest, current)
+}
+
+/// The report for the games, oldest first.
+pub fn markdown_report(games: &[GameStats]) -> String {
+    let won = games.iter().filter(|game| game.won).count();
+    let (longest_streak, current_streak) = streaks(games);
+    let mut lines = vec![
+        "# Asteroids statistics".to_string(),
+        String::new(),
+        "## Totals".to_string(),
+        String::new(),
+        format!("- Games: {}", games.len()),
+        format!("- Won: {}", won),
+        format!(
+            "- Moves: {}",
+            games.iter().map(|game| game.moves).sum::<usize>()
+        ),
+        format!(
+            "- Hints: {}",
+            games.iter().map(|game| game.hints_used).sum::<u32>()
+        ),
+        format!("- Longest winning streak: {}", longest_streak),
+        format!("- Current winning streak: {}", current_streak),
+        String::new(),
+        "## Best solves".to_string(),
+        String::new(),
+        "| Preset | Board | Moves | Minimum | Efficiency |".to_string(),
+        "|---|---|---:|---:|---:|".to_string(),
+    ];
+    // the most efficient game, the earliest of equally efficient ones
+    let mut best: BTreeMap<String, &GameStats> = BTreeMap::new();
+    for game in games.iter().filter(|game| game.won) {
+        let entry = best.entry(preset(game)).or_insert(game);
+        if game.efficiency() > entry.efficiency() {
+            *entry = game;
+        }
+    }
+    for (preset, game) in &best {
+        lines.push(format!(
+            "| {} | `{}` | {} | {} | {} |",
+            preset,
+            game.board,
+            game.moves,
+            game.optimal_moves
+                .map_or("-".to_string(), |moves| moves.to_string()),
+            percent(game.efficiency())
+        ));
+    }
+    lines.extend([
+        String::new(),
+        "## Efficiency history".to_string(),
+        String::new(),
+        "| Date | Mode | Result | Moves | Hints | Efficiency |".to_string(),
+        "|---|---|---|---:|---:|---:|".to_string(),
+    ]);
+    for game in games {
+        lines.push(format!(
+            "| {} | {} | {} | {} | {} | {} |",
+            format_date(game.finished_at / (24 * 60 * 60)),
+            mode(game),
+            if game.won { "won" } else { "not won" },
+            game.moves,
+            game.hints_used,
+            percent(game.efficiency())
+        ));
+    }
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(level: Option<u32>, moves: usize, won: bool) -> GameStats {
+        GameStats {
+            finished_at: 1776211200,
+            board: "1tl,2dd,2t,1tl,ctl,1tl,s,er,f".to_string(),
+            level,
+            optimal_moves: Some(2),
+            moves,
+            hints_used: 0,
+            won,
+            daily: None,
+        }
+    }
+
+    #[test]
+    fn test_markdown_report() {
+        let games = [
+            game(Some(1), 4, true),
+            game(Some(1), 2, true),
+            game(None, 3, false),
+            game(None, 8, true),
+        ];
+        let report = markdown_report(&games);
+        let lines: Vec<&str> = report.lines().collect();
+        assert!(lines.contains(&"- Games: 4"));
+        assert!(lines.contains(&"- Won: 3"));
+        assert!(lines.contains(&"- Longest winning streak: 2"));
+        assert!(lines.contains(&"- Current winning streak: 1"));
+        assert!(lines
+            .contains(&"| quick play, level 1 | `1tl,2dd,2t,1tl,ctl,1tl,s,er,f` | 2 | 2 | 100% |"));
+        assert!(lines.contains(&"| own board | `1tl,2dd,2t,1tl,ctl,1tl,s,er,f` | 8 | 2 | 25% |"));
+        assert!(lines.contains(&"| 2026-04-15 | own board | not won | 3 | 0 | 0% |"));
+    }
+}
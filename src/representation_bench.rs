@@ -0,0 +1,323 @@
+// benchmark of board representations -----------------------------------------
+//
+// Compares the `[Shape; 9]` + `Vec<Point>` representation used by `Board` with
+// two alternatives on the same workload: computing all successors of a set of
+// boards. Run with
+//
+//     cargo test --release representation -- --ignored --nocapture
+
+use crate::analysis::DIRECTIONS;
+use crate::{grid_coordinates_of, sample_board, Board, BoardIndex2d, Direction, Shape};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const BOARDS: usize = 1000;
+const ROUNDS: usize = 20;
+
+/// Points of every shape relative to its tile, indexed by `shape as usize`.
+fn point_table() -> Vec<Vec<(i32, i32)>> {
+    let mut table = vec![vec![]; Shape::all().len()];
+    for shape in Shape::all() {
+        table[shape as usize] = shape
+            .get_points()
+            .iter()
+            .map(|point| (point.x, point.y))
+            .collect();
+    }
+    table
+}
+
+/// Successors of a board, in the order of `DIRECTIONS`.
+trait Representation {
+    type Board: Copy;
+    fn name(&self) -> &'static str;
+    fn convert(&self, board: &Board) -> Self::Board;
+    fn to_shapes(&self, board: &Self::Board) -> [Shape; 9];
+    fn move_free_space(&self, board: &Self::Board, direction: &Direction) -> Option<Self::Board>;
+}
+
+/// The representation that the game uses.
+struct Current;
+
+impl Representation for Current {
+    type Board = Board;
+
+    fn name(&self) -> &'static str {
+        "[Shape; 9] + Vec<Point>"
+    }
+
+    fn convert(&self, board: &Board) -> Board {
+        *board
+    }
+
+    fn to_shapes(&self, board: &Board) -> [Shape; 9] {
+        board.shapes
+    }
+
+    fn move_free_space(&self, board: &Board, direction: &Direction) -> Option<Board> {
+        board.move_free_space(direction).ok()
+    }
+}
+
+/// Shape points from a precomputed table, collisions checked on an occupancy
+/// grid on the stack; nothing is allocated.
+struct ConstTable {
+    points: Vec<Vec<(i32, i32)>>,
+}
+
+/// Side length of the occupancy grid; leaves room for tiles moving off the board.
+const TABLE_GRID: i32 = 12;
+
+impl ConstTable {
+    fn is_collission_free(&self, shapes: &[Shape; 9], moving: usize, dx: i32, dy: i32) -> bool {
+        let mut occupied = [false; (TABLE_GRID * TABLE_GRID) as usize];
+        for (index, shape) in shapes.iter().enumerate() {
+            let origin = grid_coordinates_of(&BoardIndex2d::from_index(index));
+            let (shift_x, shift_y) = if index == moving { (dx, dy) } else { (0, 0) };
+            for (x, y) in &self.points[*shape as usize] {
+                // offset by 2 so that all coordinates are positive
+                let cell = (y + origin.y + shift_y + 2) * TABLE_GRID + x + origin.x + shift_x + 2;
+                if std::mem::replace(&mut occupied[cell as usize], true) {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+}
+
+impl Representation for ConstTable {
+    type Board = [Shape; 9];
+
+    fn name(&self) -> &'static str {
+        "const point table"
+    }
+
+    fn convert(&self, board: &Board) -> [Shape; 9] {
+        board.shapes
+    }
+
+    fn to_shapes(&self, board: &[Shape; 9]) -> [Shape; 9] {
+        *board
+    }
+
+    fn move_free_space(&self, shapes: &[Shape; 9], direction: &Direction) -> Option<[Shape; 9]> {
+        let free = shapes.iter().position(|&shape| shape == Shape::Free)?;
+        let neighbor = BoardIndex2d::from_index(free)
+            .neighbor(direction)?
+            .to_index();
+        let (dx, dy) = direction.opposite().grid_delta();
+        if !(1..=2).all(|step| self.is_collission_free(shapes, neighbor, dx * step, dy * step)) {
+            return None;
+        }
+        let mut new_shapes = *shapes;
+        new_shapes.swap(free, neighbor);
+        Some(new_shapes)
+    }
+}
+
+/// One bit per cell of the 8x8 grid. The occupancy of all tiles is kept up to
+/// date, so a move only needs to check the masks of the moving tile.
+struct Bitboard {
+    /// mask of every shape on every tile, indexed by `[shape][tile]`
+    masks: Vec<[u64; 9]>,
+    /// the same, moved by one and two cells in every direction,
+    /// indexed by `[shape][tile][direction][step - 1]`
+    moved_masks: Vec<[[[u64; 2]; 4]; 9]>,
+}
+
+#[derive(Clone, Copy)]
+struct PackedBoard {
+    shapes: [Shape; 9],
+    free: usize,
+    occupied: u64,
+}
+
+/// Bits of the points at the given offset; points outside of the grid cannot
+/// collide with anything, so they are dropped.
+fn mask_of(points: &[(i32, i32)], tile: usize, dx: i32, dy: i32) -> u64 {
+    let origin = grid_coordinates_of(&BoardIndex2d::from_index(tile));
+    points
+        .iter()
+        .map(|(x, y)| (x + origin.x + dx, y + origin.y + dy))
+        .filter(|(x, y)| (0..8).contains(x) && (0..8).contains(y))
+        .fold(0, |mask, (x, y)| mask | 1 << (y * 8 + x))
+}
+
+impl Bitboard {
+    fn new() -> Bitboard {
+        let points = point_table();
+        let masks = points
+            .iter()
+            .map(|points| std::array::from_fn(|tile| mask_of(points, tile, 0, 0)))
+            .collect();
+        let moved_masks = points
+            .iter()
+            .map(|points| {
+                std::array::from_fn(|tile| {
+                    std::array::from_fn(|direction| {
+                        let (dx, dy) = DIRECTIONS[direction].grid_delta();
+                        std::array::from_fn(|step| {
+                            let step = step as i32 + 1;
+                            mask_of(points, tile, dx * step, dy * step)
+                        })
+                    })
+                })
+            })
+            .collect();
+        Bitboard { masks, moved_masks }
+    }
+}
+
+fn direction_index(direction: &Direction) -> usize {
+    DIRECTIONS
+        .iter()
+        .position(|other| other == direction)
+        .unwrap()
+}
+
+impl Representation for Bitboard {
+    type Board = PackedBoard;
+
+    fn name(&self) -> &'static str {
+        "bitboard"
+    }
+
+    fn convert(&self, board: &Board) -> PackedBoard {
+        let occupied = (0..9).fold(0, |occupied, tile| {
+            occupied | self.masks[board.shapes[tile] as usize][tile]
+        });
+        PackedBoard {
+            shapes: board.shapes,
+            free: board.find_free_space().to_index(),
+            occupied,
+        }
+    }
+
+    fn to_shapes(&self, board: &PackedBoard) -> [Shape; 9] {
+        board.shapes
+    }
+
+    fn move_free_space(&self, board: &PackedBoard, direction: &Direction) -> Option<PackedBoard> {
+        let neighbor = BoardIndex2d::from_index(board.free)
+            .neighbor(direction)?
+            .to_index();
+        let shape = board.shapes[neighbor] as usize;
+        let others = board.occupied & !self.masks[shape][neighbor];
+        let moved = &self.moved_masks[shape][neighbor][direction_index(&direction.opposite())];
+        if moved.iter().any(|mask| others & mask != 0) {
+            return None;
+        }
+        let mut shapes = board.shapes;
+        shapes.swap(board.free, neighbor);
+        Some(PackedBoard {
+            shapes,
+            free: neighbor,
+            occupied: others | self.masks[shape][board.free],
+        })
+    }
+}
+
+/// Boards reached by random walks from the sample board and its mirror image.
+fn workload() -> Vec<Board> {
+    let mut rng = StdRng::seed_from_u64(1);
+    let mut boards = vec![];
+    let mut board = sample_board();
+    while boards.len() < BOARDS {
+        if boards.len() == BOARDS / 2 {
+            board = sample_board().mirror();
+        }
+        let successors: Vec<Board> = DIRECTIONS
+            .iter()
+            .filter_map(|direction| board.move_free_space(direction).ok())
+            .collect();
+        board = *successors.choose(&mut rng).unwrap();
+        boards.push(board);
+    }
+    boards
+}
+
+/// Successors of all boards, for comparing the representations.
+fn successors<R: Representation>(representation: &R, boards: &[Board]) -> Vec<Option<[Shape; 9]>> {
+    boards
+        .iter()
+        .flat_map(|board| {
+            let board = representation.convert(board);
+            DIRECTIONS.iter().map(move |direction| {
+                representation
+                    .move_free_space(&board, direction)
+                    .map(|successor| representation.to_shapes(&successor))
+            })
+        })
+        .collect()
+}
+
+/// Time to compute all successors of all boards, `ROUNDS` times.
+fn time<R: Representation>(representation: &R, boards: &[Board]) -> Duration {
+    let boards: Vec<R::Board> = boards
+        .iter()
+        .map(|board| representation.convert(board))
+        .collect();
+    let started_at = Instant::now();
+    for _ in 0..ROUNDS {
+        for board in &boards {
+            for direction in &DIRECTIONS {
+                black_box(representation.move_free_space(black_box(board), direction));
+            }
+        }
+    }
+    started_at.elapsed()
+}
+
+#[test]
+fn test_representations_agree() {
+    let boards = workload();
+    let expected = successors(&Current, &boards);
+    assert_eq!(
+        successors(
+            &ConstTable {
+                points: point_table()
+            },
+            &boards
+        ),
+        expected
+    );
+    assert_eq!(successors(&Bitboard::new(), &boards), expected);
+}
+
+#[test]
+#[ignore = "benchmark; run with --release"]
+fn bench_representations() {
+    let boards = workload();
+    let calls = (ROUNDS * boards.len() * DIRECTIONS.len()) as f64;
+    let results = [
+        (Current.name(), time(&Current, &boards)),
+        {
+            let table = ConstTable {
+                points: point_table(),
+            };
+            (table.name(), time(&table, &boards))
+        },
+        {
+            let bitboard = Bitboard::new();
+            (bitboard.name(), time(&bitboard, &boards))
+        },
+    ];
+    let baseline = results[0].1;
+    println!();
+    println!(
+        "{:<26} {:>12} {:>10}",
+        "representation", "ns per move", "speedup"
+    );
+    for (name, duration) in results {
+        println!(
+            "{:<26} {:>12.1} {:>9.1}x",
+            name,
+            duration.as_nanos() as f64 / calls,
+            baseline.as_secs_f64() / duration.as_secs_f64()
+        );
+    }
+}
@@ -0,0 +1,125 @@
+// double-buffered screen -----------------------------------------------------
+
+use crossterm::{cursor::MoveTo, queue, style::Print};
+use std::io::Write;
+
+/// Character buffer of the whole terminal.
+/// Remembers what has been written to the terminal, so that only the cells
+/// that changed since the last frame need to be written again.
+pub struct Screen {
+    width: u16,
+    height: u16,
+    /// the frame that is being drawn
+    cells: Vec<char>,
+    /// the frame that the terminal currently shows
+    shown_cells: Vec<char>,
+}
+
+impl Screen {
+    /// Create a screen for a terminal that has just been cleared.
+    pub fn new(width: u16, height: u16) -> Screen {
+        let size = width as usize * height as usize;
+        Screen {
+            width,
+            height,
+            cells: vec![' '; size],
+            shown_cells: vec![' '; size],
+        }
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Start drawing a new frame.
+    pub fn clear(&mut self) {
+        self.cells.fill(' ');
+    }
+
+    /// Write `text` starting at column `x` of row `y`; whatever does not fit is cut off.
+    pub fn put_str(&mut self, x: u16, y: u16, text: &str) {
+        if y >= self.height {
+            return;
+        }
+        for (column, character) in (x..self.width).zip(text.chars()) {
+            self.cells[y as usize * self.width as usize + column as usize] = character;
+        }
+    }
+
+    /// Write all cells that changed since the last flush to `out`.
+    pub fn flush(&mut self, out: &mut impl Write) -> crossterm::Result<()> {
+        for y in 0..self.height {
+            let row_start = y as usize * self.width as usize;
+            let mut x = 0;
+            while x < self.width {
+                let index = row_start + x as usize;
+                if self.cells[index] == self.shown_cells[index] {
+                    x += 1;
+                    continue;
+                }
+                // write the whole run of changed cells at once
+                let run_start = x;
+                let mut run = String::new();
+                while x < self.width
+                    && self.cells[row_start + x as usize]
+                        != self.shown_cells[row_start + x as usize]
+                {
+                    run.push(self.cells[row_start + x as usize]);
+                    x += 1;
+                }
+                queue!(out, MoveTo(run_start, y), Print(run))?;
+            }
+        }
+        out.flush()?;
+        self.shown_cells.copy_from_slice(&self.cells);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flush_writes_only_changes() {
+        let mut screen = Screen::new(10, 3);
+        let mut out: Vec<u8> = vec![];
+        screen.put_str(2, 1, "abc");
+        screen.flush(&mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "\x1b[2;3Habc");
+
+        // same frame again: nothing to write
+        let mut out: Vec<u8> = vec![];
+        screen.clear();
+        screen.put_str(2, 1, "abc");
+        screen.flush(&mut out).unwrap();
+        assert!(out.is_empty());
+
+        // only the changed cell is written
+        let mut out: Vec<u8> = vec![];
+        screen.clear();
+        screen.put_str(2, 1, "aXc");
+        screen.flush(&mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "\x1b[2;4HX");
+
+        // removed text is overwritten with blanks
+        let mut out: Vec<u8> = vec![];
+        screen.clear();
+        screen.flush(&mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "\x1b[2;3H   ");
+    }
+
+    #[test]
+    fn test_put_str_is_clipped() {
+        let mut screen = Screen::new(4, 1);
+        screen.put_str(2, 0, "abc");
+        screen.put_str(0, 1, "outside");
+        let mut out: Vec<u8> = vec![];
+        screen.flush(&mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "\x1b[1;3Hab");
+    }
+}
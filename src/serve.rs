@@ -0,0 +1,251 @@
+// JSON-RPC server ------------------------------------------------------------
+//
+// The engine for other front-ends, e.g. a web UI, over JSON-RPC 2.0 on a local
+// TCP socket: one request per line, each answered with one line, and a game
+// of its own for every connection. The methods:
+//
+//     new_game   {"board": <notation or share code>}    the state of the game
+//     apply_move {"direction": "left"}                   the state of the game
+//     get_board                                          the state of the game
+//     solve                                              {"moves": ["left", ...]}
+//     generate   {"moves": 10, "seed": 1}                {"board": ..., "optimal_moves": ...}
+//
+// where the state of the game is {"board", "moves", "won", "legal_moves"}.
+
+use crate::stats::GameStats;
+use asteroids_cli_game::analysis::solve;
+use asteroids_cli_game::engine::{direction_name, parse_direction, parse_position, Engine};
+use asteroids_cli_game::generator::generate;
+use asteroids_cli_game::notation::board_to_notation;
+use asteroids_cli_game::Direction;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+
+/// Port of `serve` if none is given.
+pub const DEFAULT_PORT: u16 = 7878;
+
+// error codes of JSON-RPC, and one for requests that the game refuses
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const GAME_ERROR: i64 = -32000;
+
+/// Accept connections on the port of localhost until the process ends.
+pub fn serve(port: u16) -> io::Result<()> {
+    let listener = TcpListener::bind(("127.0.0.1", port))?;
+    eprintln!("Listening on {}", listener.local_addr()?);
+    for stream in listener.incoming() {
+        let stream = stream?;
+        thread::spawn(move || {
+            // a client that goes away is no reason to stop serving the others
+            let _ = handle_connection(stream);
+        });
+    }
+    Ok(())
+}
+
+fn handle_connection(stream: TcpStream) -> io::Result<()> {
+    let mut engine = Engine::new();
+    let mut writer = stream.try_clone()?;
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = handle_request(&mut engine, &line) {
+            writeln!(writer, "{}", response)?;
+        }
+    }
+    Ok(())
+}
+
+/// The response to a request, `None` for a notification, i.e. a request
+/// without id.
+pub fn handle_request(engine: &mut Engine, request: &str) -> Option<Value> {
+    let request: Value = match serde_json::from_str(request) {
+        Ok(request) => request,
+        Err(err) => return Some(error_response(Value::Null, PARSE_ERROR, err.to_string())),
+    };
+    let id = request.get("id").cloned();
+    let Some(method) = request.get("method").and_then(Value::as_str) else {
+        return Some(error_response(
+            id.unwrap_or(Value::Null),
+            INVALID_REQUEST,
+            "a request needs a method".to_string(),
+        ));
+    };
+    let params = request.get("params").cloned().unwrap_or(json!({}));
+    let result = call(engine, method, &params);
+    let id = id?;
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err((code, msg)) => error_response(id, code, msg),
+    })
+}
+
+fn error_response(id: Value, code: i64, message: String) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+fn call(engine: &mut Engine, method: &str, params: &Value) -> Result<Value, (i64, String)> {
+    let game_error = |msg: String| (GAME_ERROR, msg);
+    match method {
+        "new_game" => {
+            let board = string_param(params, "board")?;
+            let board = parse_position(board).map_err(|msg| (INVALID_PARAMS, msg))?;
+            if board.is_won() {
+                return Err(game_error("the board is already won".to_string()));
+            }
+            engine.set_position(board);
+            game_state(engine)
+        }
+        "apply_move" => {
+            let direction = string_param(params, "direction")?;
+            let direction = parse_direction(direction).map_err(|msg| (INVALID_PARAMS, msg))?;
+            engine.play(&[direction]).map_err(game_error)?;
+            game_state(engine)
+        }
+        "get_board" => game_state(engine),
+        "solve" => {
+            let board = engine.board().ok_or_else(|| game_error(no_game()))?;
+            Ok(json!({ "moves": solve(board).as_deref().map(direction_names) }))
+        }
+        "generate" => {
+            let moves = number_param(params, "moves")?.unwrap_or(10);
+            if moves == 0 {
+                return Err((INVALID_PARAMS, "puzzles take at least one move".to_string()));
+            }
+            let seed = number_param(params, "seed")?.unwrap_or_else(GameStats::now);
+            let mut rng = StdRng::seed_from_u64(seed);
+            let (board, optimal_moves) = generate(moves as usize, &HashSet::new(), &mut rng);
+            Ok(json!({
+                "board": board_to_notation(&board),
+                "optimal_moves": optimal_moves,
+            }))
+        }
+        _ => Err((METHOD_NOT_FOUND, format!("unknown method '{}'", method))),
+    }
+}
+
+fn no_game() -> String {
+    "no game; start one with new_game".to_string()
+}
+
+fn game_state(engine: &Engine) -> Result<Value, (i64, String)> {
+    let board = engine.board().ok_or_else(|| (GAME_ERROR, no_game()))?;
+    Ok(json!({
+        "board": board_to_notation(board),
+        "moves": engine.moves(),
+        "won": board.is_won(),
+        "legal_moves": direction_names(&board.legal_moves()),
+    }))
+}
+
+fn direction_names(directions: &[Direction]) -> Vec<String> {
+    directions.iter().map(direction_name).collect()
+}
+
+fn string_param<'a>(params: &'a Value, name: &str) -> Result<&'a str, (i64, String)> {
+    params.get(name).and_then(Value::as_str).ok_or_else(|| {
+        (
+            INVALID_PARAMS,
+            format!("missing string parameter '{}'", name),
+        )
+    })
+}
+
+fn number_param(params: &Value, name: &str) -> Result<Option<u64>, (i64, String)> {
+    match params.get(name) {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => value.as_u64().map(Some).ok_or_else(|| {
+            (
+                INVALID_PARAMS,
+                format!("parameter '{}' must be a whole number", name),
+            )
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(engine: &mut Engine, request: Value) -> Value {
+        handle_request(engine, &request.to_string()).unwrap()
+    }
+
+    #[test]
+    fn test_game() {
+        let mut engine = Engine::new();
+        let response = request(
+            &mut engine,
+            json!({"jsonrpc": "2.0", "id": 1, "method": "get_board"}),
+        );
+        assert_eq!(response["error"]["code"], GAME_ERROR);
+        let response = request(
+            &mut engine,
+            json!({"jsonrpc": "2.0", "id": 2, "method": "new_game",
+                   "params": {"board": "1tl,2dd,2t,1tl,ctl,1tl,s,er,f"}}),
+        );
+        assert_eq!(
+            response,
+            json!({"jsonrpc": "2.0", "id": 2, "result": {
+                "board": "1tl,2dd,2t,1tl,ctl,1tl,s,er,f",
+                "moves": 0,
+                "won": false,
+                "legal_moves": ["left"],
+            }})
+        );
+        let response = request(
+            &mut engine,
+            json!({"jsonrpc": "2.0", "id": 3, "method": "solve"}),
+        );
+        assert_eq!(response["result"]["moves"], json!(["left", "left"]));
+        let response = request(
+            &mut engine,
+            json!({"jsonrpc": "2.0", "id": 4, "method": "apply_move",
+                   "params": {"direction": "right"}}),
+        );
+        assert_eq!(response["error"]["code"], GAME_ERROR);
+        // a notification gets no response, but is played
+        assert_eq!(
+            handle_request(
+                &mut engine,
+                r#"{"jsonrpc": "2.0", "method": "apply_move", "params": {"direction": "left"}}"#
+            ),
+            None
+        );
+        assert_eq!(engine.moves(), 1);
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut engine = Engine::new();
+        assert_eq!(
+            handle_request(&mut engine, "{").unwrap()["error"]["code"],
+            PARSE_ERROR
+        );
+        let response = request(&mut engine, json!({"jsonrpc": "2.0", "id": 1}));
+        assert_eq!(response["error"]["code"], INVALID_REQUEST);
+        let response = request(
+            &mut engine,
+            json!({"jsonrpc": "2.0", "id": 1, "method": "fly"}),
+        );
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+        let response = request(
+            &mut engine,
+            json!({"jsonrpc": "2.0", "id": 1, "method": "generate", "params": {"moves": "ten"}}),
+        );
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+    }
+}
@@ -0,0 +1,200 @@
+// sessions -------------------------------------------------------------------
+//
+// Several puzzles played back to back, e.g. from a file with one board per
+// line, with the moves and time summed up over the session.
+
+use crate::par::ParCache;
+use crate::quick_play::optimal_moves_for_level;
+use crate::stats::{GameStats, StatsStore};
+use crate::tui::{format_duration, play_game_via_tui, Rules, Terminal};
+use asteroids_cli_game::generator::generate;
+use asteroids_cli_game::notation::{board_to_notation, parse_board};
+use asteroids_cli_game::Board;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::collections::HashSet;
+use std::time::Duration;
+
+/// Level of randomly generated session puzzles, see `quick_play`.
+const RANDOM_LEVEL: u32 = 2;
+
+/// A puzzle of the session and the length of its shortest solution.
+pub struct Puzzle {
+    pub board: Board,
+    pub optimal_moves: Option<usize>,
+}
+
+/// How a puzzle of the session went.
+pub struct PuzzleResult {
+    pub won: bool,
+    pub moves: usize,
+    pub elapsed: Duration,
+}
+
+/// Boards in notation, one per line; empty lines and lines starting with `#`
+/// are skipped.
+pub fn parse_puzzles(text: &str, par_cache: &mut ParCache) -> Result<Vec<Puzzle>, String> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        .map(|(index, line)| {
+            let board = parse_board(line).map_err(|msg| format!("line {}: {}", index + 1, msg))?;
+            if board.is_won() {
+                return Err(format!("line {}: the board is already won", index + 1));
+            }
+            Ok(Puzzle {
+                board,
+                optimal_moves: par_cache.optimal_moves(&board),
+            })
+        })
+        .collect()
+}
+
+/// Generated puzzles, one for each seed from `first_seed` on.
+pub fn random_puzzles(count: usize, first_seed: u64) -> Vec<Puzzle> {
+    (first_seed..first_seed + count as u64)
+        .map(|seed| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let (board, optimal_moves) = generate(
+                optimal_moves_for_level(RANDOM_LEVEL),
+                &HashSet::new(),
+                &mut rng,
+            );
+            Puzzle {
+                board,
+                optimal_moves: Some(optimal_moves),
+            }
+        })
+        .collect()
+}
+
+/// Play the puzzles one after the other until one is not won, showing the
+/// progress of the session in the status line.
+pub fn play(
+    terminal: &mut Terminal,
+    puzzles: &[Puzzle],
+    rules: Rules,
+    store: Option<&StatsStore>,
+) -> crossterm::Result<Vec<PuzzleResult>> {
+    let mut results: Vec<PuzzleResult> = vec![];
+    for (index, puzzle) in puzzles.iter().enumerate() {
+        terminal.set_context(Some(format!(
+            "Puzzle {} of {} | Total {} moves, {}",
+            index + 1,
+            puzzles.len(),
+            results.iter().map(|result| result.moves).sum::<usize>(),
+            format_duration(results.iter().map(|result| result.elapsed).sum())
+        )));
+        let record = play_game_via_tui(terminal, puzzle.board, rules, puzzle.optimal_moves)?;
+        let won = record.history.last().unwrap().is_won();
+        if let Some(store) = store {
+            // statistics are nice to have, but not worth interrupting the session
+            let _ = store.append(&GameStats {
+                finished_at: GameStats::now(),
+                board: board_to_notation(&puzzle.board),
+                level: None,
+                optimal_moves: puzzle.optimal_moves,
+                moves: record.moves,
+                hints_used: record.hints_used,
+                won,
+                daily: None,
+            });
+        }
+        results.push(PuzzleResult {
+            won,
+            moves: record.moves,
+            elapsed: record.elapsed,
+        });
+        if !won {
+            break;
+        }
+    }
+    terminal.set_context(None);
+    Ok(results)
+}
+
+/// One line per puzzle and the totals.
+pub fn summary(puzzles: &[Puzzle], results: &[PuzzleResult]) -> Vec<String> {
+    let mut lines: Vec<String> = puzzles
+        .iter()
+        .enumerate()
+        .map(|(index, puzzle)| {
+            let minimum = puzzle
+                .optimal_moves
+                .map_or(String::new(), |moves| format!(" (minimum {})", moves));
+            match results.get(index) {
+                Some(result) if result.won => format!(
+                    "Puzzle {}: won in {} moves{}, {}",
+                    index + 1,
+                    result.moves,
+                    minimum,
+                    format_duration(result.elapsed)
+                ),
+                Some(result) => format!(
+                    "Puzzle {}: not won after {} moves, {}",
+                    index + 1,
+                    result.moves,
+                    format_duration(result.elapsed)
+                ),
+                None => format!("Puzzle {}: not played", index + 1),
+            }
+        })
+        .collect();
+    lines.push(format!(
+        "Total: {} of {} won, {} moves, {}",
+        results.iter().filter(|result| result.won).count(),
+        puzzles.len(),
+        results.iter().map(|result| result.moves).sum::<usize>(),
+        format_duration(results.iter().map(|result| result.elapsed).sum())
+    ));
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use asteroids_cli_game::sample_board;
+
+    #[test]
+    fn test_parse_puzzles() {
+        let mut par_cache = ParCache::in_memory();
+        let text = "# warm-up\n1tl,2dd,2t,1tl,ctl,1tl,s,er,f\n\n";
+        let puzzles = parse_puzzles(text, &mut par_cache).unwrap();
+        assert_eq!(puzzles.len(), 1);
+        assert_eq!(puzzles[0].board, sample_board());
+        assert_eq!(puzzles[0].optimal_moves, Some(2));
+        assert_eq!(
+            parse_puzzles("\nf", &mut par_cache).err(),
+            Some("line 2: a board consists of 9 shapes separated by commas, found 1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_summary() {
+        let puzzle = || Puzzle {
+            board: sample_board(),
+            optimal_moves: Some(2),
+        };
+        let results = [
+            PuzzleResult {
+                won: true,
+                moves: 3,
+                elapsed: Duration::from_secs(30),
+            },
+            PuzzleResult {
+                won: false,
+                moves: 5,
+                elapsed: Duration::from_secs(45),
+            },
+        ];
+        assert_eq!(
+            summary(&[puzzle(), puzzle(), puzzle()], &results),
+            vec![
+                "Puzzle 1: won in 3 moves (minimum 2), 0:30",
+                "Puzzle 2: not won after 5 moves, 0:45",
+                "Puzzle 3: not played",
+                "Total: 1 of 3 won, 8 moves, 1:15",
+            ]
+        );
+    }
+}
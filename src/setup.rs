@@ -0,0 +1,141 @@
+// board setup in progress ----------------------------------------------------
+//
+// Placing the shapes one by one takes a while, so a setup can be saved and
+// resumed later. It is stored as a few lines of text, e.g.
+//
+//     placed: 1tl,2dd
+//     remaining: 2t,1tl,et,ctl,1tl,s,f
+//     selected: 0
+//     rotations: 1
+
+use asteroids_cli_game::generator::STANDARD_SHAPES;
+use asteroids_cli_game::notation::{parse_shapes, shapes_to_notation};
+use asteroids_cli_game::Shape;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+/// The state of `make_board_via_tui`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Setup {
+    /// shapes of the cells that are done, row by row starting top left
+    pub placed: Vec<Shape>,
+    pub remaining: Vec<Shape>,
+    /// index into `remaining` of the shape that is placed next
+    pub selected: usize,
+    /// of the selected shape, 0 to 3
+    pub clockwise_rotations: i32,
+}
+
+/// A setup from scratch, with the shapes of the sample board.
+impl Default for Setup {
+    fn default() -> Setup {
+        Setup {
+            placed: vec![],
+            remaining: STANDARD_SHAPES.to_vec(),
+            selected: 0,
+            clockwise_rotations: 0,
+        }
+    }
+}
+
+impl Setup {
+    fn to_text(&self) -> String {
+        format!(
+            "placed: {}\nremaining: {}\nselected: {}\nrotations: {}\n",
+            shapes_to_notation(&self.placed),
+            shapes_to_notation(&self.remaining),
+            self.selected,
+            self.clockwise_rotations
+        )
+    }
+
+    fn from_text(text: &str) -> Result<Setup, String> {
+        let mut fields = text.lines().map(|line| line.split_once(':'));
+        let mut field = |name: &str| match fields.next() {
+            Some(Some((key, value))) if key.trim() == name => Ok(value.trim().to_string()),
+            _ => Err(format!("expected the line '{}: ...'", name)),
+        };
+        let setup = Setup {
+            placed: parse_shapes(&field("placed")?)?,
+            remaining: parse_shapes(&field("remaining")?)?,
+            selected: field("selected")?
+                .parse()
+                .map_err(|_| "invalid selected shape".to_string())?,
+            clockwise_rotations: field("rotations")?
+                .parse()
+                .map_err(|_| "invalid rotations".to_string())?,
+        };
+        if setup.placed.len() + setup.remaining.len() != 9
+            || setup.selected >= setup.remaining.len()
+            || !(0..4).contains(&setup.clockwise_rotations)
+        {
+            return Err("the shapes do not make up a board".to_string());
+        }
+        Ok(setup)
+    }
+}
+
+/// The file that a setup is saved in.
+pub struct SetupStore {
+    path: PathBuf,
+}
+
+impl SetupStore {
+    /// `$ASTEROIDS_SETUP` if set, otherwise a file in the home directory.
+    /// `None` if neither is known.
+    pub fn open_default() -> Option<SetupStore> {
+        let path = match std::env::var_os("ASTEROIDS_SETUP") {
+            Some(path) => PathBuf::from(path),
+            None => PathBuf::from(std::env::var_os("HOME")?)
+                .join(".asteroids-cli-game")
+                .join("setup.txt"),
+        };
+        Some(SetupStore { path })
+    }
+
+    pub fn save(&self, setup: &Setup) -> io::Result<()> {
+        if let Some(directory) = self.path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+        fs::write(&self.path, setup.to_text())
+    }
+
+    pub fn load(&self) -> Result<Setup, String> {
+        let text = fs::read_to_string(&self.path)
+            .map_err(|err| format!("unable to read {}: {}", self.path.display(), err))?;
+        Setup::from_text(&text)
+            .map_err(|msg| format!("invalid setup in {}: {}", self.path.display(), msg))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_roundtrip() {
+        let mut setup = Setup::default();
+        assert_eq!(Setup::from_text(&setup.to_text()), Ok(setup.clone()));
+        setup.placed = setup.remaining.drain(..2).collect();
+        setup.selected = 3;
+        setup.clockwise_rotations = 1;
+        let text = setup.to_text();
+        assert!(text.starts_with("placed: 1tl,2du\n"));
+        assert_eq!(Setup::from_text(&text), Ok(setup));
+
+        assert!(Setup::from_text("placed: 1tl\nremaining: s\nselected: 0\nrotations: 0").is_err());
+        assert!(Setup::from_text("garbage").is_err());
+    }
+
+    #[test]
+    fn test_store() {
+        let path = std::env::temp_dir().join(format!("asteroids-setup-{}.txt", std::process::id()));
+        let store = SetupStore { path: path.clone() };
+        let _ = fs::remove_file(&path);
+        assert!(store.load().is_err());
+        store.save(&Setup::default()).unwrap();
+        assert_eq!(store.load(), Ok(Setup::default()));
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,161 @@
+// share codes ----------------------------------------------------------------
+//
+// A puzzle as a short string that players can exchange, e.g. in a chat or a
+// URL: the shapes of the board, 5 bits each, optionally followed by the
+// length of its shortest solution and always by a check byte, so that a
+// mistyped code is noticed, all in URL-safe base64 without padding.
+
+use crate::{Board, Shape};
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Bytes of the nine shapes.
+const BOARD_BYTES: usize = 6;
+
+/// A puzzle read from a share code.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SharedPuzzle {
+    pub board: Board,
+    /// length of the shortest solution, if the code includes it
+    pub optimal_moves: Option<usize>,
+}
+
+/// The share code of the board, with the length of its shortest solution if
+/// given; lengths beyond 255 are left out.
+pub fn encode(board: &Board, optimal_moves: Option<usize>) -> String {
+    let key = board.canonical_key();
+    let mut bytes: Vec<u8> = key.to_be_bytes()[8 - BOARD_BYTES..].to_vec();
+    if let Some(moves) = optimal_moves.and_then(|moves| u8::try_from(moves).ok()) {
+        bytes.push(moves);
+    }
+    bytes.push(check_byte(&bytes));
+    to_base64(&bytes)
+}
+
+/// The puzzle of a share code, which must be a valid board.
+pub fn decode(code: &str) -> Result<SharedPuzzle, String> {
+    let invalid = || format!("'{}' is not a share code", code.trim());
+    let bytes = from_base64(code.trim()).ok_or_else(invalid)?;
+    let (check, data) = bytes.split_last().ok_or_else(invalid)?;
+    if !(BOARD_BYTES..=BOARD_BYTES + 1).contains(&data.len()) {
+        return Err(invalid());
+    }
+    if *check != check_byte(data) {
+        return Err(format!(
+            "the share code '{}' is damaged; check it for typos",
+            code.trim()
+        ));
+    }
+    let key = data[..BOARD_BYTES]
+        .iter()
+        .fold(0u64, |key, &byte| key << 8 | byte as u64);
+    let mut shapes = [Shape::Free; 9];
+    for (index, shape) in shapes.iter_mut().enumerate() {
+        let code = (key >> (5 * (8 - index))) & 0b11111;
+        *shape = *Shape::all().get(code as usize).ok_or_else(invalid)?;
+    }
+    let board = Board { shapes };
+    if !board.is_valid() {
+        return Err(format!(
+            "the share code '{}' is not a valid board",
+            code.trim()
+        ));
+    }
+    Ok(SharedPuzzle {
+        board,
+        optimal_moves: data.get(BOARD_BYTES).map(|&moves| moves as usize),
+    })
+}
+
+/// A byte that changes with any single changed byte and with swapped ones.
+fn check_byte(bytes: &[u8]) -> u8 {
+    bytes
+        .iter()
+        .fold(0x5a, |check: u8, &byte| check.rotate_left(3) ^ byte)
+}
+
+fn to_base64(bytes: &[u8]) -> String {
+    let mut text = String::new();
+    for chunk in bytes.chunks(3) {
+        let bits = chunk.iter().enumerate().fold(0u32, |bits, (index, &byte)| {
+            bits | (byte as u32) << (16 - 8 * index)
+        });
+        for index in 0..=chunk.len() {
+            text.push(BASE64_ALPHABET[(bits >> (18 - 6 * index) & 0b111111) as usize] as char);
+        }
+    }
+    text
+}
+
+/// `None` if the text is not URL-safe base64 without padding.
+fn from_base64(text: &str) -> Option<Vec<u8>> {
+    let mut bytes = vec![];
+    for chunk in text.as_bytes().chunks(4) {
+        if chunk.len() < 2 {
+            return None;
+        }
+        let mut bits = 0u32;
+        for (index, character) in chunk.iter().enumerate() {
+            let value = BASE64_ALPHABET
+                .iter()
+                .position(|candidate| candidate == character)?;
+            bits |= (value as u32) << (18 - 6 * index);
+        }
+        bytes.extend(&bits.to_be_bytes()[1..chunk.len()]);
+    }
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sample_board;
+
+    #[test]
+    fn test_roundtrip() {
+        let board = sample_board();
+        let code = encode(&board, Some(2));
+        assert_eq!(code.len(), 11);
+        assert!(code
+            .chars()
+            .all(|character| character.is_ascii_alphanumeric() || "-_".contains(character)));
+        assert_eq!(
+            decode(&code),
+            Ok(SharedPuzzle {
+                board,
+                optimal_moves: Some(2)
+            })
+        );
+        assert_eq!(
+            decode(&encode(&board, None)),
+            Ok(SharedPuzzle {
+                board,
+                optimal_moves: None
+            })
+        );
+        assert_eq!(
+            decode(&encode(&board, Some(300))).unwrap().optimal_moves,
+            None
+        );
+    }
+
+    #[test]
+    fn test_invalid_codes() {
+        let code = encode(&sample_board(), Some(2));
+        let mut typo = code.clone().into_bytes();
+        typo[3] = if typo[3] == b'A' { b'B' } else { b'A' };
+        let typo = String::from_utf8(typo).unwrap();
+        assert_eq!(
+            decode(&typo),
+            Err(format!(
+                "the share code '{}' is damaged; check it for typos",
+                typo
+            ))
+        );
+        assert_eq!(decode("a+b"), Err("'a+b' is not a share code".to_string()));
+        assert!(decode("").is_err());
+        assert!(decode(&code[..8]).is_err());
+        assert!(decode(&encode(&Board::empty_board(), None)).is_err());
+    }
+}
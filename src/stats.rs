@@ -0,0 +1,188 @@
+// statistics of played games -------------------------------------------------
+//
+// Every game is appended as one line to a text file, with the fields separated
+// by tabs and `-` for missing values.
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// One game, as stored in the statistics file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GameStats {
+    /// seconds since the Unix epoch
+    pub finished_at: u64,
+    /// the board at the start, see `notation`
+    pub board: String,
+    /// level of the puzzle, for games in quick-play mode
+    pub level: Option<u32>,
+    /// length of the shortest solution, if there is one
+    pub optimal_moves: Option<usize>,
+    pub moves: usize,
+    pub hints_used: u32,
+    pub won: bool,
+    /// the day of the daily puzzle, see `daily`
+    pub daily: Option<u64>,
+}
+
+fn optional<T: ToString>(value: &Option<T>) -> String {
+    value.as_ref().map_or("-".to_string(), T::to_string)
+}
+
+fn parse_optional<T: std::str::FromStr>(field: &str) -> Option<Option<T>> {
+    match field {
+        "-" => Some(None),
+        _ => field.parse().ok().map(Some),
+    }
+}
+
+impl GameStats {
+    pub fn now() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs())
+    }
+
+    fn to_line(&self) -> String {
+        [
+            self.finished_at.to_string(),
+            self.board.clone(),
+            optional(&self.level),
+            optional(&self.optimal_moves),
+            self.moves.to_string(),
+            self.hints_used.to_string(),
+            self.won.to_string(),
+            optional(&self.daily),
+        ]
+        .join("\t")
+    }
+
+    /// `None` if the line is malformed.
+    fn from_line(line: &str) -> Option<GameStats> {
+        let fields: Vec<&str> = line.split('\t').collect();
+        // lines written before daily puzzles lack the last field
+        let (fields, daily) = match fields[..] {
+            [ref fields @ .., daily] if fields.len() == 7 => (fields, parse_optional(daily)?),
+            _ => (&fields[..], None),
+        };
+        let [finished_at, board, level, optimal_moves, moves, hints_used, won] = fields[..] else {
+            return None;
+        };
+        Some(GameStats {
+            finished_at: finished_at.parse().ok()?,
+            board: board.to_string(),
+            level: parse_optional(level)?,
+            optimal_moves: parse_optional(optimal_moves)?,
+            moves: moves.parse().ok()?,
+            hints_used: hints_used.parse().ok()?,
+            won: won.parse().ok()?,
+            daily,
+        })
+    }
+
+    /// How close the game came to the shortest solution: 1 for an optimal
+    /// solution without hints, less for detours or hints, 0 if not won.
+    pub fn efficiency(&self) -> f64 {
+        match self.optimal_moves {
+            Some(optimal_moves) if self.won && self.moves > 0 => {
+                optimal_moves as f64 / self.moves as f64 / (1 + self.hints_used) as f64
+            }
+            _ => 0.0,
+        }
+    }
+}
+
+/// The file that the statistics are kept in.
+pub struct StatsStore {
+    path: PathBuf,
+}
+
+impl StatsStore {
+    /// `$ASTEROIDS_STATS` if set, otherwise a file in the home directory.
+    /// `None` if neither is known.
+    pub fn open_default() -> Option<StatsStore> {
+        let path = match std::env::var_os("ASTEROIDS_STATS") {
+            Some(path) => PathBuf::from(path),
+            None => PathBuf::from(std::env::var_os("HOME")?)
+                .join(".asteroids-cli-game")
+                .join("stats.tsv"),
+        };
+        Some(StatsStore { path })
+    }
+
+    /// All games recorded so far, oldest first; malformed lines are skipped.
+    pub fn load(&self) -> io::Result<Vec<GameStats>> {
+        match fs::read_to_string(&self.path) {
+            Ok(text) => Ok(text.lines().filter_map(GameStats::from_line).collect()),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(vec![]),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn append(&self, stats: &GameStats) -> io::Result<()> {
+        if let Some(directory) = self.path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", stats.to_line())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(level: Option<u32>, optimal_moves: Option<usize>) -> GameStats {
+        GameStats {
+            finished_at: 1700000000,
+            board: "1tl,2dd,2t,1tl,ctl,1tl,s,er,f".to_string(),
+            level,
+            optimal_moves,
+            moves: 4,
+            hints_used: 0,
+            won: true,
+            daily: None,
+        }
+    }
+
+    #[test]
+    fn test_line_roundtrip() {
+        for stats in [stats(Some(3), Some(2)), stats(None, None)] {
+            assert_eq!(GameStats::from_line(&stats.to_line()), Some(stats));
+        }
+        assert_eq!(GameStats::from_line("garbage"), None);
+        let daily = GameStats {
+            daily: Some(20500),
+            ..stats(None, Some(2))
+        };
+        assert_eq!(GameStats::from_line(&daily.to_line()), Some(daily));
+        let old = "1700000000\t1tl,2dd,2t,1tl,ctl,1tl,s,er,f\t-\t2\t4\t0\ttrue";
+        assert_eq!(GameStats::from_line(old), Some(stats(None, Some(2))));
+    }
+
+    #[test]
+    fn test_store() {
+        let path = std::env::temp_dir().join(format!("asteroids-stats-{}.tsv", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let store = StatsStore { path: path.clone() };
+        assert_eq!(store.load().unwrap(), vec![]);
+        store.append(&stats(Some(1), Some(2))).unwrap();
+        store.append(&stats(None, Some(2))).unwrap();
+        assert_eq!(store.load().unwrap().len(), 2);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_efficiency() {
+        let mut game = stats(None, Some(2));
+        assert_eq!(game.efficiency(), 0.5);
+        game.hints_used = 1;
+        assert_eq!(game.efficiency(), 0.25);
+        game.won = false;
+        assert_eq!(game.efficiency(), 0.0);
+    }
+}
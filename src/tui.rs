@@ -0,0 +1,2465 @@
+// text user interface --------------------------------------------------------
+
+use crate::describe::{describe_move, describe_step, explain_hint};
+use crate::history::HistoryTree;
+use crate::par::{par_time, score, DEFAULT_SECONDS_PER_MOVE};
+use crate::screen::Screen;
+use crate::setup::{Setup, SetupStore};
+use asteroids_cli_game::analysis::{BreadthFirstSearch, SearchProgress};
+use asteroids_cli_game::backtracking::{
+    distance_map, CancellationToken, CostModel, ExitDistance, Heuristic, SearchLimits,
+    SearchOutcome, Strategy, Verbosity,
+};
+use asteroids_cli_game::generator::complete_board;
+use asteroids_cli_game::notation::RecordedGame;
+use asteroids_cli_game::rendering::{Frame, Highlight, Renderer};
+use asteroids_cli_game::share::{self, SharedPuzzle};
+use asteroids_cli_game::{
+    format_moves, parse_moves, Board, BoardIndex2d, Direction, GameRecord, InvalidMove, MoveError,
+    MovingTile, Point,
+};
+use crossterm::{
+    cursor,
+    event::{
+        DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers, MouseButton,
+        MouseEventKind,
+    },
+    execute,
+    terminal::{
+        disable_raw_mode, enable_raw_mode, size, Clear, ClearType, EnterAlternateScreen,
+        LeaveAlternateScreen,
+    },
+};
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::io::{stdin, stdout, BufRead};
+use std::rc::Rc;
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Frames per second at which the interactive loops update and redraw.
+const FRAME_RATE: u32 = 30;
+
+/// Time it takes a tile to slide into the free space.
+const ANIMATION_DELAY: Duration = Duration::from_millis(200);
+
+/// How to type keys in line mode, see `keys_for_line`.
+const LINE_MODE_HELP: &str = "Type keys followed by Enter: u/d/l/r for the arrow keys, an empty line for Enter, restart for R and random for r.";
+
+/// Width assumed for line mode, where the width of the terminal is unknown.
+const LINE_MODE_WIDTH: u16 = 80;
+
+/// Time for which the tile that moved last stays marked.
+const HIGHLIGHT_DURATION: Duration = Duration::from_millis(3 * ANIMATION_DELAY.as_millis() as u64);
+
+/// Time after which the search for a hint gives up, to keep the game going.
+const HINT_SEARCH_DURATION: Duration = Duration::from_secs(2);
+
+/// Time after which the check whether a position can still be won gives up,
+/// without a warning.
+const DEAD_END_CHECK_DURATION: Duration = Duration::from_millis(500);
+
+/// Time without changes after which line mode considers the output settled,
+/// i.e. not in the middle of an animation.
+const SETTLED_DELAY: Duration = Duration::from_millis(2 * ANIMATION_DELAY.as_millis() as u64);
+
+// help of the screens, each short enough to be shown in full on a terminal
+// of `Renderer::Full.min_size()` together with a message of two lines
+const CHOOSE_HELP: &str = "Up/Down choose what to play, Enter starts, 'q' quits.";
+const SETUP_HELP: &str =
+    "Left/Right rotate the shape, Up/Down pick another, Enter places it, 'a' fills the rest solvably, 'r' randomly, 'w' saves the setup, 'q' quits.";
+const EDIT_HELP: &str =
+    "'m' mirrors the board, Left/Right rotate it, Enter confirms it, 'q' quits.";
+const PLAY_HELP: &str =
+    "Arrows or 1-9 move a tile into the 'free' space, Shift+arrow slides, 's' types moves, 'h' hint, 'a' demo, 'z' undo, 'y' redo, 't' history, 'e' explore, 'R' restart, 'q' quit.";
+const TYPED_HELP: &str = "Moves of the 'free' space (U, D, L, R), Enter plays them, Esc cancels:";
+const EXPLORE_HELP: &str =
+    "Exploring: Enter plays the explored moves, Esc discards them; the other keys work as usual, 'q' quits.";
+const WON_HELP: &str = "'c' share code, 'z' undo, 't' history, 'R' restart, 'q' quit.";
+const BROWSE_HELP: &str =
+    "Up/Down go back and forth, Left/Right switch lines, PageUp/PageDown jump to where lines split, Enter continues from here, 't' returns.";
+const REPLAY_HELP: &str =
+    "Left/Right step back and forth, Home/End jump to the start or end, 'q' quits.";
+const STEP_HELP: &str =
+    "Space expands the next position, 'c' runs or pauses, Left/Right browse the frontier, Enter expands the one shown, 'j' jumps to a dead end, 'b' shows its path, 'q' quits.";
+
+/// Source of the input events that drive the interactive loops.
+/// Abstracts over the terminal, so that the loops can also be fed scripted input.
+pub trait InputSource {
+    /// Wait at most `timeout` for the next event.
+    fn next_event(&mut self, timeout: Duration) -> crossterm::Result<Option<Event>>;
+}
+
+/// Key strokes and other events of a terminal in raw mode.
+#[cfg(not(feature = "async-input"))]
+struct TerminalInput;
+
+#[cfg(not(feature = "async-input"))]
+impl InputSource for TerminalInput {
+    fn next_event(&mut self, timeout: Duration) -> crossterm::Result<Option<Event>> {
+        use crossterm::event::{poll, read};
+        if poll(timeout)? {
+            Ok(Some(read()?))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+/// Key strokes and other events of a terminal in raw mode, read from
+/// crossterm's async event stream. While waiting for input, a small executor
+/// runs, so that other tasks spawned on it make progress in the meantime.
+#[cfg(feature = "async-input")]
+struct AsyncTerminalInput {
+    events: crossterm::event::EventStream,
+    executor: futures::executor::LocalPool,
+}
+
+#[cfg(feature = "async-input")]
+impl AsyncTerminalInput {
+    fn new() -> AsyncTerminalInput {
+        AsyncTerminalInput {
+            events: crossterm::event::EventStream::new(),
+            executor: futures::executor::LocalPool::new(),
+        }
+    }
+
+    /// Spawner for tasks that run while the interactive loops wait for input.
+    #[allow(dead_code)] // nothing runs next to the input yet
+    fn spawner(&self) -> futures::executor::LocalSpawner {
+        self.executor.spawner()
+    }
+}
+
+#[cfg(feature = "async-input")]
+impl InputSource for AsyncTerminalInput {
+    fn next_event(&mut self, timeout: Duration) -> crossterm::Result<Option<Event>> {
+        use futures::{FutureExt, StreamExt};
+        let mut next_event = self.events.next().fuse();
+        let mut timeout = futures_timer::Delay::new(timeout).fuse();
+        self.executor.run_until(async {
+            futures::select! {
+                event = next_event => event.transpose(),
+                _ = timeout => Ok(None),
+            }
+        })
+    }
+}
+
+/// Line based input, for terminals that do not support raw mode.
+/// Every line typed is translated into key strokes, see `keys_for_line`.
+struct LineInput {
+    /// lines typed by the user, read in the background
+    input_lines: Receiver<String>,
+    pending_keys: VecDeque<KeyCode>,
+    /// whether the reaction to the last input still needs to be printed;
+    /// shared with the `LinePrinter`
+    needs_printing: Rc<Cell<bool>>,
+}
+
+impl LineInput {
+    fn new(needs_printing: Rc<Cell<bool>>) -> LineInput {
+        let (sender, receiver) = channel();
+        thread::spawn(move || {
+            for line in stdin().lock().lines().map_while(Result::ok) {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        LineInput {
+            input_lines: receiver,
+            pending_keys: VecDeque::new(),
+            needs_printing,
+        }
+    }
+}
+
+impl InputSource for LineInput {
+    fn next_event(&mut self, timeout: Duration) -> crossterm::Result<Option<Event>> {
+        if self.pending_keys.is_empty() {
+            if self.needs_printing.get() {
+                // let the game react to the last input before reading more
+                thread::sleep(timeout);
+                return Ok(None);
+            }
+            match self.input_lines.recv_timeout(timeout) {
+                Ok(line) => self.pending_keys.extend(keys_for_line(&line)),
+                Err(RecvTimeoutError::Timeout) => return Ok(None),
+                // end of input: leave whatever the user is doing
+                Err(RecvTimeoutError::Disconnected) => {
+                    self.pending_keys.extend([KeyCode::Esc, KeyCode::Char('q')])
+                }
+            }
+            self.needs_printing.set(true);
+        }
+        Ok(self
+            .pending_keys
+            .pop_front()
+            .map(|code| Event::Key(code.into())))
+    }
+}
+
+/// Translate a line typed in line mode into the key strokes it stands for:
+/// `u`, `d`, `l`, `r` are the arrow keys, an empty line is Enter, and
+/// `restart` and `random` are `R` and `r`, which the arrow key takes otherwise.
+fn keys_for_line(line: &str) -> Vec<KeyCode> {
+    if line.trim().is_empty() {
+        return vec![KeyCode::Enter];
+    }
+    if line.trim().eq_ignore_ascii_case("restart") {
+        return vec![KeyCode::Char('R')];
+    }
+    if line.trim().eq_ignore_ascii_case("random") {
+        return vec![KeyCode::Char('r')];
+    }
+    line.chars()
+        .filter(|character| !character.is_whitespace())
+        .map(|character| match character.to_ascii_lowercase() {
+            'u' => KeyCode::Up,
+            'd' => KeyCode::Down,
+            'l' => KeyCode::Left,
+            'r' => KeyCode::Right,
+            other => KeyCode::Char(other),
+        })
+        .collect()
+}
+
+/// Line based output: frames are printed once they have settled.
+struct LinePrinter {
+    last_frame: Vec<String>,
+    /// printed after the next frame
+    announcements: Vec<String>,
+    last_change: Instant,
+    /// shared with the `LineInput`
+    needs_printing: Rc<Cell<bool>>,
+}
+
+impl LinePrinter {
+    /// Print the frame once it has settled, e.g. after an animation.
+    fn draw(&mut self, frame: &Frame) {
+        let lines = Renderer::Full.render(frame, LINE_MODE_WIDTH);
+        if lines != self.last_frame {
+            self.last_frame = lines;
+            self.last_change = Instant::now();
+        }
+        if self.needs_printing.get() && self.last_change.elapsed() >= SETTLED_DELAY {
+            for line in self.last_frame.iter().chain(&self.announcements) {
+                println!("{}", line);
+            }
+            self.announcements.clear();
+            self.needs_printing.set(false);
+        }
+    }
+}
+
+enum Output {
+    /// raw mode, drawing on the alternate screen
+    Screen(Screen),
+    Lines(LinePrinter),
+    /// keep the last frame instead of showing it
+    #[cfg(test)]
+    Recorded {
+        last_frame: Vec<String>,
+        announcements: Vec<String>,
+    },
+}
+
+/// The terminal that the game is played on.
+/// In raw mode, the terminal is restored when this is dropped.
+pub struct Terminal {
+    next_frame: Instant,
+    input: Box<dyn InputSource>,
+    output: Output,
+    /// time it takes a tile to slide into the free space
+    animation_delay: Duration,
+    mouse_captured: bool,
+    /// last position of the mouse pointer, (column, row)
+    mouse_position: Option<(u16, u16)>,
+    /// the renderer of the last frame and the top left corner of the board's
+    /// border on the terminal, (column, row), to find the tile that is clicked
+    board_origin: Option<(Renderer, u16, u16)>,
+    /// describe every action in a line of text, for screen readers
+    announcing: bool,
+    /// shown in front of the status, see `set_context`
+    context: Option<String>,
+}
+
+impl Terminal {
+    /// Use raw mode if possible, otherwise (or if `simple_input` is set) fall
+    /// back to line mode.
+    pub fn new(simple_input: bool) -> crossterm::Result<Terminal> {
+        // raw mode to get individual key strokes
+        let (input, output): (Box<dyn InputSource>, Output) =
+            if !simple_input && enable_raw_mode().is_ok() {
+                execute!(
+                    stdout(),
+                    EnterAlternateScreen,
+                    cursor::Hide,
+                    Clear(ClearType::All)
+                )?;
+                let (width, height) = size()?;
+                #[cfg(not(feature = "async-input"))]
+                let input = TerminalInput;
+                #[cfg(feature = "async-input")]
+                let input = AsyncTerminalInput::new();
+                (Box::new(input), Output::Screen(Screen::new(width, height)))
+            } else {
+                println!("{}", LINE_MODE_HELP);
+                let needs_printing = Rc::new(Cell::new(true));
+                (
+                    Box::new(LineInput::new(needs_printing.clone())),
+                    Output::Lines(LinePrinter {
+                        last_frame: vec![],
+                        announcements: vec![],
+                        last_change: Instant::now(),
+                        needs_printing,
+                    }),
+                )
+            };
+        Ok(Terminal {
+            next_frame: Instant::now(),
+            input,
+            output,
+            animation_delay: ANIMATION_DELAY,
+            mouse_captured: false,
+            mouse_position: None,
+            board_origin: None,
+            announcing: false,
+            context: None,
+        })
+    }
+
+    /// A terminal that reads its input from `input` and only records what is drawn.
+    /// Moves are not animated.
+    #[cfg(test)]
+    fn headless(input: impl InputSource + 'static) -> Terminal {
+        Terminal {
+            next_frame: Instant::now(),
+            input: Box::new(input),
+            output: Output::Recorded {
+                last_frame: vec![],
+                announcements: vec![],
+            },
+            animation_delay: Duration::ZERO,
+            mouse_captured: false,
+            mouse_position: None,
+            board_origin: None,
+            announcing: false,
+            context: None,
+        }
+    }
+
+    /// Check that the terminal is large enough to draw the game on.
+    pub fn check_size(&self) -> Result<(), String> {
+        match &self.output {
+            Output::Screen(screen) if !fits_on(screen, Renderer::Compact) => {
+                let (min_width, min_height) = Renderer::Compact.min_size();
+                Err(format!(
+                    "The terminal is too small ({}x{}); at least {}x{} characters are needed.",
+                    screen.width(),
+                    screen.height(),
+                    min_width,
+                    min_height
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Report mouse events, to name the shape under the mouse pointer in the
+    /// status line and to select tiles by clicking them. Only in raw mode.
+    pub fn capture_mouse(&mut self) -> crossterm::Result<()> {
+        if let Output::Screen(_) = self.output {
+            execute!(stdout(), EnableMouseCapture)?;
+            self.mouse_captured = true;
+        }
+        Ok(())
+    }
+
+    /// Describe every action in a line of text, for screen readers. Only in
+    /// line mode, where the text follows the frame.
+    pub fn enable_announcements(&mut self) {
+        self.announcing = true;
+    }
+
+    fn announce(&mut self, text: String) {
+        if !self.announcing {
+            return;
+        }
+        match &mut self.output {
+            Output::Screen(_) => {}
+            Output::Lines(printer) => printer.announcements.push(text),
+            #[cfg(test)]
+            Output::Recorded { announcements, .. } => announcements.push(text),
+        }
+    }
+
+    /// Wait for the next input event, but at most until the current frame is over.
+    /// Returns `None` once per frame, so that the caller can update and draw.
+    fn next_event(&mut self) -> crossterm::Result<Option<Event>> {
+        let timeout = self.next_frame.saturating_duration_since(Instant::now());
+        if let Some(event) = self.input.next_event(timeout)? {
+            if let (Event::Resize(width, height), Output::Screen(screen)) =
+                (&event, &mut self.output)
+            {
+                // start over with a blank terminal
+                execute!(stdout(), Clear(ClearType::All))?;
+                *screen = Screen::new(*width, *height);
+            }
+            if let Event::Mouse(mouse) = &event {
+                if mouse.kind == MouseEventKind::Moved {
+                    self.mouse_position = Some((mouse.column, mouse.row));
+                }
+            }
+            return Ok(Some(event));
+        }
+        // do not try to catch up on frames that we missed
+        self.next_frame =
+            (self.next_frame + Duration::from_secs(1) / FRAME_RATE).max(Instant::now());
+        Ok(None)
+    }
+
+    /// The tile drawn at the given position of the terminal in the last frame.
+    fn tile_at(&self, column: u16, row: u16) -> Option<BoardIndex2d> {
+        let (renderer, left, top) = self.board_origin?;
+        renderer.tile_at(column.checked_sub(left)?, row.checked_sub(top)?)
+    }
+
+    /// Show `context` in front of the status of every frame, e.g. the progress
+    /// of a session of several games.
+    pub fn set_context(&mut self, context: Option<String>) {
+        self.context = context;
+    }
+
+    /// Show the frame instead of whatever was shown before.
+    fn draw(&mut self, frame: &Frame) -> crossterm::Result<()> {
+        let with_context;
+        let frame = match &self.context {
+            Some(context) => {
+                with_context = Frame {
+                    status: format!("{} | {}", context, frame.status),
+                    short_status: frame.short_status.clone(),
+                    message: frame.message.clone(),
+                    help: frame.help.clone(),
+                    ..*frame
+                };
+                &with_context
+            }
+            None => frame,
+        };
+        match &mut self.output {
+            Output::Screen(screen) => {
+                screen.clear();
+                if fits_on(screen, Renderer::Compact) {
+                    let renderer = Renderer::for_frame(frame, screen.width(), screen.height());
+                    let mut lines = renderer.render(frame, screen.width());
+                    // center the lines; lines that are too wide start at the left edge
+                    let top = screen.height().saturating_sub(lines.len() as u16) / 2;
+                    let left = screen
+                        .width()
+                        .saturating_sub(lines[0].chars().count() as u16)
+                        / 2;
+                    self.board_origin = Some((renderer, left, top));
+                    // name the shape under the mouse pointer, if there is room for it
+                    if let (Renderer::Full, Some((column, row))) = (renderer, self.mouse_position) {
+                        let tile =
+                            renderer.tile_at(column.wrapping_sub(left), row.wrapping_sub(top));
+                        if let Some(tile) = tile {
+                            let shape = frame.board.shapes[tile.to_index()];
+                            let frame = Frame {
+                                status: format!("{} | {}", frame.status, shape.name()),
+                                short_status: frame.short_status.clone(),
+                                message: frame.message.clone(),
+                                help: frame.help.clone(),
+                                ..*frame
+                            };
+                            lines = renderer.render(&frame, screen.width());
+                        }
+                    }
+                    for (y, line) in lines.iter().enumerate() {
+                        let left = screen.width().saturating_sub(line.chars().count() as u16) / 2;
+                        screen.put_str(left, top + y as u16, line);
+                    }
+                } else {
+                    self.board_origin = None;
+                    screen.put_str(0, 0, "Terminal too small.");
+                }
+                screen.flush(&mut stdout())
+            }
+            Output::Lines(printer) => {
+                printer.draw(frame);
+                Ok(())
+            }
+            #[cfg(test)]
+            Output::Recorded { last_frame, .. } => {
+                *last_frame = Renderer::Full.render(frame, LINE_MODE_WIDTH);
+                self.board_origin = Some((Renderer::Full, 0, 0));
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Whether the screen is large enough for the renderer.
+fn fits_on(screen: &Screen, renderer: Renderer) -> bool {
+    let (min_width, min_height) = renderer.min_size();
+    screen.width() >= min_width && screen.height() >= min_height
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        if let Output::Screen(_) = self.output {
+            // nothing sensible left to do if restoring fails
+            if self.mouse_captured {
+                let _ = execute!(stdout(), DisableMouseCapture);
+            }
+            let _ = execute!(stdout(), cursor::Show, LeaveAlternateScreen);
+            let _ = disable_raw_mode();
+        }
+    }
+}
+
+/// How a move of the 'free' space is played.
+#[derive(Clone, Copy, PartialEq)]
+enum Hop {
+    /// a move on its own
+    Single,
+    /// the first move of a slide, which continues in the same direction until
+    /// it is blocked
+    SlideStart,
+    /// a further move of a slide
+    SlideContinued,
+}
+
+/// A move in progress: The tile slides into the free space, then the board is updated.
+struct Animation {
+    direction: Direction,
+    hop: Hop,
+    moving_tile: MovingTile,
+    new_board: Board,
+    done_at: Instant,
+}
+
+/// Start moving the free space in the given direction iff it is a valid move.
+fn start_move(
+    board: &Board,
+    direction: &Direction,
+    hop: Hop,
+    delay: Duration,
+) -> Result<Animation, MoveError> {
+    let new_board = board.move_free_space(direction)?;
+    // the tile next to the free space moves in the opposite direction, half way for now
+    let tile = board.find_free_space().neighbor(direction).unwrap();
+    let (grid_dx, grid_dy) = direction.opposite().grid_delta();
+    Ok(Animation {
+        direction: *direction,
+        hop,
+        moving_tile: MovingTile {
+            board_index: tile,
+            grid_dx,
+            grid_dy,
+        },
+        new_board,
+        done_at: Instant::now() + delay,
+    })
+}
+
+/// A search for a solution, running in the background so that the game keeps
+/// going and the search can be cancelled, e.g. for a hint.
+struct BackgroundSearch {
+    board: Board,
+    cancel: CancellationToken,
+    outcome: Receiver<SearchOutcome<Vec<Direction>>>,
+}
+
+impl BackgroundSearch {
+    fn start(board: Board, cost_model: CostModel, max_duration: Duration) -> BackgroundSearch {
+        let cancel = CancellationToken::default();
+        let limits = SearchLimits {
+            max_duration: Some(max_duration),
+            cancel: Some(cancel.clone()),
+            ..SearchLimits::default()
+        };
+        let (sender, outcome) = channel();
+        thread::spawn(move || {
+            let (found, _) = Strategy::BreadthFirst.solve_with_cost(
+                &board,
+                cost_model,
+                None,
+                &limits,
+                &mut Verbosity::Quiet,
+            );
+            // the game may be over already
+            let _ = sender.send(found);
+        });
+        BackgroundSearch {
+            board,
+            cancel,
+            outcome,
+        }
+    }
+}
+
+impl BackgroundSearch {
+    /// The outcome, if the search ends within `timeout`.
+    fn wait(&self, timeout: Duration) -> Option<SearchOutcome<Vec<Direction>>> {
+        match self.outcome.recv_timeout(timeout) {
+            Ok(outcome) => Some(outcome),
+            Err(RecvTimeoutError::Timeout) => None,
+            Err(RecvTimeoutError::Disconnected) => Some(SearchOutcome::Cancelled),
+        }
+    }
+}
+
+/// Searches that are no longer needed do not keep running.
+impl Drop for BackgroundSearch {
+    fn drop(&mut self) {
+        self.cancel.cancel();
+    }
+}
+
+/// Which moves the free space can make, e.g. "the free space can move Up or
+/// Left".
+fn possible_moves(board: &Board) -> String {
+    match board.legal_moves().as_slice() {
+        [] => "no move is possible".to_string(),
+        [direction] => format!("the free space can only move {:?}", direction),
+        [first @ .., last] => format!(
+            "the free space can move {} or {:?}",
+            first
+                .iter()
+                .map(|direction| format!("{:?}", direction))
+                .collect::<Vec<_>>()
+                .join(", "),
+            last
+        ),
+    }
+}
+
+/// Format a duration as minutes and seconds.
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// Pause between the moves of the demo, unless configured otherwise.
+pub const DEFAULT_DEMO_DELAY: Duration = Duration::from_millis(500);
+
+/// Number of hints per game, unless configured otherwise.
+pub const DEFAULT_HINTS: u32 = 3;
+
+/// Rules of a game that can be changed on the command line.
+#[derive(Clone, Copy)]
+pub struct Rules {
+    /// number of hints available
+    pub hints: u32,
+    /// a slide (Shift+arrow) counts as one move, instead of one per tile
+    pub slide_counts_once: bool,
+    /// pace of the par time
+    pub seconds_per_move: u32,
+    /// refuse moves to positions from which the exit cannot be reached
+    pub assist: bool,
+    /// pause between the moves of the demo, see 'a'
+    pub demo_delay: Duration,
+    /// what the solutions of hints and the demo take the fewest of
+    pub cost_model: CostModel,
+    /// show the fewest moves to the exit from the current position
+    pub optimal_counter: bool,
+}
+
+impl Default for Rules {
+    fn default() -> Rules {
+        Rules {
+            hints: DEFAULT_HINTS,
+            slide_counts_once: false,
+            seconds_per_move: DEFAULT_SECONDS_PER_MOVE,
+            assist: false,
+            demo_delay: DEFAULT_DEMO_DELAY,
+            cost_model: CostModel::Moves,
+            optimal_counter: false,
+        }
+    }
+}
+
+/// Play the game via text user interface. With the length of the shortest
+/// solution, the par time is shown and won games are scored.
+pub fn play_game_via_tui(
+    terminal: &mut Terminal,
+    board: Board,
+    rules: Rules,
+    optimal_moves: Option<usize>,
+) -> crossterm::Result<GameRecord> {
+    assert!(
+        board.is_valid(),
+        "Unable to play since board setup is invalid."
+    );
+
+    let mut history = HistoryTree::new(board);
+    // while exploring, `history` holds the scratch moves and this the real ones
+    let mut exploring: Option<HistoryTree> = None;
+    // the position shown in the history browser, if it is open
+    let mut browsing: Option<usize> = None;
+    // moves to play one after the other
+    let mut pending_moves: VecDeque<(Direction, Hop)> = VecDeque::new();
+    let mut animation: Option<Animation> = None;
+    // the last move, marked until the given time
+    let mut last_move: Option<(Highlight, Instant)> = None;
+    // the tile of the last hint, marked as long as the board is unchanged
+    let mut hinted: Option<(Board, Highlight)> = None;
+    let mut hint_search: Option<BackgroundSearch> = None;
+    // the search for the solution that the demo plays, and its moves to play
+    let mut demo_search: Option<BackgroundSearch> = None;
+    let mut demo_moves: VecDeque<Direction> = VecDeque::new();
+    let mut next_demo_move = Instant::now();
+    let mut demo_played = false;
+    // whether the position can still be won, checked after every move
+    let mut dead_end_check: Option<BackgroundSearch> = None;
+    let mut checked_board = board;
+    // the sequence of moves that the user is typing, if any
+    let mut typed_moves: Option<String> = None;
+    let mut message = String::new();
+    // the message that was announced last, to announce each one once
+    let mut announced = String::new();
+    let started_at = Instant::now();
+    let mut won_at: Option<Instant> = None;
+    // e.g. a share code or a setup of a board that is already won
+    if board.is_won() {
+        won_at = Some(started_at);
+        message = "The ship is at the exit already; you won without a move.".to_string();
+    }
+    let mut hints_used = 0;
+    let par = optimal_moves.map(|moves| par_time(moves, rules.seconds_per_move));
+    // with assistance, the positions from which the exit can be reached
+    let winnable = rules.assist.then(|| distance_map(&board));
+    // for the optimal counter, the same for all positions reachable from the
+    // start; searched in the background, as that takes a while for hard boards
+    let counter_search = rules.optimal_counter.then(|| {
+        let (sender, distances) = channel();
+        thread::spawn(move || {
+            // the game may be over already
+            let _ = sender.send(distance_map(&board));
+        });
+        distances
+    });
+    let mut counter_distances = None;
+
+    loop {
+        match terminal.next_event()? {
+            Some(Event::Key(_)) if hint_search.is_some() => {
+                hint_search = None;
+                message = "Hint cancelled.".to_string();
+            }
+            Some(Event::Key(_)) if demo_search.is_some() || !demo_moves.is_empty() => {
+                demo_search = None;
+                demo_moves.clear();
+                message = "Demo stopped.".to_string();
+            }
+            Some(Event::Key(event)) => {
+                if let Some(node) = browsing {
+                    let siblings = history.siblings(node);
+                    let position = siblings
+                        .iter()
+                        .position(|&sibling| sibling == node)
+                        .unwrap();
+                    browsing = match event.code {
+                        KeyCode::Up => Some(history.parent(node).unwrap_or(node)),
+                        KeyCode::Down => Some(history.next(node).unwrap_or(node)),
+                        KeyCode::Left => Some(siblings[position.saturating_sub(1)]),
+                        KeyCode::Right => Some(siblings[(position + 1).min(siblings.len() - 1)]),
+                        KeyCode::PageUp => Some(history.previous_branch(node)),
+                        KeyCode::PageDown => Some(history.next_branch(node)),
+                        KeyCode::Enter => {
+                            history.go_to(node);
+                            terminal.announce(describe_step(
+                                "continuing from the history",
+                                history.depth(),
+                                history.board(),
+                            ));
+                            None
+                        }
+                        KeyCode::Esc | KeyCode::Char('t') => None,
+                        _ => Some(node),
+                    };
+                    continue;
+                }
+                if let Some(mut typed) = typed_moves.take() {
+                    match event.code {
+                        KeyCode::Char(character) => {
+                            typed.push(character);
+                            typed_moves = Some(typed);
+                        }
+                        KeyCode::Up | KeyCode::Down | KeyCode::Left | KeyCode::Right => {
+                            typed.push(match event.code {
+                                KeyCode::Up => 'U',
+                                KeyCode::Down => 'D',
+                                KeyCode::Left => 'L',
+                                _ => 'R',
+                            });
+                            typed_moves = Some(typed);
+                        }
+                        KeyCode::Backspace => {
+                            typed.pop();
+                            typed_moves = Some(typed);
+                        }
+                        KeyCode::Enter => {
+                            // the board once the current move is done
+                            let board = animation
+                                .as_ref()
+                                .map_or(*history.board(), |animation| animation.new_board);
+                            // play the sequence only if all of its moves are possible
+                            match parse_moves(&typed).and_then(|moves| {
+                                board.apply_moves(&moves).map(|_| moves).map_err(|error| {
+                                    format!("{}; none of the moves is played.", error)
+                                })
+                            }) {
+                                Ok(moves) => {
+                                    pending_moves = moves
+                                        .into_iter()
+                                        .map(|direction| (direction, Hop::Single))
+                                        .collect()
+                                }
+                                Err(msg) => message = msg,
+                            }
+                        }
+                        KeyCode::Esc => {}
+                        _ => typed_moves = Some(typed),
+                    }
+                    continue;
+                }
+
+                message.clear();
+                let direction = match event.code {
+                    KeyCode::Up => Some(Direction::Up),
+                    KeyCode::Down => Some(Direction::Down),
+                    KeyCode::Left => Some(Direction::Left),
+                    KeyCode::Right => Some(Direction::Right),
+                    KeyCode::Char(digit @ '1'..='9') => {
+                        // tiles are numbered row by row, starting top left
+                        let tile = BoardIndex2d::from_index(digit as usize - '1' as usize);
+                        let free_space = history.board().find_free_space();
+                        free_space.direction_to(&tile)
+                    }
+                    KeyCode::Char('s') => {
+                        typed_moves = Some(String::new());
+                        continue;
+                    }
+                    KeyCode::Char('h') => {
+                        message = if hints_used >= rules.hints {
+                            "No hints left.".to_string()
+                        } else {
+                            // the board once the current move is done
+                            let board = animation
+                                .as_ref()
+                                .map_or(*history.board(), |animation| animation.new_board);
+                            hint_search = Some(BackgroundSearch::start(
+                                board,
+                                rules.cost_model,
+                                HINT_SEARCH_DURATION,
+                            ));
+                            "Searching for a hint; press any key to cancel.".to_string()
+                        };
+                        continue;
+                    }
+                    KeyCode::Char('a') => {
+                        // the board once the current move is done
+                        let board = animation
+                            .as_ref()
+                            .map_or(*history.board(), |animation| animation.new_board);
+                        pending_moves.clear();
+                        demo_search = Some(BackgroundSearch::start(
+                            board,
+                            rules.cost_model,
+                            HINT_SEARCH_DURATION,
+                        ));
+                        message = "Solving for the demo; press any key to cancel.".to_string();
+                        continue;
+                    }
+                    KeyCode::Char('z' | 'y' | 't' | 'e' | 'R') | KeyCode::Enter | KeyCode::Esc
+                        if animation.is_some() =>
+                    {
+                        message = "Wait for the move to finish.".to_string();
+                        continue;
+                    }
+                    KeyCode::Char('z') => {
+                        pending_moves.clear();
+                        last_move = None;
+                        if history.undo() {
+                            terminal.announce(describe_step(
+                                "move undone",
+                                history.depth(),
+                                history.board(),
+                            ));
+                        } else {
+                            message = "Nothing to undo.".to_string();
+                        }
+                        continue;
+                    }
+                    KeyCode::Char('y') => {
+                        pending_moves.clear();
+                        last_move = None;
+                        if history.redo() {
+                            terminal.announce(describe_step(
+                                "move redone",
+                                history.depth(),
+                                history.board(),
+                            ));
+                        } else {
+                            message = "Nothing to redo.".to_string();
+                        }
+                        continue;
+                    }
+                    KeyCode::Char('t') => {
+                        pending_moves.clear();
+                        last_move = None;
+                        browsing = Some(history.current());
+                        continue;
+                    }
+                    KeyCode::Char('R') => {
+                        pending_moves.clear();
+                        last_move = None;
+                        // restarting leaves the exploration, too
+                        if let Some(real) = exploring.take() {
+                            history = real;
+                        }
+                        history.go_to(0);
+                        message = "Back at the start; the moves so far are kept in the history."
+                            .to_string();
+                        continue;
+                    }
+                    KeyCode::Char('e') if exploring.is_none() => {
+                        pending_moves.clear();
+                        last_move = None;
+                        let scratch = HistoryTree::new(*history.board());
+                        exploring = Some(std::mem::replace(&mut history, scratch));
+                        message =
+                            "Exploring: try moves freely, they are not played yet.".to_string();
+                        continue;
+                    }
+                    KeyCode::Enter if exploring.is_some() => {
+                        // replay the explored moves on the real board
+                        let scratch = std::mem::replace(&mut history, exploring.take().unwrap());
+                        pending_moves = scratch
+                            .moves_to(scratch.current())
+                            .into_iter()
+                            .map(|direction| (direction, Hop::Single))
+                            .collect();
+                        last_move = None;
+                        continue;
+                    }
+                    KeyCode::Esc | KeyCode::Char('e') if exploring.is_some() => {
+                        pending_moves.clear();
+                        last_move = None;
+                        history = exploring.take().unwrap();
+                        message = "Exploration discarded.".to_string();
+                        continue;
+                    }
+                    KeyCode::Char('c') if won_at.is_some() => {
+                        message = format!("Share code: {}", share::encode(&board, optimal_moves));
+                        continue;
+                    }
+                    KeyCode::Char('q') => break,
+                    _ => None,
+                };
+
+                if let Some(direction) = direction {
+                    let hop = if event.modifiers.contains(KeyModifiers::SHIFT) {
+                        Hop::SlideStart
+                    } else {
+                        Hop::Single
+                    };
+                    pending_moves.push_back((direction, hop));
+                } else {
+                    message =
+                        "Use the arrow keys to move the 'free' space or select a tile next to it."
+                            .to_string();
+                }
+            }
+            Some(Event::Mouse(mouse))
+                if mouse.kind == MouseEventKind::Down(MouseButton::Left) && browsing.is_none() =>
+            {
+                // clicking a tile next to the 'free' space moves it there
+                if let Some(tile) = terminal.tile_at(mouse.column, mouse.row) {
+                    message.clear();
+                    match history.board().find_free_space().direction_to(&tile) {
+                        Some(direction) => pending_moves.push_back((direction, Hop::Single)),
+                        None => {
+                            message =
+                                "Click a tile next to the 'free' space to move it.".to_string()
+                        }
+                    }
+                }
+            }
+            Some(_) => {}
+            None => {
+                // frame is over: update the game ...
+                if let Some(search) = &hint_search {
+                    // wait for the hint rather than for input, which cancels it
+                    if let Some(outcome) = search.wait(Duration::from_secs(1) / FRAME_RATE) {
+                        let board = search.board;
+                        hint_search = None;
+                        message = match outcome {
+                            SearchOutcome::Found(moves) if !moves.is_empty() => {
+                                let direction = moves[0];
+                                hints_used += 1;
+                                let free_space = board.find_free_space();
+                                let tile = free_space.neighbor(&direction).unwrap();
+                                // the hinted tile takes the place of the free space
+                                hinted = Some((
+                                    board,
+                                    Highlight {
+                                        moved: tile,
+                                        vacated: free_space,
+                                    },
+                                ));
+                                format!(
+                                    "Hint: move the 'free' space {:?}, i.e. tile {} into it, so that {}.",
+                                    direction,
+                                    tile.to_index() + 1,
+                                    explain_hint(&board, &moves).unwrap()
+                                )
+                            }
+                            SearchOutcome::Found(_) => "You already won.".to_string(),
+                            SearchOutcome::Exhausted => {
+                                "There is no solution from here.".to_string()
+                            }
+                            SearchOutcome::Aborted(_) => {
+                                "No hint found in time; try again after a few moves.".to_string()
+                            }
+                            SearchOutcome::Cancelled => "Hint cancelled.".to_string(),
+                        };
+                    }
+                }
+                if let Some(search) = &demo_search {
+                    if let Some(outcome) = search.wait(Duration::from_secs(1) / FRAME_RATE) {
+                        let board = search.board;
+                        demo_search = None;
+                        message = match outcome {
+                            SearchOutcome::Found(moves) if moves.is_empty() => {
+                                "You already won.".to_string()
+                            }
+                            SearchOutcome::Found(moves) => {
+                                let cost = rules.cost_model.cost(&board, &moves);
+                                demo_moves = moves.into();
+                                next_demo_move = Instant::now();
+                                demo_played = true;
+                                format!(
+                                    "Demo: the solution takes {} moves; press any key to stop.",
+                                    cost
+                                )
+                            }
+                            SearchOutcome::Exhausted => {
+                                "There is no solution from here.".to_string()
+                            }
+                            SearchOutcome::Aborted(_) => {
+                                "No solution found in time; try again after a few moves."
+                                    .to_string()
+                            }
+                            SearchOutcome::Cancelled => "Demo stopped.".to_string(),
+                        };
+                    }
+                }
+                if animation.is_none()
+                    && pending_moves.is_empty()
+                    && Instant::now() >= next_demo_move
+                {
+                    if let Some(direction) = demo_moves.pop_front() {
+                        pending_moves.push_back((direction, Hop::Single));
+                        next_demo_move =
+                            Instant::now() + terminal.animation_delay + rules.demo_delay;
+                    }
+                }
+                if let Some(current) = &animation {
+                    if Instant::now() >= current.done_at {
+                        last_move = Highlight::of_move(history.board(), &current.direction)
+                            .map(|highlight| (highlight, current.done_at + HIGHLIGHT_DURATION));
+                        if current.hop == Hop::SlideContinued && rules.slide_counts_once {
+                            history.continue_move(current.new_board);
+                        } else {
+                            history.play(current.new_board);
+                        }
+                        terminal.announce(describe_move(
+                            &current.direction,
+                            history.depth(),
+                            history.board(),
+                        ));
+                        // slides go on as long as possible
+                        let direction = current.direction;
+                        if current.hop != Hop::Single
+                            && history.board().move_free_space(&direction).is_ok()
+                        {
+                            pending_moves.push_front((direction, Hop::SlideContinued));
+                        }
+                        animation = None;
+                        if history.board().is_won() && exploring.is_some() {
+                            message = "This line wins; Enter to play it.".to_string();
+                            pending_moves.clear();
+                        } else if history.board().is_won() && demo_played {
+                            message = format!("Demo done: won in {} moves.", history.depth());
+                            won_at.get_or_insert(Instant::now());
+                            pending_moves.clear();
+                        } else if history.board().is_won() {
+                            message = match hints_used {
+                                0 => "You won!".to_string(),
+                                1 => "You won with 1 hint!".to_string(),
+                                _ => format!("You won with {} hints!", hints_used),
+                            };
+                            let finished_at = *won_at.get_or_insert(Instant::now());
+                            if let (Some(optimal_moves), Some(par)) = (optimal_moves, par) {
+                                let points = score(
+                                    history.depth(),
+                                    optimal_moves,
+                                    hints_used,
+                                    finished_at - started_at,
+                                    par,
+                                );
+                                message = format!("{} Score {}.", message, points);
+                            }
+                            pending_moves.clear();
+                        }
+                    }
+                }
+                if animation.is_none()
+                    && pending_moves.is_empty()
+                    && *history.board() != checked_board
+                {
+                    checked_board = *history.board();
+                    // replacing the check of the previous position cancels it
+                    dead_end_check = (!checked_board.is_won()).then(|| {
+                        // any solution will do
+                        BackgroundSearch::start(
+                            checked_board,
+                            CostModel::Moves,
+                            DEAD_END_CHECK_DURATION,
+                        )
+                    });
+                }
+                if let Some(check) = &dead_end_check {
+                    match check.outcome.try_recv() {
+                        Ok(SearchOutcome::Exhausted) if check.board == *history.board() => {
+                            message = "Careful: the ship cannot reach the exit from here anymore; 'z' to undo.".to_string();
+                            dead_end_check = None;
+                        }
+                        Err(TryRecvError::Empty) => {}
+                        _ => dead_end_check = None,
+                    }
+                }
+                if animation.is_none() {
+                    if let Some((direction, hop)) = pending_moves.pop_front() {
+                        let started =
+                            start_move(history.board(), &direction, hop, terminal.animation_delay);
+                        let refused = started.as_ref().is_ok_and(|animation| {
+                            winnable.as_ref().is_some_and(|winnable| {
+                                !winnable.contains_key(&animation.new_board)
+                            })
+                        });
+                        match started {
+                            Ok(_) if refused => {
+                                pending_moves.clear();
+                                message = "Assist: the ship could not reach the exit after that move, so it is not played.".to_string();
+                            }
+                            Ok(started) => animation = Some(started),
+                            Err(error) => {
+                                let possible = possible_moves(history.board());
+                                message = if pending_moves.is_empty() {
+                                    format!("invalid move: {}; {}.", error, possible)
+                                } else {
+                                    format!(
+                                        "invalid move: {}; {}; skipping the remaining moves.",
+                                        error, possible
+                                    )
+                                };
+                                pending_moves.clear();
+                            }
+                        }
+                    }
+                }
+
+                // ... and draw it
+                if message != announced {
+                    announced = message.clone();
+                    if !message.is_empty() {
+                        terminal.announce(message.clone());
+                    }
+                }
+                let no_move = MovingTile::no_move();
+                let moving_tile = animation
+                    .as_ref()
+                    .map_or(&no_move, |animation| &animation.moving_tile);
+                let elapsed = won_at.unwrap_or_else(Instant::now) - started_at;
+                let help = if browsing.is_some() {
+                    BROWSE_HELP.to_string()
+                } else if let Some(typed) = &typed_moves {
+                    format!("{} {}_", TYPED_HELP, typed)
+                } else if exploring.is_some() {
+                    EXPLORE_HELP.to_string()
+                } else if won_at.is_some() {
+                    WON_HELP.to_string()
+                } else {
+                    PLAY_HELP.to_string()
+                };
+                if let Some(distances) = counter_search
+                    .as_ref()
+                    .and_then(|search| search.try_recv().ok())
+                {
+                    counter_distances = Some(distances);
+                }
+                // while exploring, the moves so far and the explored ones
+                let moves = exploring.as_ref().map_or(0, HistoryTree::depth) + history.depth();
+                let exploring_marker = if exploring.is_some() {
+                    "Exploring | "
+                } else {
+                    ""
+                };
+                let (shown, message) = match browsing {
+                    Some(node) => {
+                        let siblings = history.siblings(node);
+                        let position = siblings
+                            .iter()
+                            .position(|&sibling| sibling == node)
+                            .unwrap();
+                        (
+                            history.board_at(node),
+                            format!(
+                                "Line {} of {} at move {}: {}",
+                                position + 1,
+                                siblings.len(),
+                                history.depth_of(node),
+                                format_moves(&history.moves_to(node))
+                            ),
+                        )
+                    }
+                    None => (history.board(), message.clone()),
+                };
+                // `?` while the distances are searched, `-` without a way out
+                let to_go = counter_distances
+                    .as_ref()
+                    .map_or("?".to_string(), |distances| {
+                        distances
+                            .get(shown)
+                            .map_or("-".to_string(), |distance| distance.to_string())
+                    });
+                // the next move takes over once it starts
+                let highlight = last_move
+                    .filter(|(_, until)| {
+                        animation.is_none() && browsing.is_none() && Instant::now() < *until
+                    })
+                    .map(|(highlight, _)| highlight)
+                    .or(hinted
+                        .filter(|(board, _)| {
+                            animation.is_none() && browsing.is_none() && board == history.board()
+                        })
+                        .map(|(_, highlight)| highlight));
+                terminal.draw(&Frame {
+                    board: shown,
+                    moving_tile,
+                    status: format!(
+                        "{}Move {} | Time {}{} | Hints left {}{}",
+                        exploring_marker,
+                        moves,
+                        format_duration(elapsed),
+                        par.map_or(String::new(), |par| format!(
+                            " | Par {}",
+                            format_duration(par)
+                        )),
+                        rules.hints - hints_used,
+                        if rules.optimal_counter {
+                            format!(" | To go {}", to_go)
+                        } else {
+                            String::new()
+                        }
+                    ),
+                    short_status: format!(
+                        "{}M{} {} H{}{}",
+                        if exploring.is_some() { "EXP " } else { "" },
+                        moves,
+                        format_duration(elapsed),
+                        rules.hints - hints_used,
+                        if rules.optimal_counter {
+                            format!(" G{}", to_go)
+                        } else {
+                            String::new()
+                        }
+                    ),
+                    message,
+                    help,
+                    highlight,
+                    collisions: &[],
+                })?;
+            }
+        }
+    }
+    // moves that were only explored do not count
+    if let Some(real) = exploring {
+        history = real;
+    }
+    Ok(GameRecord {
+        history: history.line(),
+        moves: history.depth(),
+        hints_used,
+        elapsed: won_at.unwrap_or_else(Instant::now) - started_at,
+    })
+}
+
+/// Return the ``modulo`` of ``value`` and make sure that it is always positive.
+fn positive_modulo(value: i32, modulo: i32) -> i32 {
+    ((value % modulo) + modulo) % modulo
+}
+
+/// What to play, as chosen in the start-up menu.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GameChoice {
+    /// a puzzle from a share code, see `--code`
+    Shared(SharedPuzzle),
+    /// generated puzzles, see `quick_play`
+    RandomPuzzle,
+    /// place the shapes of a new board
+    CustomSetup,
+    /// continue placing the shapes of the saved setup
+    ResumeSetup,
+}
+
+/// Let the user choose what to play. The saved setup, if any, is offered to
+/// resume. Returns `None` if the user quits.
+pub fn choose_game_via_tui(
+    terminal: &mut Terminal,
+    saved_setup: Option<&Setup>,
+) -> crossterm::Result<Option<GameChoice>> {
+    let entries = [
+        GameChoice::RandomPuzzle,
+        GameChoice::CustomSetup,
+        GameChoice::ResumeSetup,
+    ];
+    let entry_count = if saved_setup.is_some() { 3 } else { 2 };
+    let mut selected = 0;
+    loop {
+        match terminal.next_event()? {
+            Some(Event::Key(event)) => match event.code {
+                KeyCode::Up => selected = (selected + entry_count - 1) % entry_count,
+                KeyCode::Down => selected = (selected + 1) % entry_count,
+                KeyCode::Enter => return Ok(Some(entries[selected])),
+                KeyCode::Char('q') => return Ok(None),
+                _ => {}
+            },
+            Some(_) => {}
+            None => {
+                let names: Vec<String> = (0..entry_count)
+                    .map(|index| {
+                        let name = match entries[index] {
+                            GameChoice::Shared(_) => "shared puzzle".to_string(),
+                            GameChoice::RandomPuzzle => "random puzzle".to_string(),
+                            GameChoice::CustomSetup => "custom setup".to_string(),
+                            GameChoice::ResumeSetup => "resume setup".to_string(),
+                        };
+                        if index == selected {
+                            format!("[{}]", name)
+                        } else {
+                            name
+                        }
+                    })
+                    .collect();
+                // the board that the entry starts with
+                let board = match (entries[selected], saved_setup) {
+                    (GameChoice::ResumeSetup, Some(setup)) => {
+                        let mut board = Board::empty_board();
+                        board.shapes[..setup.placed.len()].copy_from_slice(&setup.placed);
+                        board
+                    }
+                    _ => Board::empty_board(),
+                };
+                let status = match entries[selected] {
+                    GameChoice::Shared(_) => "A puzzle from a share code".to_string(),
+                    GameChoice::RandomPuzzle => {
+                        "Generated puzzles, harder as you get better".to_string()
+                    }
+                    GameChoice::CustomSetup => "Place the shapes of the game yourself".to_string(),
+                    GameChoice::ResumeSetup => "Continue the setup saved last".to_string(),
+                };
+                terminal.draw(&Frame {
+                    board: &board,
+                    moving_tile: &MovingTile::no_move(),
+                    short_status: names[selected].clone(),
+                    status,
+                    message: names.join(" | "),
+                    help: CHOOSE_HELP.to_string(),
+                    highlight: None,
+                    collisions: &[],
+                })?;
+            }
+        }
+    }
+}
+
+/// Create a board by asking the user to place the shapes, starting from
+/// `setup`. The setup can be saved to `store` to resume it later.
+/// Returns `None` if the user aborts.
+pub fn make_board_via_tui(
+    terminal: &mut Terminal,
+    setup: Setup,
+    store: Option<&SetupStore>,
+) -> crossterm::Result<Option<Board>> {
+    let mut board = Board::empty_board();
+    board.shapes[..setup.placed.len()].copy_from_slice(&setup.placed);
+    let mut shapes_to_place = setup.remaining;
+    assert!(setup.placed.len() + shapes_to_place.len() == 9);
+
+    let mut board_index = setup.placed.len();
+    let mut shape_to_allocate_index = setup.selected;
+    let mut clockwise_rotations = setup.clockwise_rotations;
+    let mut message = String::new();
+    // where the last rejected placement overlapped with other tiles
+    let mut collisions: Vec<Point> = vec![];
+
+    loop {
+        let board_index_2d = BoardIndex2d::from_index(board_index);
+        assert!(!shapes_to_place.is_empty());
+        let mut shape = shapes_to_place[shape_to_allocate_index];
+        assert!((0..4).contains(&clockwise_rotations));
+        for _ in 0..clockwise_rotations {
+            shape = shape.rotate(true);
+        }
+        board = board.set_shape(&board_index_2d, &shape);
+
+        match terminal.next_event()? {
+            Some(Event::Key(event)) => {
+                message.clear();
+                collisions.clear();
+                match event.code {
+                    KeyCode::Down => {
+                        shape_to_allocate_index += 1;
+                        if shape_to_allocate_index >= shapes_to_place.len() {
+                            shape_to_allocate_index = 0;
+                        }
+                    }
+                    KeyCode::Up => {
+                        if shape_to_allocate_index == 0 {
+                            shape_to_allocate_index = shapes_to_place.len() - 1;
+                        } else {
+                            shape_to_allocate_index -= 1;
+                        }
+                    }
+                    KeyCode::Left => {
+                        clockwise_rotations -= 1;
+                        clockwise_rotations = positive_modulo(clockwise_rotations, 4);
+                    }
+                    KeyCode::Right => {
+                        clockwise_rotations += 1;
+                        clockwise_rotations = positive_modulo(clockwise_rotations, 4);
+                    }
+
+                    KeyCode::Enter => {
+                        let overlaps = board.collisions(&MovingTile::no_move());
+                        if !overlaps.is_empty() {
+                            let mut tiles: Vec<BoardIndex2d> = overlaps
+                                .iter()
+                                .map(|(tile, _)| *tile)
+                                .filter(|tile| *tile != board_index_2d)
+                                .collect();
+                            tiles.dedup();
+                            let names: Vec<String> = tiles
+                                .iter()
+                                .map(|tile| {
+                                    format!(
+                                        "tile {} ({})",
+                                        tile.to_index() + 1,
+                                        board.shapes[tile.to_index()].name()
+                                    )
+                                })
+                                .collect();
+                            message = format!(
+                                "Invalid placement; there is a collission with {} at the points marked with !.",
+                                names.join(" and ")
+                            );
+                            collisions = overlaps.into_iter().map(|(_, point)| point).collect();
+                            continue;
+                        }
+                        clockwise_rotations = 0;
+                        shapes_to_place.remove(shape_to_allocate_index);
+                        shape_to_allocate_index = 0;
+                        board_index += 1;
+                        if board_index >= 9 {
+                            return Ok(Some(board));
+                        }
+                    }
+                    KeyCode::Char(key @ ('a' | 'r')) => {
+                        let solvable = key == 'a';
+                        let mut rng = rand::thread_rng();
+                        match complete_board(
+                            &board,
+                            board_index,
+                            &shapes_to_place,
+                            solvable,
+                            &mut rng,
+                        ) {
+                            Some(board) => return Ok(Some(board)),
+                            None if solvable => {
+                                message =
+                                    "There is no solvable arrangement of the remaining shapes."
+                                        .to_string()
+                            }
+                            None => message = "The remaining shapes do not fit.".to_string(),
+                        }
+                    }
+                    KeyCode::Char('w') => {
+                        let setup = Setup {
+                            placed: board.shapes[..board_index].to_vec(),
+                            remaining: shapes_to_place.clone(),
+                            selected: shape_to_allocate_index,
+                            clockwise_rotations,
+                        };
+                        message = match store.map(|store| store.save(&setup)) {
+                            Some(Ok(())) => {
+                                "Setup saved; resume it with --resume-setup.".to_string()
+                            }
+                            Some(Err(err)) => format!("Unable to save the setup: {}", err),
+                            None => "There is no file to save the setup to.".to_string(),
+                        };
+                    }
+                    KeyCode::Char('q') => return Ok(None),
+                    _ => {}
+                }
+            }
+            Some(_) => {}
+            None => {
+                terminal.draw(&Frame {
+                    board: &board,
+                    moving_tile: &MovingTile::no_move(),
+                    status: format!("Placing tile {} of 9", board_index + 1),
+                    short_status: format!("Tile {}/9", board_index + 1),
+                    message: message.clone(),
+                    help: SETUP_HELP.to_string(),
+                    highlight: None,
+                    collisions: &collisions,
+                })?;
+            }
+        }
+    }
+}
+
+/// Let the user create a variant of the board by mirroring or rotating it.
+/// Returns `None` if the user aborts.
+pub fn edit_board_via_tui(
+    terminal: &mut Terminal,
+    mut board: Board,
+) -> crossterm::Result<Option<Board>> {
+    let mut message = String::new();
+    loop {
+        match terminal.next_event()? {
+            Some(Event::Key(event)) => {
+                message.clear();
+                let new_board = match event.code {
+                    KeyCode::Char('m') => board.mirror(),
+                    KeyCode::Left => board.rotate(false),
+                    KeyCode::Right => board.rotate(true),
+                    KeyCode::Enter => return Ok(Some(board)),
+                    KeyCode::Char('q') => return Ok(None),
+                    _ => continue,
+                };
+                if new_board.is_collission_free(&MovingTile::no_move()) {
+                    board = new_board;
+                } else {
+                    message =
+                        "Unable to rotate; the ship cannot rotate and would collide.".to_string();
+                }
+            }
+            Some(_) => {}
+            None => {
+                terminal.draw(&Frame {
+                    board: &board,
+                    moving_tile: &MovingTile::no_move(),
+                    status: "Create a variant of the board".to_string(),
+                    short_status: "m/</>/Enter".to_string(),
+                    message: message.clone(),
+                    help: EDIT_HELP.to_string(),
+                    highlight: None,
+                    collisions: &[],
+                })?;
+            }
+        }
+    }
+}
+
+/// Step through a recorded game, forward and backward, up to its first move
+/// that is not possible, if any.
+pub fn view_replay_via_tui(terminal: &mut Terminal, game: &RecordedGame) -> crossterm::Result<()> {
+    let mut boards = vec![game.board];
+    let mut invalid = None;
+    for (index, direction) in game.moves.iter().enumerate() {
+        match boards[index].move_free_space(direction) {
+            Ok(board) => boards.push(board),
+            Err(error) => {
+                invalid = Some(InvalidMove {
+                    index,
+                    direction: *direction,
+                    error,
+                });
+                break;
+            }
+        }
+    }
+    let last = boards.len() - 1;
+    let mut step: usize = 0;
+    loop {
+        match terminal.next_event()? {
+            Some(Event::Key(event)) => match event.code {
+                KeyCode::Left | KeyCode::Up => step = step.saturating_sub(1),
+                KeyCode::Right | KeyCode::Down => step = (step + 1).min(last),
+                KeyCode::Home => step = 0,
+                KeyCode::End => step = last,
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                _ => {}
+            },
+            Some(_) => {}
+            None => {
+                let message = if boards[step].is_won() {
+                    "The ship reaches the exit.".to_string()
+                } else if step < last {
+                    String::new()
+                } else {
+                    match &invalid {
+                        Some(invalid) => format!("The game ends as {}.", invalid),
+                        None => "The game ends here.".to_string(),
+                    }
+                };
+                let highlight = step.checked_sub(1).and_then(|previous| {
+                    Highlight::of_move(&boards[previous], &game.moves[previous])
+                });
+                let played = match step {
+                    0 => String::new(),
+                    _ => format!(" ({:?})", game.moves[step - 1]),
+                };
+                terminal.draw(&Frame {
+                    board: &boards[step],
+                    moving_tile: &MovingTile::no_move(),
+                    status: format!("Replay | Move {} of {}{}", step, last, played),
+                    short_status: format!("R {}/{}", step, last),
+                    message,
+                    help: REPLAY_HELP.to_string(),
+                    highlight,
+                    collisions: &[],
+                })?;
+            }
+        }
+    }
+}
+
+/// A position of the search that the user picked to look at.
+#[derive(Clone, Copy)]
+enum Picked {
+    /// by its index in the frontier
+    Frontier(usize),
+    /// by its index among the dead ends
+    DeadEnd(usize),
+}
+
+/// Watch a breadth-first search for a solution, one expansion at a time. The
+/// frontier can be browsed and its positions expanded out of turn, and the
+/// dead ends found so far can be looked at.
+pub fn observe_search_via_tui(terminal: &mut Terminal, board: Board) -> crossterm::Result<()> {
+    let mut search = BreadthFirstSearch::new(&board);
+    // the position shown: the one expanded last, unless one was picked
+    let mut current = board;
+    let mut picked: Option<Picked> = None;
+    let mut running = false;
+    let mut show_path = false;
+    let mut message = String::new();
+    let mut outcome: Option<String> = None;
+    loop {
+        // the index in the frontier of the position to expand next, if any
+        let mut to_expand: Option<usize> = None;
+        match terminal.next_event()? {
+            Some(Event::Key(event)) => {
+                message.clear();
+                match event.code {
+                    KeyCode::Char(' ') => {
+                        running = false;
+                        to_expand = Some(0);
+                    }
+                    KeyCode::Enter => match picked {
+                        Some(Picked::Frontier(index)) => to_expand = Some(index),
+                        _ => message = "Pick a position of the frontier first.".to_string(),
+                    },
+                    KeyCode::Left | KeyCode::Right if search.frontier() > 0 => {
+                        running = false;
+                        let step = if event.code == KeyCode::Right { 1 } else { -1 };
+                        let index = match picked {
+                            Some(Picked::Frontier(index)) => index as i32 + step,
+                            _ if step > 0 => 0,
+                            _ => -1,
+                        };
+                        let index = positive_modulo(index, search.frontier() as i32) as usize;
+                        picked = Some(Picked::Frontier(index));
+                        current = search.frontier_position(index).unwrap();
+                    }
+                    KeyCode::Char('j') if search.dead_ends().is_empty() => {
+                        message = "No dead ends so far.".to_string();
+                    }
+                    KeyCode::Char('j') => {
+                        running = false;
+                        let index = match picked {
+                            Some(Picked::DeadEnd(index)) => (index + 1) % search.dead_ends().len(),
+                            _ => 0,
+                        };
+                        picked = Some(Picked::DeadEnd(index));
+                        current = search.dead_ends()[index];
+                    }
+                    KeyCode::Char('c') => running = !running,
+                    KeyCode::Char('b') => show_path = !show_path,
+                    KeyCode::Char('q') => return Ok(()),
+                    _ => {}
+                }
+            }
+            Some(_) => {}
+            None => {
+                let message = if let Some(outcome) = &outcome {
+                    outcome.clone()
+                } else if show_path {
+                    format!(
+                        "Path to this position: {}",
+                        format_moves(&search.path_to(&current))
+                    )
+                } else {
+                    message.clone()
+                };
+                let position = match picked {
+                    Some(Picked::Frontier(index)) => {
+                        format!(" | Frontier position {}/{}", index + 1, search.frontier())
+                    }
+                    Some(Picked::DeadEnd(index)) => {
+                        format!(" | Dead end {}/{}", index + 1, search.dead_ends().len())
+                    }
+                    None => String::new(),
+                };
+                terminal.draw(&Frame {
+                    board: &current,
+                    moving_tile: &MovingTile::no_move(),
+                    status: format!(
+                        "Expanded {} | Frontier {} | Depth {} | Estimate {}{}{}",
+                        search.expanded(),
+                        search.frontier(),
+                        search.path_to(&current).len(),
+                        ExitDistance.estimate(&current),
+                        position,
+                        if running { "" } else { " | Paused" }
+                    ),
+                    short_status: format!("E{} F{}", search.expanded(), search.frontier()),
+                    message,
+                    help: STEP_HELP.to_string(),
+                    highlight: None,
+                    collisions: &[],
+                })?;
+                if running {
+                    to_expand = Some(0);
+                }
+            }
+        }
+        let Some(index) = to_expand.filter(|_| outcome.is_none()) else {
+            continue;
+        };
+        picked = None;
+        match search.expand_frontier(index) {
+            SearchProgress::Expanded(board) => current = board,
+            SearchProgress::Solved(moves) => {
+                outcome = Some(format!(
+                    "Solved in {} moves: {}",
+                    moves.len(),
+                    format_moves(&moves)
+                ));
+                current = board;
+                for direction in &moves {
+                    current = current.move_free_space(direction).unwrap_or(current);
+                }
+            }
+            SearchProgress::Exhausted => outcome = Some("There is no solution.".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use asteroids_cli_game::{sample_board, Shape};
+    use crossterm::event::{KeyEvent, MouseEvent};
+
+    /// Frames to wait after each scripted key, enough to finish any queued moves.
+    const FRAMES_PER_KEY: u32 = 20;
+
+    /// Plays back a fixed sequence of key strokes, one every `FRAMES_PER_KEY` frames.
+    struct ScriptedInput {
+        events: VecDeque<Event>,
+        frames_to_wait: u32,
+    }
+
+    impl ScriptedInput {
+        fn new<K: Into<KeyEvent> + Copy>(keys: &[K]) -> ScriptedInput {
+            ScriptedInput::with_events(keys.iter().map(|&key| Event::Key(key.into())).collect())
+        }
+
+        /// The same with any events, e.g. mouse clicks.
+        fn with_events(events: Vec<Event>) -> ScriptedInput {
+            ScriptedInput {
+                events: events.into(),
+                frames_to_wait: FRAMES_PER_KEY,
+            }
+        }
+    }
+
+    impl InputSource for ScriptedInput {
+        fn next_event(&mut self, _timeout: Duration) -> crossterm::Result<Option<Event>> {
+            if self.frames_to_wait > 0 {
+                self.frames_to_wait -= 1;
+                return Ok(None);
+            }
+            self.frames_to_wait = FRAMES_PER_KEY;
+            let event = self.events.pop_front().expect("the script ended too early");
+            Ok(Some(event))
+        }
+    }
+
+    /// Like `ScriptedInput`, but each frame takes a little real time, for what
+    /// runs in the background meanwhile.
+    struct PacedInput(ScriptedInput);
+
+    impl InputSource for PacedInput {
+        fn next_event(&mut self, timeout: Duration) -> crossterm::Result<Option<Event>> {
+            thread::sleep(Duration::from_millis(5));
+            self.0.next_event(timeout)
+        }
+    }
+
+    fn rules(hints: u32) -> Rules {
+        Rules {
+            hints,
+            ..Rules::default()
+        }
+    }
+
+    fn last_frame(terminal: &Terminal) -> &[String] {
+        match &terminal.output {
+            Output::Recorded { last_frame, .. } => last_frame,
+            _ => panic!("the terminal does not record frames"),
+        }
+    }
+
+    #[test]
+    fn test_choose_game_via_tui() {
+        use KeyCode::*;
+        let choose = |keys: &[KeyCode], saved_setup: Option<&Setup>| {
+            let mut terminal = Terminal::headless(ScriptedInput::new(keys));
+            let choice = choose_game_via_tui(&mut terminal, saved_setup).unwrap();
+            (choice, last_frame(&terminal).to_vec())
+        };
+        let (choice, frame) = choose(&[Down, Char('q')], None);
+        assert_eq!(choice, None);
+        assert!(frame.contains(&"random puzzle | [custom setup]".to_string()));
+        assert_eq!(choose(&[Enter], None).0, Some(GameChoice::RandomPuzzle));
+        assert_eq!(
+            choose(&[Down, Enter], None).0,
+            Some(GameChoice::CustomSetup)
+        );
+        // resuming is only offered with a saved setup
+        assert_eq!(choose(&[Up, Enter], None).0, Some(GameChoice::CustomSetup));
+        let setup = Setup::default();
+        assert_eq!(
+            choose(&[Up, Enter], Some(&setup)).0,
+            Some(GameChoice::ResumeSetup)
+        );
+        assert_eq!(choose(&[Char('q')], None).0, None);
+    }
+
+    #[test]
+    fn test_make_board_via_tui() {
+        use KeyCode::*;
+        let mut terminal = Terminal::headless(ScriptedInput::new(&[
+            Enter, Right, Enter, Enter, Enter, Down, Enter, Down, Enter, Down, Enter, Right, Enter,
+            Enter, Enter,
+        ]));
+        let board = make_board_via_tui(&mut terminal, Setup::default(), None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(board.shapes, sample_board().shapes);
+
+        // the same, resumed after the first tile
+        let mut setup = Setup::default();
+        setup.placed = setup.remaining.drain(..1).collect();
+        setup.clockwise_rotations = 1;
+        let mut terminal = Terminal::headless(ScriptedInput::new(&[
+            Enter, Enter, Enter, Down, Enter, Down, Enter, Down, Enter, Right, Enter, Enter, Enter,
+        ]));
+        let board = make_board_via_tui(&mut terminal, setup, None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(board.shapes, sample_board().shapes);
+
+        let mut terminal = Terminal::headless(ScriptedInput::new(&[Enter, Char('q')]));
+        assert!(make_board_via_tui(&mut terminal, Setup::default(), None)
+            .unwrap()
+            .is_none());
+
+        let mut terminal = Terminal::headless(ScriptedInput::new(&[Enter, Char('a')]));
+        let board = make_board_via_tui(&mut terminal, Setup::default(), None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(board.shapes[0], Shape::OneTL);
+        assert!(asteroids_cli_game::analysis::solve(&board).is_some());
+
+        let mut terminal = Terminal::headless(ScriptedInput::new(&[Char('r')]));
+        let board = make_board_via_tui(&mut terminal, Setup::default(), None)
+            .unwrap()
+            .unwrap();
+        assert!(board.is_valid());
+    }
+
+    #[test]
+    fn test_setup_collisions() {
+        use KeyCode::*;
+        let mut terminal = Terminal::headless(ScriptedInput::new(&[
+            Enter,
+            Enter,
+            Enter,
+            Down,
+            Down,
+            Enter,
+            Down,
+            Enter,
+            Char('q'),
+        ]));
+        assert!(make_board_via_tui(&mut terminal, Setup::default(), None)
+            .unwrap()
+            .is_none());
+        // the large edge overlaps with the diagonal above it
+        let frame = last_frame(&terminal);
+        assert_eq!(frame[3], "│%%%%  !!##      │");
+        assert!(frame[11].contains("collission with tile 2 (two, diagonal up)"));
+    }
+
+    #[test]
+    fn test_edit_board_via_tui() {
+        let mut terminal =
+            Terminal::headless(ScriptedInput::new(&[KeyCode::Char('m'), KeyCode::Enter]));
+        let board = edit_board_via_tui(&mut terminal, sample_board())
+            .unwrap()
+            .unwrap();
+        assert_eq!(board.shapes, sample_board().mirror().shapes);
+    }
+
+    #[test]
+    fn test_view_replay_via_tui() {
+        use KeyCode::*;
+        let game = RecordedGame {
+            board: sample_board(),
+            moves: vec![Direction::Left, Direction::Left],
+        };
+        let mut terminal = Terminal::headless(ScriptedInput::new(&[
+            Right,
+            Right,
+            Right,
+            Left,
+            Right,
+            Char('q'),
+        ]));
+        view_replay_via_tui(&mut terminal, &game).unwrap();
+        let frame = last_frame(&terminal);
+        assert!(frame
+            .iter()
+            .any(|line| line.contains("Replay | Move 2 of 2 (Left)")));
+        assert!(frame.contains(&"The ship reaches the exit.".to_string()));
+
+        // up to the first move that is not possible
+        let game = RecordedGame {
+            board: sample_board(),
+            moves: vec![Direction::Right, Direction::Left],
+        };
+        let mut terminal = Terminal::headless(ScriptedInput::new(&[Right, Char('q')]));
+        view_replay_via_tui(&mut terminal, &game).unwrap();
+        assert!(last_frame(&terminal)
+            .iter()
+            .any(|line| line.starts_with("The game ends as move 1 (Right) is not possible")));
+    }
+
+    #[test]
+    fn test_play_game_via_tui() {
+        use KeyCode::*;
+        // invalid move first, which must not count
+        let mut terminal = Terminal::headless(ScriptedInput::new(&[Down, Left, Left, Char('q')]));
+        let history = play_game_via_tui(&mut terminal, sample_board(), rules(0), None)
+            .unwrap()
+            .history;
+        assert_eq!(history.len(), 3);
+        assert!(history.last().unwrap().is_won());
+        assert!(last_frame(&terminal).contains(&"You won!".to_string()));
+
+        // with par time and score
+        let mut terminal = Terminal::headless(ScriptedInput::new(&[Left, Left, Char('q')]));
+        play_game_via_tui(&mut terminal, sample_board(), rules(0), Some(2)).unwrap();
+        let frame = last_frame(&terminal);
+        assert!(frame.iter().any(|line| line.contains("| Par 0:10 |")));
+        assert!(frame.contains(&"You won! Score 100.".to_string()));
+
+        // the share code of the puzzle, once it is won
+        let mut terminal = Terminal::headless(ScriptedInput::new(&[
+            Char('c'),
+            Left,
+            Left,
+            Char('c'),
+            Char('q'),
+        ]));
+        play_game_via_tui(&mut terminal, sample_board(), rules(0), Some(2)).unwrap();
+        assert!(last_frame(&terminal).contains(&format!(
+            "Share code: {}",
+            share::encode(&sample_board(), Some(2))
+        )));
+
+        // the same moves typed as a sequence
+        let mut terminal = Terminal::headless(ScriptedInput::new(&[
+            Char('s'),
+            Char('l'),
+            Left,
+            Enter,
+            Char('q'),
+        ]));
+        let history = play_game_via_tui(&mut terminal, sample_board(), rules(0), None)
+            .unwrap()
+            .history;
+        assert!(history.last().unwrap().is_won());
+
+        // a sequence with an impossible move is not played at all
+        let mut terminal = Terminal::headless(ScriptedInput::new(&[
+            Char('s'),
+            Char('l'),
+            Char('d'),
+            Enter,
+            Char('q'),
+        ]));
+        let history = play_game_via_tui(&mut terminal, sample_board(), rules(0), None)
+            .unwrap()
+            .history;
+        assert_eq!(history.len(), 1);
+        assert!(last_frame(&terminal)
+            .iter()
+            .any(|line| line
+                .starts_with("move 2 (Down) is not possible: the free space is at the edge")));
+    }
+
+    #[test]
+    fn test_help_fits_min_size() {
+        let (width, height) = Renderer::Full.min_size();
+        let board = sample_board();
+        let typed = format!("{} LLURDDLU_", TYPED_HELP);
+        let helps = [
+            CHOOSE_HELP,
+            SETUP_HELP,
+            EDIT_HELP,
+            PLAY_HELP,
+            &typed,
+            EXPLORE_HELP,
+            WON_HELP,
+            BROWSE_HELP,
+            REPLAY_HELP,
+            STEP_HELP,
+        ];
+        for help in helps {
+            let frame = Frame {
+                board: &board,
+                moving_tile: &MovingTile::no_move(),
+                status: "Move 12 | Time 1:05 | Par 0:10".to_string(),
+                short_status: "M12 1:05".to_string(),
+                // two lines
+                message: "Back at the start; the moves so far are kept in the history.".to_string(),
+                help: help.to_string(),
+                highlight: None,
+                collisions: &[],
+            };
+            assert_eq!(
+                Renderer::for_frame(&frame, width, height),
+                Renderer::Full,
+                "{}",
+                help
+            );
+            let lines = Renderer::Full.render(&frame, width);
+            assert!(lines.len() <= height as usize, "{}", help);
+            assert!(help.ends_with(lines.last().unwrap().as_str()), "{}", help);
+        }
+    }
+
+    #[test]
+    fn test_play_won_board() {
+        let won = sample_board()
+            .apply_moves(&[Direction::Left, Direction::Left])
+            .unwrap();
+        let mut terminal = Terminal::headless(ScriptedInput::new(&[KeyCode::Char('q')]));
+        let record = play_game_via_tui(&mut terminal, won, rules(0), None).unwrap();
+        assert_eq!(record.moves, 0);
+        assert!(last_frame(&terminal)
+            .contains(&"The ship is at the exit already; you won without a move.".to_string()));
+    }
+
+    #[test]
+    fn test_click_tiles() {
+        let click = |column, row| {
+            Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                column,
+                row,
+                modifiers: KeyModifiers::NONE,
+            })
+        };
+        // tile 8 and then tile 7, both left of the 'free' space; the top left
+        // tile is not next to it
+        let mut terminal = Terminal::headless(ScriptedInput::with_events(vec![
+            click(3, 2),
+            click(8, 6),
+            click(4, 7),
+            Event::Key(KeyCode::Char('q').into()),
+        ]));
+        let record = play_game_via_tui(&mut terminal, sample_board(), rules(0), None).unwrap();
+        assert_eq!(record.history.len(), 3);
+        assert!(record.history.last().unwrap().is_won());
+    }
+
+    #[test]
+    fn test_slide() {
+        let slide_left = KeyEvent::new(KeyCode::Left, KeyModifiers::SHIFT);
+        let quit = KeyCode::Char('q').into();
+        let mut terminal = Terminal::headless(ScriptedInput::new(&[slide_left, quit]));
+        let record = play_game_via_tui(&mut terminal, sample_board(), rules(0), None).unwrap();
+        assert!(record.history.last().unwrap().is_won());
+        assert_eq!(record.moves, 2);
+
+        let mut terminal = Terminal::headless(ScriptedInput::new(&[slide_left, quit]));
+        let rules = Rules {
+            slide_counts_once: true,
+            ..rules(0)
+        };
+        let record = play_game_via_tui(&mut terminal, sample_board(), rules, None).unwrap();
+        assert_eq!(record.history.len(), 3);
+        assert_eq!(record.moves, 1);
+    }
+
+    #[test]
+    fn test_explore() {
+        use KeyCode::*;
+        // explored moves are discarded, or played once committed
+        let mut terminal = Terminal::headless(ScriptedInput::new(&[
+            Char('e'),
+            Left,
+            Esc,
+            Char('e'),
+            Left,
+            Left,
+            Char('q'),
+        ]));
+        let record = play_game_via_tui(&mut terminal, sample_board(), rules(0), None).unwrap();
+        assert_eq!(record.moves, 0);
+        let frame = last_frame(&terminal);
+        assert!(frame
+            .iter()
+            .any(|line| line.starts_with("Exploring | Move 2 |")));
+        assert!(frame.contains(&"This line wins; Enter to play it.".to_string()));
+
+        let mut terminal = Terminal::headless(ScriptedInput::new(&[
+            Left,
+            Char('e'),
+            Left,
+            Enter,
+            Char('q'),
+        ]));
+        let record = play_game_via_tui(&mut terminal, sample_board(), rules(0), None).unwrap();
+        assert_eq!(record.moves, 2);
+        assert!(record.history.last().unwrap().is_won());
+    }
+
+    #[test]
+    fn test_hint_budget() {
+        use KeyCode::*;
+        let mut terminal =
+            Terminal::headless(ScriptedInput::new(&[Char('h'), Left, Char('h'), Char('q')]));
+        let record = play_game_via_tui(&mut terminal, sample_board(), rules(1), None).unwrap();
+        assert_eq!(record.hints_used, 1);
+        assert!(last_frame(&terminal).contains(&"No hints left.".to_string()));
+
+        let mut terminal =
+            Terminal::headless(ScriptedInput::new(&[Char('h'), Left, Left, Char('q')]));
+        let record = play_game_via_tui(&mut terminal, sample_board(), rules(3), None).unwrap();
+        assert_eq!(record.hints_used, 1);
+        assert!(last_frame(&terminal).contains(&"You won with 1 hint!".to_string()));
+    }
+
+    #[test]
+    fn test_hint_marks_tile() {
+        use KeyCode::*;
+        let mut terminal = Terminal::headless(ScriptedInput::new(&[Char('h'), Char('q')]));
+        play_game_via_tui(&mut terminal, sample_board(), rules(1), None).unwrap();
+        let frame = last_frame(&terminal);
+        let hint = frame
+            .iter()
+            .position(|line| line.starts_with("Hint:"))
+            .unwrap();
+        assert_eq!(
+            frame[hint..hint + 2].join(" "),
+            "Hint: move the 'free' space Left, i.e. tile 8 into it, so that the large edge \
+             piece moves right and the ship can move right to the exit next turn."
+        );
+        // the tile left of the free space in the bottom row
+        assert_eq!(frame[6], "│VVVVVVVV****..  │");
+        assert_eq!(frame[7], "│  VVVV  ****..  │");
+    }
+
+    #[test]
+    fn test_dead_end_warning() {
+        use Shape::*;
+        // only one tile can move, back and forth
+        let board = Board {
+            shapes: [
+                OneTL,
+                OneBR,
+                Ship,
+                LargeCornerTL,
+                LargeEdgeL,
+                TwoHorR,
+                TwoDiagDown,
+                LargeCornerTR,
+                Free,
+            ],
+        };
+        let key = match board.legal_moves()[0] {
+            Direction::Up => KeyCode::Up,
+            Direction::Down => KeyCode::Down,
+            Direction::Left => KeyCode::Left,
+            Direction::Right => KeyCode::Right,
+        };
+        let mut terminal =
+            Terminal::headless(PacedInput(ScriptedInput::new(&[key, KeyCode::Char('q')])));
+        play_game_via_tui(&mut terminal, board, rules(0), None).unwrap();
+        assert!(last_frame(&terminal).iter().any(|line| line
+            .contains("Careful: the ship cannot reach the exit from here anymore; 'z' to undo.")));
+
+        // with assistance, the move is not even played
+        let mut terminal = Terminal::headless(ScriptedInput::new(&[key, KeyCode::Char('q')]));
+        let assisted = Rules {
+            assist: true,
+            ..rules(0)
+        };
+        let record = play_game_via_tui(&mut terminal, board, assisted, None).unwrap();
+        assert_eq!(record.history, vec![board]);
+        assert!(last_frame(&terminal)
+            .iter()
+            .any(|line| line.contains("Assist: the ship could not reach the exit")));
+        // but every move of a solvable puzzle is
+        let mut terminal =
+            Terminal::headless(ScriptedInput::new(&[KeyCode::Left, KeyCode::Char('q')]));
+        let record = play_game_via_tui(&mut terminal, sample_board(), assisted, None).unwrap();
+        assert_eq!(record.history.len(), 2);
+    }
+
+    #[test]
+    fn test_optimal_counter() {
+        let counted = Rules {
+            optimal_counter: true,
+            ..rules(0)
+        };
+        let mut terminal = Terminal::headless(PacedInput(ScriptedInput::new(&[
+            KeyCode::Left,
+            KeyCode::Right,
+            KeyCode::Char('q'),
+        ])));
+        play_game_via_tui(&mut terminal, sample_board(), counted, None).unwrap();
+        assert!(last_frame(&terminal)
+            .contains(&"Move 2 | Time 0:00 | Hints left 0 | To go 2".to_string()));
+
+        let mut terminal = Terminal::headless(ScriptedInput::new(&[KeyCode::Char('q')]));
+        play_game_via_tui(&mut terminal, sample_board(), rules(0), None).unwrap();
+        assert!(last_frame(&terminal).contains(&"Move 0 | Time 0:00 | Hints left 0".to_string()));
+    }
+
+    #[test]
+    fn test_demo() {
+        let mut terminal = Terminal::headless(ScriptedInput::new(&[
+            KeyCode::Char('a'),
+            KeyCode::Char('q'),
+        ]));
+        let demo = Rules {
+            demo_delay: Duration::ZERO,
+            ..rules(0)
+        };
+        let record = play_game_via_tui(&mut terminal, sample_board(), demo, None).unwrap();
+        assert!(record.history.last().unwrap().is_won());
+        assert!(last_frame(&terminal).contains(&"Demo done: won in 2 moves.".to_string()));
+    }
+
+    #[test]
+    fn test_history_tree_via_tui() {
+        use KeyCode::*;
+        // win, undo and play a different move, then switch back to the winning line
+        let mut terminal = Terminal::headless(ScriptedInput::new(&[
+            Left,
+            Left,
+            Char('z'),
+            Right,
+            Char('t'),
+            Left,
+            Enter,
+            Char('q'),
+        ]));
+        let history = play_game_via_tui(&mut terminal, sample_board(), rules(0), None)
+            .unwrap()
+            .history;
+        assert_eq!(history.len(), 3);
+        assert!(history.last().unwrap().is_won());
+
+        // the same, jumping back to where the lines split
+        let mut terminal = Terminal::headless(ScriptedInput::new(&[
+            Left,
+            Left,
+            Char('z'),
+            Right,
+            Left,
+            Char('t'),
+            PageUp,
+            Left,
+            Enter,
+            Char('q'),
+        ]));
+        let history = play_game_via_tui(&mut terminal, sample_board(), rules(0), None)
+            .unwrap()
+            .history;
+        assert_eq!(history.len(), 3);
+        assert!(history.last().unwrap().is_won());
+
+        let mut terminal = Terminal::headless(ScriptedInput::new(&[
+            Left,
+            Char('z'),
+            Char('y'),
+            Char('y'),
+            Char('q'),
+        ]));
+        let history = play_game_via_tui(&mut terminal, sample_board(), rules(0), None)
+            .unwrap()
+            .history;
+        assert_eq!(history.len(), 2);
+        assert!(last_frame(&terminal).contains(&"Nothing to redo.".to_string()));
+    }
+
+    #[test]
+    fn test_restart() {
+        use KeyCode::*;
+        // restart after a move, then win; redo would lead back into the old line
+        let mut terminal = Terminal::headless(ScriptedInput::new(&[
+            Left,
+            Right,
+            Char('R'),
+            Char('y'),
+            Left,
+            Char('q'),
+        ]));
+        let record = play_game_via_tui(&mut terminal, sample_board(), rules(0), None).unwrap();
+        assert_eq!(record.moves, 2);
+        assert!(record.history.last().unwrap().is_won());
+    }
+
+    #[test]
+    fn test_possible_moves() {
+        let board = sample_board();
+        assert_eq!(possible_moves(&board), "the free space can only move Left");
+        let board = board.move_free_space(&Direction::Left).unwrap();
+        assert_eq!(
+            possible_moves(&board),
+            "the free space can move Left or Right"
+        );
+        let board =
+            asteroids_cli_game::notation::parse_board("1tl,1tl,1tl,1tl,f,1tl,1br,s,1br").unwrap();
+        assert_eq!(
+            possible_moves(&board),
+            "the free space can move Up, Left or Right"
+        );
+    }
+
+    #[test]
+    fn test_announcements() {
+        use KeyCode::*;
+        let mut terminal = Terminal::headless(ScriptedInput::new(&[
+            Left,
+            Char('z'),
+            Up,
+            Left,
+            Left,
+            Char('q'),
+        ]));
+        terminal.enable_announcements();
+        play_game_via_tui(&mut terminal, sample_board(), rules(0), None).unwrap();
+        let Output::Recorded { announcements, .. } = &terminal.output else {
+            unreachable!()
+        };
+        assert_eq!(
+            announcements,
+            &[
+                "free space moved left; move 1; ship is one column from the exit",
+                "move undone; move 0; ship is one column from the exit",
+                "invalid move: tile 8 is in the way; the free space can only move Left.",
+                "free space moved left; move 1; ship is one column from the exit",
+                "free space moved left; move 2; ship is at the exit",
+                "You won!",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_observe_search_via_tui() {
+        use KeyCode::*;
+        let mut terminal = Terminal::headless(ScriptedInput::new(&[
+            Char(' '),
+            Char('b'),
+            Char(' '),
+            Char('c'),
+            Char('q'),
+        ]));
+        observe_search_via_tui(&mut terminal, sample_board()).unwrap();
+        assert!(last_frame(&terminal).contains(&"Solved in 2 moves: LL".to_string()));
+    }
+
+    #[test]
+    fn test_explore_search_via_tui() {
+        use KeyCode::*;
+        let mut terminal =
+            Terminal::headless(ScriptedInput::new(&[Char(' '), Char('j'), Left, Char('q')]));
+        observe_search_via_tui(&mut terminal, sample_board()).unwrap();
+        let frame = last_frame(&terminal);
+        assert!(frame.iter().any(|line| line
+            == "Expanded 1 | Frontier 1 | Depth 1 | Estimate 1 | Frontier position 1/1 | Paused"));
+        let mut terminal =
+            Terminal::headless(ScriptedInput::new(&[Char(' '), Right, Enter, Char('q')]));
+        observe_search_via_tui(&mut terminal, sample_board()).unwrap();
+        assert!(last_frame(&terminal)
+            .iter()
+            .any(|line| line.starts_with("Expanded 2 | ")));
+
+        // run the search for a while, then look at a dead end
+        let board =
+            asteroids_cli_game::notation::parse_board("1tl,s,1tr,1bl,f,1br,2t,2b,2t").unwrap();
+        let mut keys = vec![Char('c')];
+        keys.extend([Null; 20]);
+        keys.extend([Char('c'), Char('j'), Char('q')]);
+        let mut terminal = Terminal::headless(ScriptedInput::new(&keys));
+        observe_search_via_tui(&mut terminal, board).unwrap();
+        assert!(last_frame(&terminal)
+            .iter()
+            .any(|line| line.contains(" | Dead end 1/")));
+    }
+
+    #[test]
+    fn test_keys_for_line() {
+        assert_eq!(keys_for_line(""), vec![KeyCode::Enter]);
+        assert_eq!(keys_for_line("  "), vec![KeyCode::Enter]);
+        assert_eq!(
+            keys_for_line("u D q"),
+            vec![KeyCode::Up, KeyCode::Down, KeyCode::Char('q')]
+        );
+        assert_eq!(
+            keys_for_line("lr5"),
+            vec![KeyCode::Left, KeyCode::Right, KeyCode::Char('5')]
+        );
+        assert_eq!(keys_for_line(" restart"), vec![KeyCode::Char('R')]);
+        assert_eq!(keys_for_line("Random"), vec![KeyCode::Char('r')]);
+    }
+}
@@ -0,0 +1,320 @@
+// visited sets ---------------------------------------------------------------
+//
+// The positions that an analysis has seen. Enumerating all positions of some
+// shapes can see more of them than fit into memory, so instead of a `HashSet`
+// they can be kept in a file: a hash table of `canonical_key`s, eight bytes
+// per slot, with at least twice as many slots as positions in it. The
+// positions waiting to be expanded are kept in a file next to it, too.
+
+use crate::{Board, Shape};
+use std::collections::HashSet;
+use std::fs::{self, File, OpenOptions};
+use std::hash::BuildHasher;
+use std::io::{self, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+/// Slots of a new file.
+const INITIAL_SLOTS: u64 = 1 << 16;
+
+/// Slots read at once while probing, as most runs of taken slots are short.
+const PROBE_SLOTS: usize = 64;
+
+/// Boards of a `DiskStack` that are moved to or from its file at once.
+const STACK_CHUNK: usize = 1 << 16;
+
+pub trait VisitedSet {
+    /// Add the board, `false` if it was in the set already.
+    fn insert(&mut self, board: Board) -> io::Result<bool>;
+    fn len(&self) -> usize;
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// An empty stack for the boards seen but not expanded yet, kept where
+    /// the set is kept.
+    fn frontier(&self) -> io::Result<Box<dyn Frontier>> {
+        Ok(Box::new(Vec::new()))
+    }
+}
+
+impl<S: BuildHasher> VisitedSet for HashSet<Board, S> {
+    fn insert(&mut self, board: Board) -> io::Result<bool> {
+        Ok(HashSet::insert(self, board))
+    }
+    fn len(&self) -> usize {
+        HashSet::len(self)
+    }
+}
+
+/// Boards waiting to be expanded, last in first out.
+pub trait Frontier {
+    fn push(&mut self, board: Board) -> io::Result<()>;
+    fn pop(&mut self) -> io::Result<Option<Board>>;
+}
+
+impl Frontier for Vec<Board> {
+    fn push(&mut self, board: Board) -> io::Result<()> {
+        Vec::push(self, board);
+        Ok(())
+    }
+    fn pop(&mut self) -> io::Result<Option<Board>> {
+        Ok(Vec::pop(self))
+    }
+}
+
+/// The error of a file that an analysis cannot go on without.
+fn unusable(path: &Path, err: io::Error) -> io::Error {
+    io::Error::new(
+        err.kind(),
+        format!("unable to use {}: {}", path.display(), err),
+    )
+}
+
+/// A visited set in a file, which is removed again when the set is dropped.
+/// Reading and writing it is slow compared to a `HashSet`.
+pub struct DiskVisitedSet {
+    path: PathBuf,
+    file: File,
+    /// each the key of a board plus one, 0 for an empty slot
+    slots: u64,
+    len: usize,
+}
+
+impl DiskVisitedSet {
+    /// An empty set in the file at `path`, which is overwritten.
+    pub fn create(path: &Path) -> io::Result<DiskVisitedSet> {
+        DiskVisitedSet::with_slots(path, INITIAL_SLOTS)
+    }
+
+    fn with_slots(path: &Path, slots: u64) -> io::Result<DiskVisitedSet> {
+        Ok(DiskVisitedSet {
+            path: path.to_path_buf(),
+            file: DiskVisitedSet::empty_file(path, slots)?,
+            slots,
+            len: 0,
+        })
+    }
+
+    fn empty_file(path: &Path, slots: u64) -> io::Result<File> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        file.set_len(slots * 8)?;
+        Ok(file)
+    }
+
+    /// Add the entry, which is a key plus one, with linear probing.
+    fn insert_entry(&mut self, entry: u64) -> io::Result<bool> {
+        // Fibonacci hashing, as keys of similar boards share most bits
+        let mut slot = entry.wrapping_mul(0x9e37_79b9_7f4a_7c15) % self.slots;
+        let mut block = [0; PROBE_SLOTS * 8];
+        'probe: loop {
+            // the slots from `slot` on, up to the end of the file
+            let count = (self.slots - slot).min(PROBE_SLOTS as u64) as usize;
+            let bytes = &mut block[..count * 8];
+            self.file.seek(SeekFrom::Start(slot * 8))?;
+            self.file.read_exact(bytes)?;
+            for found in bytes.chunks_exact(8) {
+                match u64::from_le_bytes(found.try_into().unwrap()) {
+                    0 => break 'probe,
+                    found if found == entry => return Ok(false),
+                    _ => slot = (slot + 1) % self.slots,
+                }
+            }
+        }
+        self.file.seek(SeekFrom::Start(slot * 8))?;
+        self.file.write_all(&entry.to_le_bytes())?;
+        self.len += 1;
+        Ok(true)
+    }
+
+    /// Move the entries to a file with twice the slots.
+    fn grow(&mut self) -> io::Result<()> {
+        let mut grown_path = self.path.clone().into_os_string();
+        grown_path.push(".grow");
+        let grown_path = PathBuf::from(grown_path);
+        let old_slots = self.slots;
+        let old_file = std::mem::replace(
+            &mut self.file,
+            DiskVisitedSet::empty_file(&grown_path, old_slots * 2)?,
+        );
+        self.slots = old_slots * 2;
+        self.len = 0;
+        let mut reader = BufReader::new(old_file);
+        reader.seek(SeekFrom::Start(0))?;
+        let mut bytes = [0; 8];
+        for _ in 0..old_slots {
+            reader.read_exact(&mut bytes)?;
+            let entry = u64::from_le_bytes(bytes);
+            if entry != 0 {
+                self.insert_entry(entry)?;
+            }
+        }
+        fs::rename(&grown_path, &self.path)
+    }
+}
+
+impl VisitedSet for DiskVisitedSet {
+    fn insert(&mut self, board: Board) -> io::Result<bool> {
+        if (self.len as u64 + 1) * 2 > self.slots {
+            self.grow().map_err(|err| unusable(&self.path, err))?;
+        }
+        self.insert_entry(board.canonical_key() + 1)
+            .map_err(|err| unusable(&self.path, err))
+    }
+    fn len(&self) -> usize {
+        self.len
+    }
+    /// A `DiskStack` in a file next to the set.
+    fn frontier(&self) -> io::Result<Box<dyn Frontier>> {
+        let mut path = self.path.clone().into_os_string();
+        path.push(".frontier");
+        Ok(Box::new(DiskStack::create(Path::new(&path), STACK_CHUNK)?))
+    }
+}
+
+impl Drop for DiskVisitedSet {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// A stack of boards in a file, which is removed again when the stack is
+/// dropped. The boards on top are kept in memory, up to two chunks of them;
+/// the others are moved to the file a chunk at a time, nine bytes each.
+pub struct DiskStack {
+    path: PathBuf,
+    file: File,
+    /// boards moved to or from the file at once
+    chunk: usize,
+    /// boards in the file, below those in memory
+    stored: u64,
+    top: Vec<Board>,
+}
+
+impl DiskStack {
+    /// An empty stack in the file at `path`, which is overwritten.
+    pub fn create(path: &Path, chunk: usize) -> io::Result<DiskStack> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        Ok(DiskStack {
+            path: path.to_path_buf(),
+            file,
+            chunk,
+            stored: 0,
+            top: vec![],
+        })
+    }
+
+    /// Move the lowest chunk of the boards in memory to the file.
+    fn store_chunk(&mut self) -> io::Result<()> {
+        let bytes: Vec<u8> = self.top[..self.chunk]
+            .iter()
+            .flat_map(|board| board.shapes.map(|shape| shape as u8))
+            .collect();
+        self.file.seek(SeekFrom::Start(self.stored * 9))?;
+        self.file.write_all(&bytes)?;
+        self.stored += self.chunk as u64;
+        self.top.drain(..self.chunk);
+        Ok(())
+    }
+
+    /// Move the highest chunk of the boards in the file to memory, which has
+    /// none.
+    fn load_chunk(&mut self) -> io::Result<()> {
+        let count = self.stored.min(self.chunk as u64);
+        self.stored -= count;
+        let mut bytes = vec![0; count as usize * 9];
+        self.file.seek(SeekFrom::Start(self.stored * 9))?;
+        self.file.read_exact(&mut bytes)?;
+        for cells in bytes.chunks_exact(9) {
+            let mut shapes = [Shape::Free; 9];
+            for (shape, &number) in shapes.iter_mut().zip(cells) {
+                *shape = *Shape::all()
+                    .get(number as usize)
+                    .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "not a shape"))?;
+            }
+            self.top.push(Board { shapes });
+        }
+        Ok(())
+    }
+}
+
+impl Frontier for DiskStack {
+    fn push(&mut self, board: Board) -> io::Result<()> {
+        if self.top.len() == 2 * self.chunk {
+            self.store_chunk()
+                .map_err(|err| unusable(&self.path, err))?;
+        }
+        self.top.push(board);
+        Ok(())
+    }
+    fn pop(&mut self) -> io::Result<Option<Board>> {
+        if self.top.is_empty() && self.stored > 0 {
+            self.load_chunk().map_err(|err| unusable(&self.path, err))?;
+        }
+        Ok(self.top.pop())
+    }
+}
+
+impl Drop for DiskStack {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::count_reachable;
+    use crate::backtracking::distance_map;
+    use crate::sample_board;
+
+    #[test]
+    fn test_disk_visited_set() {
+        let path =
+            std::env::temp_dir().join(format!("asteroids-visited-{}.bin", std::process::id()));
+        // fewer slots than positions, so that the file grows
+        let mut on_disk = DiskVisitedSet::with_slots(&path, 1024).unwrap();
+        let board = sample_board();
+        assert_eq!(
+            count_reachable(&board, &mut on_disk).unwrap(),
+            count_reachable(&board, &mut HashSet::new()).unwrap()
+        );
+        assert_eq!(on_disk.len(), 6159);
+        assert_eq!(on_disk.slots, 16384);
+        assert!(!on_disk.insert(board).unwrap());
+        drop(on_disk);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_disk_stack() {
+        let path = std::env::temp_dir().join(format!("asteroids-stack-{}.bin", std::process::id()));
+        let mut stack = DiskStack::create(&path, 4).unwrap();
+        let boards: Vec<Board> = distance_map(&sample_board()).into_keys().take(30).collect();
+        for board in &boards[..20] {
+            stack.push(*board).unwrap();
+        }
+        assert_eq!((stack.top.len(), stack.stored), (8, 12));
+        for board in boards[..20].iter().rev() {
+            assert_eq!(stack.pop().unwrap(), Some(*board));
+        }
+        assert_eq!(stack.pop().unwrap(), None);
+        // the file is used again from the bottom
+        for board in &boards[20..] {
+            stack.push(*board).unwrap();
+        }
+        for board in boards[20..].iter().rev() {
+            assert_eq!(stack.pop().unwrap(), Some(*board));
+        }
+        drop(stack);
+        assert!(!path.exists());
+    }
+}
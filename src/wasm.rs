@@ -0,0 +1,83 @@
+// WebAssembly API ------------------------------------------------------------
+//
+// The board, its moves and the solver for JavaScript, with boards passed in
+// and out as their notation, e.g.
+//
+//     const board = new_board("1tl,2dd,2t,1tl,ctl,1tl,s,er,f");
+//     const next = apply_move(board, "left");
+//     is_won(next);  // false
+//     solve(next);   // "L"
+//
+// Errors are thrown as JavaScript `Error`s with the message of the engine.
+
+use crate::analysis;
+use crate::engine::{parse_direction, parse_position};
+use crate::format_moves;
+use crate::notation::board_to_notation;
+use wasm_bindgen::prelude::*;
+
+/// The notation of a board given in notation or as a share code, which must
+/// be a valid board.
+#[wasm_bindgen]
+pub fn new_board(text: &str) -> Result<String, JsError> {
+    checked_board(text).map_err(|msg| JsError::new(&msg))
+}
+
+/// The board after moving its free space, e.g. `left` or `l`.
+#[wasm_bindgen]
+pub fn apply_move(board: &str, direction: &str) -> Result<String, JsError> {
+    moved_board(board, direction).map_err(|msg| JsError::new(&msg))
+}
+
+#[wasm_bindgen]
+pub fn is_won(board: &str) -> Result<bool, JsError> {
+    parse_position(board)
+        .map(|board| board.is_won())
+        .map_err(|msg| JsError::new(&msg))
+}
+
+/// A shortest solution as moves like `LLU`, `undefined` if there is none.
+#[wasm_bindgen]
+pub fn solve(board: &str) -> Result<Option<String>, JsError> {
+    solution(board).map_err(|msg| JsError::new(&msg))
+}
+
+// the same with plain errors, which work outside the browser as well
+
+fn checked_board(text: &str) -> Result<String, String> {
+    parse_position(text).map(|board| board_to_notation(&board))
+}
+
+fn moved_board(board: &str, direction: &str) -> Result<String, String> {
+    let board = parse_position(board)?;
+    let direction = parse_direction(direction)?;
+    board
+        .move_free_space(&direction)
+        .map(|board| board_to_notation(&board))
+        .map_err(|error| error.to_string())
+}
+
+fn solution(board: &str) -> Result<Option<String>, String> {
+    let board = parse_position(board)?;
+    Ok(analysis::solve(&board).map(|moves| format_moves(&moves)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api() {
+        let board = checked_board("1tl,2dd,2t,1tl,ctl,1tl,s,er,f").unwrap();
+        assert_eq!(solution(&board), Ok(Some("LL".to_string())));
+        let next = moved_board(&board, "left").unwrap();
+        assert_eq!(next, "1tl,2dd,2t,1tl,ctl,1tl,s,f,er");
+        assert_eq!(solution(&next), Ok(Some("L".to_string())));
+        assert_eq!(
+            moved_board(&board, "down"),
+            Err("the free space is at the edge of the board".to_string())
+        );
+        assert!(moved_board(&board, "sideways").is_err());
+        assert!(checked_board("1tl,2dd").is_err());
+    }
+}